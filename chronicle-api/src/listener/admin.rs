@@ -0,0 +1,411 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    auth::{
+        AdminAccess,
+        Authorized,
+    },
+    *,
+};
+use chronicle_broker::{
+    control::{
+        self,
+        RelayError,
+    },
+    BrokerSocketMsg,
+    BrokerTopology,
+    ChronicleBrokerThrough,
+    ImportType,
+    RequesterTopology,
+};
+use chronicle_common::{
+    get_config_file_async,
+    get_history_mut_async,
+};
+use futures::{
+    SinkExt,
+    StreamExt,
+};
+use rocket::{
+    http::ContentType,
+    response::{
+        Content,
+        Responder,
+    },
+    Request,
+    State,
+};
+use rocket_contrib::json::Json;
+use serde_json::Value;
+use std::{
+    net::SocketAddr,
+    ops::Range,
+    path::PathBuf,
+    time::Duration,
+};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::Message,
+};
+use url::Url;
+
+/// The kind of a feed source
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub(crate) enum FeedSourceKind {
+    /// A MQTT broker, which feeds all the topics
+    Mqtt,
+    /// A websocket event stream, which feeds all the topics
+    EventStream,
+}
+
+/// A feed source to add or remove
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct FeedRequest {
+    kind: FeedSourceKind,
+    url: Url,
+}
+
+/// An api endpoint of the requesters to add or remove
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct EndpointRequest {
+    url: Url,
+}
+
+/// An import of LogFiles, from either a local file or dir `path`, or a remote `url`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ImportRequest {
+    path: Option<PathBuf>,
+    url: Option<Url>,
+    #[serde(default)]
+    resume: bool,
+    import_range: Option<Range<u32>>,
+    #[serde(default = "default_import_type")]
+    import_type: ImportType,
+}
+
+fn default_import_type() -> ImportType {
+    ImportType::All
+}
+
+/// A scylla node to add or remove
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct NodeRequest {
+    address: SocketAddr,
+}
+
+/// A rebuild of the scylla ring
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct RingRequest {
+    uniform_rf: u8,
+}
+
+/// The outcome of an admin command
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) enum AdminStatus {
+    /// The command got applied
+    Applied,
+    /// The command got rejected
+    Rejected,
+}
+
+/// The response of the admin routes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AdminResponse {
+    status: AdminStatus,
+    /// The commands sent to the broker or scylla
+    commands: Vec<Value>,
+    /// The reason of the rejection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl AdminResponse {
+    fn new(status: AdminStatus, commands: Vec<Value>) -> Self {
+        Self {
+            status,
+            commands,
+            error: None,
+        }
+    }
+
+    fn rejected(commands: Vec<Value>, error: String) -> Self {
+        Self {
+            status: AdminStatus::Rejected,
+            commands,
+            error: Some(error),
+        }
+    }
+}
+
+impl<'r> Responder<'r, 'static> for AdminResponse {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let status = match self.status {
+            AdminStatus::Applied => Status::Ok,
+            AdminStatus::Rejected => Status::UnprocessableEntity,
+        };
+        let string = serde_json::to_string(&SuccessBody::from(self)).map_err(|e| {
+            error!("JSON failed to serialize: {:?}", e);
+            Status::InternalServerError
+        })?;
+        let mut response = Content(ContentType::JSON, string).respond_to(req)?;
+        response.set_status(status);
+        Ok(response)
+    }
+}
+
+/// Matches the reply to a command among the messages sent by an app websocket
+type ReplyMatcher = fn(&Value, &Value) -> Option<Result<(), String>>;
+
+/// Send the commands one by one to the websocket of the app, waiting for the reply to each of them, and stop at
+/// the first rejection
+async fn relay(
+    app: &'static str,
+    address: SocketAddr,
+    commands: Vec<Value>,
+    reply: ReplyMatcher,
+    timeout: Duration,
+) -> Result<AdminResponse, ListenerError> {
+    let url = Url::parse(&format!("ws://{}/", address)).map_err(|e| ListenerError::Other(e.into()))?;
+    let (mut stream, _) = connect_async(url).await.map_err(|e| {
+        error!("Unable to connect to the {} websocket: {}", app, e);
+        ListenerError::Unavailable(app)
+    })?;
+    let outcome = tokio::time::timeout(timeout, async {
+        for command in commands.iter() {
+            stream.send(Message::text(command.to_string())).await.map_err(|e| {
+                error!("Unable to send the command to the {} websocket: {}", app, e);
+                ListenerError::Unavailable(app)
+            })?;
+            let outcome = loop {
+                match stream.next().await {
+                    Some(Ok(Message::Text(text))) => {
+                        if let Some(outcome) = serde_json::from_str::<Value>(&text)
+                            .ok()
+                            .and_then(|message| reply(command, &message))
+                        {
+                            break outcome;
+                        }
+                    }
+                    Some(Ok(_)) => (),
+                    _ => return Err(ListenerError::Unavailable(app)),
+                }
+            };
+            if let Err(error) = outcome {
+                return Ok(Err(error));
+            }
+        }
+        Ok(Ok(()))
+    })
+    .await
+    .map_err(|_| ListenerError::Timeout)?;
+    stream.close(None).await.ok();
+    match outcome? {
+        Ok(()) => Ok(AdminResponse::new(AdminStatus::Applied, commands)),
+        Err(error) => Ok(AdminResponse::rejected(commands, error)),
+    }
+}
+
+/// Send the topology changes to the broker, as requests acknowledged by the broker
+async fn relay_to_broker(
+    topologies: Vec<BrokerTopology>,
+    api_config: &ApiConfig,
+) -> Result<AdminResponse, ListenerError> {
+    let requests = topologies
+        .into_iter()
+        .map(|topology| ChronicleBrokerThrough::Topology(topology).into_request())
+        .collect::<Vec<_>>();
+    let commands = requests
+        .iter()
+        .map(|request| serde_json::to_value(BrokerSocketMsg::ChronicleBroker(request)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ListenerError::Other(e.into()))?;
+    let address = get_config_async().await.broker_config.websocket_address;
+    let timeout = Duration::from_secs(api_config.request_timeout_secs);
+    let outcome = control::relay_to_broker(address, &requests, timeout)
+        .await
+        .map_err(|e| match e {
+            RelayError::Unavailable(reason) => {
+                error!("Unable to relay the commands to the broker websocket: {}", reason);
+                ListenerError::Unavailable("broker")
+            }
+            RelayError::Timeout(_) => ListenerError::Timeout,
+        })?;
+    match outcome {
+        Ok(()) => Ok(AdminResponse::new(AdminStatus::Applied, commands)),
+        Err(error) => Ok(AdminResponse::rejected(commands, error)),
+    }
+}
+
+/// Send the topology change to scylla, and update the configured nodes once it got applied
+async fn relay_to_scylla(topology: Topology, api_config: &ApiConfig) -> Result<AdminResponse, ListenerError> {
+    let node_change = match topology {
+        Topology::AddNode(address) => Some((address, true)),
+        Topology::RemoveNode(address) => Some((address, false)),
+        Topology::BuildRing(_) => None,
+    };
+    let command = serde_json::to_value(scylla_rs::prelude::SocketMsg::Scylla(ScyllaThrough::Topology(topology)))
+        .map_err(|e| ListenerError::Other(e.into()))?;
+    let config = get_config_async().await;
+    let response = relay(
+        "scylla",
+        config.storage_config.listen_address,
+        vec![command],
+        scylla_reply,
+        Duration::from_secs(api_config.request_timeout_secs),
+    )
+    .await?;
+    if let (AdminStatus::Applied, Some((address, add))) = (response.status, node_change) {
+        let config = get_config_file_async().await;
+        let mut new_config = config.clone();
+        if add {
+            new_config.storage_config.nodes.insert(address);
+        } else {
+            new_config.storage_config.nodes.remove(&address);
+        }
+        if new_config != config {
+            get_history_mut_async().await.update(new_config.into());
+        }
+    }
+    Ok(response)
+}
+
+/// Scylla replies to its topology changes with either Ok or Err
+fn scylla_reply(_command: &Value, message: &Value) -> Option<Result<(), String>> {
+    let reply = message.get("Scylla")?;
+    if reply.get("Ok").is_some() {
+        Some(Ok(()))
+    } else {
+        reply
+            .get("Err")
+            .map(|topology| Err(format!("Scylla rejected the topology change: {}", topology)))
+    }
+}
+
+fn feed_topologies(request: FeedRequest, add: bool) -> Vec<BrokerTopology> {
+    let url = request.url;
+    match (request.kind, add) {
+        (FeedSourceKind::Mqtt, true) => vec![
+            BrokerTopology::AddMqttMessages(url.clone()),
+            BrokerTopology::AddMqttMessagesReferenced(url.clone()),
+            BrokerTopology::AddMqttLatestMilestone(url),
+        ],
+        (FeedSourceKind::Mqtt, false) => vec![
+            BrokerTopology::RemoveMqttMessages(url.clone()),
+            BrokerTopology::RemoveMqttMessagesReferenced(url.clone()),
+            BrokerTopology::RemoveMqttLatestMilestone(url),
+        ],
+        (FeedSourceKind::EventStream, true) => vec![BrokerTopology::AddEventStream(url)],
+        (FeedSourceKind::EventStream, false) => vec![BrokerTopology::RemoveEventStream(url)],
+    }
+}
+
+#[post("/admin/feeds", data = "<request>")]
+pub(crate) async fn add_feed(
+    _auth: Authorized<AdminAccess>,
+    request: Json<FeedRequest>,
+    api_config: State<'_, ApiConfig>,
+) -> Result<AdminResponse, ListenerError> {
+    relay_to_broker(feed_topologies(request.into_inner(), true), &api_config).await
+}
+
+#[delete("/admin/feeds", data = "<request>")]
+pub(crate) async fn remove_feed(
+    _auth: Authorized<AdminAccess>,
+    request: Json<FeedRequest>,
+    api_config: State<'_, ApiConfig>,
+) -> Result<AdminResponse, ListenerError> {
+    relay_to_broker(feed_topologies(request.into_inner(), false), &api_config).await
+}
+
+#[post("/admin/endpoints", data = "<request>")]
+pub(crate) async fn add_endpoint(
+    _auth: Authorized<AdminAccess>,
+    request: Json<EndpointRequest>,
+    api_config: State<'_, ApiConfig>,
+) -> Result<AdminResponse, ListenerError> {
+    let topology = BrokerTopology::Requesters(RequesterTopology::AddEndpoint(request.into_inner().url));
+    relay_to_broker(vec![topology], &api_config).await
+}
+
+#[delete("/admin/endpoints", data = "<request>")]
+pub(crate) async fn remove_endpoint(
+    _auth: Authorized<AdminAccess>,
+    request: Json<EndpointRequest>,
+    api_config: State<'_, ApiConfig>,
+) -> Result<AdminResponse, ListenerError> {
+    let topology = BrokerTopology::Requesters(RequesterTopology::RemoveEndpoint(request.into_inner().url));
+    relay_to_broker(vec![topology], &api_config).await
+}
+
+#[post("/admin/imports", data = "<request>")]
+pub(crate) async fn start_import(
+    _auth: Authorized<AdminAccess>,
+    request: Json<ImportRequest>,
+    api_config: State<'_, ApiConfig>,
+) -> Result<AdminResponse, ListenerError> {
+    let ImportRequest {
+        path,
+        url,
+        resume,
+        import_range,
+        import_type,
+    } = request.into_inner();
+    let topology = match (path, url) {
+        (Some(path), None) => BrokerTopology::Import {
+            path,
+            resume,
+            import_range,
+            import_type,
+        },
+        (None, Some(url)) => BrokerTopology::ImportRemote {
+            url,
+            resume,
+            import_range,
+            import_type,
+        },
+        _ => {
+            return Err(ListenerError::BadParse(anyhow::anyhow!(
+                "Either a path or an url must be provided!"
+            )))
+        }
+    };
+    // the broker acknowledges the import once started, its progress is reported by the import route
+    relay_to_broker(vec![topology], &api_config).await
+}
+
+#[post("/admin/nodes", data = "<request>")]
+pub(crate) async fn add_node(
+    _auth: Authorized<AdminAccess>,
+    request: Json<NodeRequest>,
+    api_config: State<'_, ApiConfig>,
+) -> Result<AdminResponse, ListenerError> {
+    relay_to_scylla(Topology::AddNode(request.address), &api_config).await
+}
+
+#[delete("/admin/nodes", data = "<request>")]
+pub(crate) async fn remove_node(
+    _auth: Authorized<AdminAccess>,
+    request: Json<NodeRequest>,
+    api_config: State<'_, ApiConfig>,
+) -> Result<AdminResponse, ListenerError> {
+    relay_to_scylla(Topology::RemoveNode(request.address), &api_config).await
+}
+
+#[post("/admin/ring", data = "<request>")]
+pub(crate) async fn build_ring(
+    _auth: Authorized<AdminAccess>,
+    request: Json<RingRequest>,
+    api_config: State<'_, ApiConfig>,
+) -> Result<AdminResponse, ListenerError> {
+    relay_to_scylla(Topology::BuildRing(request.uniform_rf), &api_config).await
+}
+
+#[post("/admin/config/rollback")]
+pub(crate) async fn rollback_config(_auth: Authorized<AdminAccess>) -> AdminResponse {
+    let command = serde_json::json!("Rollback");
+    match get_history_mut_async().await.rollback() {
+        Some(_) => AdminResponse::new(AdminStatus::Applied, vec![command]),
+        None => AdminResponse::rejected(vec![command], "There is no config to roll back".to_owned()),
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    rate_limit::{
+        ApiClient,
+        QueryBudget,
+        RateLimiter,
+    },
+    *,
+};
+use chronicle_common::config::{
+    ApiPermission,
+    ApiScope,
+    AuthConfig,
+};
+use jsonwebtoken::{
+    decode,
+    Algorithm,
+    DecodingKey,
+    Validation,
+};
+use rocket::{
+    request::{
+        FromRequest,
+        Outcome,
+    },
+    Request,
+    State,
+};
+use std::{
+    collections::HashSet,
+    sync::Arc,
+};
+use subtle::ConstantTimeEq;
+
+/// The permission required by a route
+pub(crate) trait RoutePermission: Send + Sync + 'static {
+    /// The required permission
+    const PERMISSION: ApiPermission;
+}
+
+/// Requires the data permission
+pub(crate) struct DataAccess;
+/// Requires the analytics permission
+pub(crate) struct AnalyticsAccess;
+/// Requires the admin permission
+pub(crate) struct AdminAccess;
+
+impl RoutePermission for DataAccess {
+    const PERMISSION: ApiPermission = ApiPermission::Data;
+}
+
+impl RoutePermission for AnalyticsAccess {
+    const PERMISSION: ApiPermission = ApiPermission::Analytics;
+}
+
+impl RoutePermission for AdminAccess {
+    const PERMISSION: ApiPermission = ApiPermission::Admin;
+}
+
+/// The claims of the JWT bearer tokens
+#[derive(Debug, Deserialize)]
+struct Claims {
+    /// The client name
+    sub: String,
+    #[serde(default)]
+    keyspaces: HashSet<String>,
+    #[serde(default)]
+    permissions: HashSet<ApiPermission>,
+}
+
+/// The duration a throttled client has to wait, for the Retry-After header of the 429 responses
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetryAfter(pub Option<u64>);
+
+/// A request guard, which ensures the client is granted the permission P of the route,
+/// on the requested keyspace if any (the first segment of the keyspace routes), and is not rate limited
+pub(crate) struct Authorized<P> {
+    client: ApiClient,
+    rate_limiter: Arc<RateLimiter>,
+    _permission: PhantomData<P>,
+}
+
+impl<P> Authorized<P> {
+    /// Charge the client for the storage queries of its request
+    pub(crate) fn charge(&self, budget: &QueryBudget) {
+        self.rate_limiter.charge(&self.client, budget);
+    }
+}
+
+/// Authenticate the request credentials, returns the client and its scope, or None without credentials
+fn authenticate(auth: &AuthConfig, request: &Request<'_>) -> Result<Option<(ApiClient, ApiScope)>, ListenerError> {
+    let token = match request.headers().get_one("X-API-Key") {
+        Some(api_key) => api_key,
+        None => match request.headers().get_one("Authorization") {
+            Some(authorization) => authorization
+                .strip_prefix("Bearer ")
+                .ok_or(ListenerError::Unauthorized)?
+                .trim(),
+            None => return Ok(None),
+        },
+    };
+    // every key is compared in constant time, so the response time doesn't leak their prefixes
+    let api_key = auth.api_keys.iter().fold(None, |found, api_key| {
+        let matches: bool = api_key.key.as_bytes().ct_eq(token.as_bytes()).into();
+        found.or(if matches { Some(api_key) } else { None })
+    });
+    if let Some(api_key) = api_key {
+        return Ok(Some((ApiClient::ApiKey(api_key.name.clone()), api_key.scope.clone())));
+    }
+    let secret = auth.jwt_secret.as_ref().ok_or(ListenerError::Unauthorized)?;
+    let claims = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::new(Algorithm::HS256),
+    )
+    .map_err(|e| {
+        debug!("Rejected JWT: {}", e);
+        ListenerError::Unauthorized
+    })?
+    .claims;
+    let scope = ApiScope {
+        keyspaces: claims.keyspaces,
+        permissions: claims.permissions,
+    };
+    Ok(Some((ApiClient::Jwt(claims.sub), scope)))
+}
+
+#[rocket::async_trait]
+impl<'a, 'r, P: RoutePermission> FromRequest<'a, 'r> for Authorized<P> {
+    type Error = ListenerError;
+
+    async fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        let api_config = match request.guard::<State<'_, ApiConfig>>().await.succeeded() {
+            Some(api_config) => api_config.inner(),
+            None => {
+                let error = ListenerError::Other(anyhow::anyhow!("The api config is not managed!"));
+                return Outcome::Failure((Status::InternalServerError, error));
+            }
+        };
+        let client = match api_config.auth.as_ref() {
+            Some(auth) => {
+                let (client, scope) = match authenticate(auth, request) {
+                    Ok(Some((client, scope))) => (Some(client), scope),
+                    Ok(None) => match auth.anonymous_scope.as_ref() {
+                        Some(scope) => (None, scope.clone()),
+                        None => return Outcome::Failure((Status::Unauthorized, ListenerError::Unauthorized)),
+                    },
+                    Err(e) => return Outcome::Failure((e.status(), e)),
+                };
+                let keyspace = request.param::<String>(0).and_then(Result::ok);
+                if !scope.allows(P::PERMISSION, keyspace.as_deref()) {
+                    return match client {
+                        Some(_) => Outcome::Failure((Status::Forbidden, ListenerError::Forbidden)),
+                        // the anonymous scope doesn't grant it, but credentials could
+                        None => Outcome::Failure((Status::Unauthorized, ListenerError::Unauthorized)),
+                    };
+                }
+                client
+            }
+            // the API is open
+            None => None,
+        };
+        let client = client.unwrap_or_else(|| ApiClient::Anonymous(request.client_ip()));
+        let rate_limiter = match request.guard::<State<'_, Arc<RateLimiter>>>().await.succeeded() {
+            Some(rate_limiter) => rate_limiter.inner().clone(),
+            None => {
+                let error = ListenerError::Other(anyhow::anyhow!("The rate limiter is not managed!"));
+                return Outcome::Failure((Status::InternalServerError, error));
+            }
+        };
+        if let Err(retry_after) = rate_limiter.acquire(&client) {
+            let retry_after = retry_after.as_secs() + 1;
+            request.local_cache(|| RetryAfter(Some(retry_after)));
+            return Outcome::Failure((Status::TooManyRequests, ListenerError::TooManyRequests(retry_after)));
+        }
+        Outcome::Success(Self {
+            client,
+            rate_limiter,
+            _permission: PhantomData,
+        })
+    }
+}
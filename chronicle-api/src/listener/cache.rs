@@ -0,0 +1,176 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::responses::ListenerResponse;
+use chronicle_common::{
+    config::{
+        ApiPermission,
+        ApiScope,
+    },
+    metrics::API_CACHE_REQUESTS,
+};
+use lru::LruCache;
+use rocket::{
+    http::ContentType,
+    response::Responder,
+    Request,
+    Response,
+};
+use std::{
+    collections::hash_map::DefaultHasher,
+    future::Future,
+    hash::{
+        Hash,
+        Hasher,
+    },
+    io::Cursor,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// A serialized response, along with its entity tag
+#[derive(Clone)]
+struct CachedBody {
+    body: Arc<String>,
+    etag: String,
+    cached_at: Instant,
+}
+
+/// The in-process cache of the responses holding immutable data, keyed by keyspace and route
+pub(crate) struct ResponseCache {
+    entries: Option<Mutex<LruCache<String, CachedBody>>>,
+    ttl: Duration,
+    /// The scope of the requests without credentials, None if the API is open to anyone
+    anonymous_scope: Option<Option<ApiScope>>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(api_config: &ApiConfig) -> Self {
+        Self {
+            entries: (api_config.cache_capacity > 0).then(|| Mutex::new(LruCache::new(api_config.cache_capacity))),
+            ttl: Duration::from_secs(api_config.cache_ttl_secs),
+            anonymous_scope: api_config.auth.as_ref().map(|auth| auth.anonymous_scope.clone()),
+        }
+    }
+
+    /// Whether the responses of the keyspace are private, ie they can't be stored by the shared caches (proxies,
+    /// CDNs), as the requests without credentials can't read them
+    fn private(&self, keyspace: &str) -> bool {
+        self.anonymous_scope.as_ref().map_or(false, |anonymous_scope| {
+            !matches!(anonymous_scope, Some(scope) if scope.allows(ApiPermission::Data, Some(keyspace)))
+        })
+    }
+
+    /// Serve the response of the route from the cache, or fetch it. The fetched response gets cached only if
+    /// it's final, ie it can't change anymore.
+    pub(crate) async fn get_or_fetch<F>(
+        &self,
+        route: &'static str,
+        key: String,
+        fetch: F,
+    ) -> Result<CachedResponse, ListenerError>
+    where
+        F: Future<Output = Result<(ListenerResponse, bool), ListenerError>>,
+    {
+        // the keys start with the keyspace
+        let private = self.private(key.split('/').next().unwrap_or_default());
+        if let Some(entries) = self.entries.as_ref() {
+            let cached = entries.lock().unwrap().get(&key).cloned();
+            match cached {
+                Some(cached) if cached.cached_at.elapsed() < self.ttl => {
+                    API_CACHE_REQUESTS.with_label_values(&[route, "hit"]).inc();
+                    return Ok(CachedResponse::new(cached.body, cached.etag, Some(self.ttl), private));
+                }
+                _ => API_CACHE_REQUESTS.with_label_values(&[route, "miss"]).inc(),
+            }
+        }
+        let (response, is_final) = fetch.await?;
+        let body = serde_json::to_string(&SuccessBody::from(response)).map_err(|e| ListenerError::Other(e.into()))?;
+        let body = Arc::new(body);
+        let etag = entity_tag(&body);
+        match self.entries.as_ref() {
+            Some(entries) if is_final => {
+                let cached = CachedBody {
+                    body: body.clone(),
+                    etag: etag.clone(),
+                    cached_at: Instant::now(),
+                };
+                entries.lock().unwrap().put(key, cached);
+                Ok(CachedResponse::new(body, etag, Some(self.ttl), private))
+            }
+            _ => Ok(CachedResponse::new(body, etag, None, private)),
+        }
+    }
+}
+
+/// The strong entity tag of the serialized response
+fn entity_tag(body: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+/// A json response with an entity tag, which supports conditional requests (If-None-Match)
+pub(crate) struct CachedResponse {
+    body: Arc<String>,
+    etag: String,
+    max_age: Option<Duration>,
+    private: bool,
+}
+
+impl CachedResponse {
+    fn new(body: Arc<String>, etag: String, max_age: Option<Duration>, private: bool) -> Self {
+        Self {
+            body,
+            etag,
+            max_age,
+            private,
+        }
+    }
+
+    /// Whether the client already holds the response
+    fn not_modified(&self, request: &Request<'_>) -> bool {
+        request
+            .headers()
+            .get("If-None-Match")
+            .flat_map(|tags| tags.split(','))
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == "*" || tag == self.etag)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for CachedResponse {
+    fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let cache_control = match self.max_age {
+            Some(max_age) => format!(
+                "{}, max-age={}, immutable",
+                if self.private { "private" } else { "public" },
+                max_age.as_secs()
+            ),
+            None => "no-cache".to_owned(),
+        };
+        let mut response = Response::build();
+        if self.not_modified(request) {
+            response.status(Status::NotModified);
+        } else {
+            response
+                .sized_body(None, Cursor::new(self.body.as_ref().clone()))
+                .header(ContentType::JSON);
+        }
+        if self.private {
+            // the response depends on the credentials of the request
+            response.raw_header_adjoin("Vary", "Authorization, X-API-Key");
+        }
+        response
+            .raw_header("ETag", self.etag)
+            .raw_header("Cache-Control", cache_control)
+            .ok()
+    }
+}
@@ -3,9 +3,13 @@
 
 use super::*;
 use application::*;
-use chronicle_common::get_config_async;
+use chronicle_common::{
+    config::ApiConfig,
+    get_config_async,
+};
 use chronicle_storage::access::*;
 use rocket::{
+    figment::Figment,
     http::Status,
     Rocket,
 };
@@ -20,8 +24,16 @@ use std::{
 };
 use thiserror::Error;
 
+#[cfg(feature = "rocket_listener")]
+mod admin;
+#[cfg(feature = "rocket_listener")]
+mod auth;
+#[cfg(feature = "rocket_listener")]
+mod cache;
 mod init;
 #[cfg(feature = "rocket_listener")]
+mod rate_limit;
+#[cfg(feature = "rocket_listener")]
 mod rocket_event_loop;
 mod terminating;
 
@@ -41,6 +53,18 @@ enum ListenerError {
     InvalidState,
     #[error("No endpoint found!")]
     NotFound,
+    #[error("The syncer is not running!")]
+    SyncerNotRunning,
+    #[error("The request timed out!")]
+    Timeout,
+    #[error("Missing or invalid credentials!")]
+    Unauthorized,
+    #[error("The credentials don't grant access to this route!")]
+    Forbidden,
+    #[error("Too many requests, retry in {0} seconds!")]
+    TooManyRequests(u64),
+    #[error("The {0} is unavailable!")]
+    Unavailable(&'static str),
     #[error(transparent)]
     BadParse(anyhow::Error),
     #[error(transparent)]
@@ -50,8 +74,15 @@ enum ListenerError {
 impl ListenerError {
     pub fn status(&self) -> Status {
         match self {
-            ListenerError::NoResults | ListenerError::InvalidKeyspace(_) => Status::NotFound,
+            ListenerError::NoResults | ListenerError::InvalidKeyspace(_) | ListenerError::SyncerNotRunning => {
+                Status::NotFound
+            }
             ListenerError::IndexTooLarge | ListenerError::InvalidHex | ListenerError::BadParse(_) => Status::BadRequest,
+            ListenerError::Timeout => Status::GatewayTimeout,
+            ListenerError::Unauthorized => Status::Unauthorized,
+            ListenerError::Forbidden => Status::Forbidden,
+            ListenerError::TooManyRequests(_) => Status::TooManyRequests,
+            ListenerError::Unavailable(_) => Status::BadGateway,
             _ => Status::InternalServerError,
         }
     }
@@ -71,6 +102,20 @@ impl RocketListener {
     pub fn new(rocket: Rocket) -> Self {
         Self { rocket: Some(rocket) }
     }
+
+    /// The Rocket configuration, with the listen address and TLS of the api config
+    /// The api config takes precedence over the `ROCKET_ADDRESS`, `ROCKET_PORT` and `ROCKET_TLS` environment variables
+    pub fn figment(api_config: &ApiConfig) -> Figment {
+        let mut figment = rocket::Config::figment()
+            .merge(("address", api_config.listen_address.ip()))
+            .merge(("port", api_config.listen_address.port()));
+        if let Some(tls) = api_config.tls.as_ref() {
+            figment = figment
+                .merge(("tls.certs", &tls.cert_path))
+                .merge(("tls.key", &tls.key_path));
+        }
+        figment
+    }
 }
 
 /// A listener. Can use Rocket or another impl depending on data provided
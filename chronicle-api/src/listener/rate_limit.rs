@@ -0,0 +1,177 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use chronicle_common::{
+    config::RateLimitConfig,
+    metrics::API_THROTTLED_REQUESTS,
+};
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    sync::Mutex,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// The buckets idle for longer than this get dropped
+const IDLE_BUCKET_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// The interval between two consecutive evictions of the idle buckets
+const EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The token bucket of a client
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// The token buckets of the clients, keyed by client
+struct Buckets {
+    buckets: HashMap<String, TokenBucket>,
+    /// The last time the idle buckets got evicted
+    evicted: Instant,
+}
+
+/// The token bucket rate limiter of the API clients
+pub(crate) struct RateLimiter {
+    config: Option<RateLimitConfig>,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: Option<RateLimitConfig>) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                evicted: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take `cost` tokens from the client bucket, which can go into debt.
+    /// Unless `force`d, nothing is taken if the bucket is empty, and the duration to wait before retrying is returned.
+    fn take(&self, client: &str, cost: f64, force: bool) -> Result<(), Duration> {
+        let config = match self.config.as_ref() {
+            Some(config) => config,
+            None => return Ok(()),
+        };
+        let rate = config.requests_per_sec as f64;
+        let burst = config.burst as f64;
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        // evict the idle buckets periodically, rather than scanning all of them on every request
+        if now.duration_since(buckets.evicted) >= EVICTION_INTERVAL {
+            buckets
+                .buckets
+                .retain(|_, bucket| now.duration_since(bucket.updated) < IDLE_BUCKET_TIMEOUT);
+            buckets.evicted = now;
+        }
+        let bucket = buckets.buckets.entry(client.to_owned()).or_insert(TokenBucket {
+            tokens: burst,
+            updated: now,
+        });
+        bucket.tokens = (bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * rate).min(burst);
+        bucket.updated = now;
+        if bucket.tokens < 1.0 && !force {
+            return Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate));
+        }
+        bucket.tokens -= cost;
+        Ok(())
+    }
+
+    /// Take a token for a new request of the client, see `Authorized`
+    pub(crate) fn acquire(&self, client: &ApiClient) -> Result<(), Duration> {
+        self.take(&client.key(), 1.0, false).map_err(|retry_after| {
+            API_THROTTLED_REQUESTS
+                .with_label_values(&[client.kind(), "rate_limit"])
+                .inc();
+            retry_after
+        })
+    }
+
+    /// Charge the client for the extra partitions scanned by its request
+    pub(crate) fn charge(&self, client: &ApiClient, budget: &QueryBudget) {
+        let extra_partitions = budget.partitions().saturating_sub(1);
+        if extra_partitions > 0 {
+            self.take(&client.key(), extra_partitions as f64, true).ok();
+        }
+        if budget.exceeded {
+            API_THROTTLED_REQUESTS
+                .with_label_values(&[client.kind(), "query_budget"])
+                .inc();
+        }
+    }
+}
+
+/// An API client, which gets rate limited
+#[derive(Debug, Clone)]
+pub(crate) enum ApiClient {
+    /// A client authenticated with an API key, by key name
+    ApiKey(String),
+    /// A client authenticated with a JWT, by subject
+    Jwt(String),
+    /// An anonymous client, by ip address if known
+    Anonymous(Option<std::net::IpAddr>),
+}
+
+impl ApiClient {
+    fn key(&self) -> String {
+        match self {
+            // the API key names and the JWT subjects are distinct namespaces, so they don't share a bucket
+            ApiClient::ApiKey(name) => format!("key:{}", name),
+            ApiClient::Jwt(subject) => format!("jwt:{}", subject),
+            ApiClient::Anonymous(Some(ip)) => format!("ip:{}", ip),
+            ApiClient::Anonymous(None) => "anonymous".to_owned(),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            ApiClient::ApiKey(_) | ApiClient::Jwt(_) => "authenticated",
+            ApiClient::Anonymous(_) => "anonymous",
+        }
+    }
+}
+
+/// The cost budget of the storage queries of a paged request, in partitions and rows scanned
+#[derive(Debug, Clone)]
+pub(crate) struct QueryBudget {
+    max_partitions: usize,
+    max_rows: usize,
+    partitions: HashSet<PartitionId>,
+    rows: usize,
+    exceeded: bool,
+}
+
+impl QueryBudget {
+    pub(crate) fn new(api_config: &ApiConfig) -> Self {
+        Self {
+            max_partitions: api_config.max_query_partitions,
+            max_rows: api_config.max_query_rows,
+            partitions: HashSet::new(),
+            rows: 0,
+            exceeded: false,
+        }
+    }
+
+    /// Record the rows scanned from the partition
+    pub(crate) fn scanned(&mut self, partition_id: PartitionId, rows: usize) {
+        self.partitions.insert(partition_id);
+        self.rows += rows;
+    }
+
+    /// Whether the request exceeded its budget, in which case its page gets cut short
+    pub(crate) fn exceeded(&mut self) -> bool {
+        self.exceeded |= self.partitions.len() >= self.max_partitions || self.rows >= self.max_rows;
+        self.exceeded
+    }
+
+    fn partitions(&self) -> usize {
+        self.partitions.len()
+    }
+}
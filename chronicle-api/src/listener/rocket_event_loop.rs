@@ -1,7 +1,24 @@
 // Copyright 2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use super::*;
+use super::{
+    auth::{
+        AdminAccess,
+        AnalyticsAccess,
+        Authorized,
+        DataAccess,
+        RetryAfter,
+    },
+    cache::{
+        CachedResponse,
+        ResponseCache,
+    },
+    rate_limit::{
+        QueryBudget,
+        RateLimiter,
+    },
+    *,
+};
 use crate::responses::*;
 use anyhow::anyhow;
 use bee_message::{
@@ -18,16 +35,32 @@ use bee_message::{
 };
 use chronicle_broker::{
     AnalyticsData,
+    ImportReport,
     SyncData,
+    SyncerStatus,
+    UnreachableMilestone,
+    SYNCER_STATUS,
 };
 use chronicle_common::{
-    config::PartitionConfig,
+    config::{
+        ApiRouteGroup,
+        PartitionConfig,
+    },
     metrics::{
         prometheus::{
             self,
             Encoder,
             TextEncoder,
         },
+        IMPORT_BYTES,
+        IMPORT_BYTES_PER_SECOND,
+        IMPORT_ETA_SECONDS,
+        IMPORT_FAILURES,
+        IMPORT_IN_PROGRESS_FILES,
+        IMPORT_MILESTONES,
+        IMPORT_MILESTONES_PER_SECOND,
+        IMPORT_PENDING_FILES,
+        IMPORT_TOTAL_BYTES,
         INCOMING_REQUESTS,
         REGISTRY,
         RESPONSE_CODE_COLLECTOR,
@@ -79,7 +112,17 @@ use std::{
     io::Cursor,
     path::PathBuf,
     str::FromStr,
-    time::SystemTime,
+    sync::{
+        atomic::{
+            AtomicU64,
+            Ordering,
+        },
+        Arc,
+    },
+    time::{
+        Duration,
+        SystemTime,
+    },
 };
 use tokio::sync::mpsc;
 
@@ -99,7 +142,8 @@ impl<H: ChronicleAPIScope> EventLoop<ChronicleAPISender<H>> for Listener<RocketL
                 .map_err(|_| Need::Abort)?;
         }
 
-        let storage_config = get_config_async().await.storage_config;
+        let config = get_config_async().await;
+        let storage_config = config.storage_config;
 
         let keyspaces = storage_config
             .keyspaces
@@ -114,8 +158,8 @@ impl<H: ChronicleAPIScope> EventLoop<ChronicleAPISender<H>> for Listener<RocketL
                 .take()
                 .ok_or_else(|| Need::Abort)?
                 .manage(storage_config.partition_config.clone())
-                .manage(keyspaces)
-                .register(catchers![internal_error, not_found]),
+                .manage(keyspaces),
+            &config.api_config,
         )
         .launch()
         .await
@@ -123,35 +167,98 @@ impl<H: ChronicleAPIScope> EventLoop<ChronicleAPISender<H>> for Listener<RocketL
     }
 }
 
-fn construct_rocket(rocket: Rocket) -> Rocket {
-    rocket
-        .mount(
-            "/api",
-            routes![
-                options,
-                info,
-                metrics,
-                service,
-                sync,
+/// The timeout of the storage queries, as configured by the api config
+static REQUEST_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(30);
+
+fn construct_rocket(rocket: Rocket, api_config: &ApiConfig) -> Rocket {
+    REQUEST_TIMEOUT_SECS.store(api_config.request_timeout_secs, Ordering::Relaxed);
+    let mut api_routes = routes![options];
+    for route_group in ApiRouteGroup::ALL.iter() {
+        if !api_config.route_groups.contains(route_group) {
+            continue;
+        }
+        api_routes.extend(match route_group {
+            ApiRouteGroup::Info => routes![info, metrics, service],
+            ApiRouteGroup::Sync => routes![import, syncer, sync, unreachable],
+            ApiRouteGroup::Messages => routes![
                 get_message,
                 get_message_metadata,
                 get_message_children,
-                get_message_by_index,
-                get_output_by_transaction_id,
-                get_output,
-                get_ed25519_outputs,
+                get_message_by_index
+            ],
+            ApiRouteGroup::Outputs => routes![get_output_by_transaction_id, get_output, get_ed25519_outputs],
+            ApiRouteGroup::Transactions => routes![
                 get_transactions_for_address,
                 get_transaction_for_message,
-                get_transaction_included_message,
-                get_milestone,
-                get_analytics
+                get_transaction_included_message
             ],
-        )
-        .attach(CORS)
+            ApiRouteGroup::Milestones => routes![get_milestone],
+            ApiRouteGroup::Analytics => routes![get_analytics],
+            ApiRouteGroup::Admin => routes![
+                admin::add_feed,
+                admin::remove_feed,
+                admin::add_endpoint,
+                admin::remove_endpoint,
+                admin::start_import,
+                admin::add_node,
+                admin::remove_node,
+                admin::build_ring,
+                admin::rollback_config
+            ],
+        });
+    }
+    rocket
+        .mount("/api", api_routes)
+        .register(catchers![
+            internal_error,
+            not_found,
+            unauthorized,
+            forbidden,
+            too_many_requests
+        ])
+        .manage(api_config.clone())
+        .manage(Arc::new(RateLimiter::new(api_config.rate_limit.clone())))
+        .manage(ResponseCache::new(api_config))
+        .attach(CORS::new(api_config.allowed_origins.clone(), &api_config.route_groups))
         .attach(RequestTimer)
 }
 
-struct CORS;
+/// The request headers a cross origin client may send, covering both API key schemes
+const ALLOW_HEADERS: &str = "Authorization, X-API-Key, Content-Type";
+
+/// The CORS policy, which allows either any origin, or only the configured ones
+struct CORS {
+    allowed_origins: Vec<String>,
+    /// The value of the Access-Control-Allow-Methods header
+    allow_methods: &'static str,
+}
+
+impl CORS {
+    fn new(allowed_origins: Vec<String>, route_groups: &HashSet<ApiRouteGroup>) -> Self {
+        // only the admin routes mutate anything, the rest of the API is read only
+        let allow_methods = if route_groups.contains(&ApiRouteGroup::Admin) {
+            "GET, POST, DELETE, OPTIONS"
+        } else {
+            "GET, OPTIONS"
+        };
+        Self {
+            allowed_origins,
+            allow_methods,
+        }
+    }
+
+    /// The value of the Access-Control-Allow-Origin header, None if the origin is not allowed
+    fn allow_origin(&self, request: &Request<'_>) -> Option<String> {
+        if self.allowed_origins.iter().any(|origin| origin == "*") {
+            return Some("*".to_owned());
+        }
+        request
+            .headers()
+            .get_one("Origin")
+            .filter(|origin| self.allowed_origins.iter().any(|allowed| allowed == origin))
+            .map(str::to_owned)
+    }
+}
 
 #[rocket::async_trait]
 impl Fairing for CORS {
@@ -162,11 +269,16 @@ impl Fairing for CORS {
         }
     }
 
-    async fn on_response<'r>(&self, _request: &'r Request<'_>, response: &mut Response<'r>) {
-        response.set_raw_header("Access-Control-Allow-Origin", "*");
-        response.set_raw_header("Access-Control-Allow-Methods", "GET, OPTIONS");
-        response.set_raw_header("Access-Control-Allow-Headers", "*");
-        response.set_raw_header("Access-Control-Allow-Credentials", "true");
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if let Some(allow_origin) = self.allow_origin(request) {
+            if allow_origin != "*" {
+                response.adjoin_raw_header("Vary", "Origin");
+            }
+            response.set_raw_header("Access-Control-Allow-Origin", allow_origin);
+            response.set_raw_header("Access-Control-Allow-Methods", self.allow_methods);
+            response.set_raw_header("Access-Control-Allow-Headers", ALLOW_HEADERS);
+            response.set_raw_header("Access-Control-Allow-Credentials", "true");
+        }
     }
 }
 
@@ -226,17 +338,25 @@ impl Fairing for RequestTimer {
 
 impl<'r> Responder<'r, 'static> for ListenerError {
     fn respond_to(self, _req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let retry_after = match self {
+            ListenerError::TooManyRequests(retry_after) => Some(retry_after),
+            _ => None,
+        };
         let err = ErrorBody::from(self);
         let string = serde_json::to_string(&err).map_err(|e| {
             error!("JSON failed to serialize: {:?}", e);
             Status::InternalServerError
         })?;
 
-        Response::build()
+        let mut response = Response::build();
+        response
             .sized_body(None, Cursor::new(string))
             .status(err.status)
-            .header(ContentType::JSON)
-            .ok()
+            .header(ContentType::JSON);
+        if let Some(retry_after) = retry_after {
+            response.raw_header("Retry-After", retry_after.to_string());
+        }
+        response.ok()
     }
 }
 
@@ -258,7 +378,11 @@ type ListenerResult = Result<ListenerResponse, ListenerError>;
 async fn options(_path: PathBuf) {}
 
 #[get("/<keyspace>/info")]
-async fn info(keyspaces: State<'_, HashSet<String>>, keyspace: String) -> ListenerResult {
+async fn info(
+    _auth: Authorized<DataAccess>,
+    keyspaces: State<'_, HashSet<String>>,
+    keyspace: String,
+) -> ListenerResult {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
@@ -275,7 +399,7 @@ async fn info(keyspaces: State<'_, HashSet<String>>, keyspace: String) -> Listen
 }
 
 #[get("/metrics")]
-async fn metrics() -> Result<String, ListenerError> {
+async fn metrics(_auth: Authorized<AdminAccess>) -> Result<String, ListenerError> {
     let encoder = TextEncoder::new();
     let mut buffer = Vec::new();
     encoder
@@ -294,12 +418,46 @@ async fn metrics() -> Result<String, ListenerError> {
 }
 
 #[get("/service")]
-async fn service() -> Json<Service> {
+async fn service(_auth: Authorized<AdminAccess>) -> Json<Service> {
     Json(SERVICE.read().await.clone())
 }
 
+#[get("/import")]
+async fn import(_auth: Authorized<AdminAccess>) -> Json<ImportReport> {
+    let eta_seconds = IMPORT_ETA_SECONDS.get();
+    Json(ImportReport {
+        milestones: IMPORT_MILESTONES.get() as u64,
+        bytes: IMPORT_BYTES.get() as u64,
+        total_bytes: IMPORT_TOTAL_BYTES.get() as u64,
+        milestones_per_second: IMPORT_MILESTONES_PER_SECOND.get(),
+        bytes_per_second: IMPORT_BYTES_PER_SECOND.get(),
+        eta_seconds: if eta_seconds >= 0 {
+            Some(eta_seconds as u64)
+        } else {
+            None
+        },
+        failures: IMPORT_FAILURES.get() as usize,
+        in_progress_files: IMPORT_IN_PROGRESS_FILES.get() as usize,
+        pending_files: IMPORT_PENDING_FILES.get() as usize,
+    })
+}
+
+#[get("/syncer")]
+async fn syncer(_auth: Authorized<AdminAccess>) -> Result<Json<SyncerStatus>, ListenerError> {
+    SYNCER_STATUS
+        .read()
+        .await
+        .clone()
+        .map(|status| Json(status))
+        .ok_or(ListenerError::SyncerNotRunning)
+}
+
 #[get("/<keyspace>/sync")]
-async fn sync(keyspaces: State<'_, HashSet<String>>, keyspace: String) -> Result<Json<SyncData>, ListenerError> {
+async fn sync(
+    _auth: Authorized<AdminAccess>,
+    keyspaces: State<'_, HashSet<String>>,
+    keyspace: String,
+) -> Result<Json<SyncData>, ListenerError> {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
@@ -310,6 +468,22 @@ async fn sync(keyspaces: State<'_, HashSet<String>>, keyspace: String) -> Result
         .map_err(|e| ListenerError::Other(e.into()))
 }
 
+#[get("/<keyspace>/unreachable")]
+async fn unreachable(
+    _auth: Authorized<AdminAccess>,
+    keyspaces: State<'_, HashSet<String>>,
+    keyspace: String,
+) -> Result<Json<Vec<UnreachableMilestone>>, ListenerError> {
+    if !keyspaces.contains(&keyspace) {
+        return Err(ListenerError::InvalidKeyspace(keyspace));
+    }
+    let keyspace = ChronicleKeyspace::new(keyspace);
+    UnreachableMilestone::try_fetch(&keyspace, 3)
+        .await
+        .map(|u| Json(u))
+        .map_err(|e| ListenerError::Other(e.into()))
+}
+
 async fn query<V, S, K>(
     keyspace: S,
     key: K,
@@ -337,14 +511,13 @@ where
 
     request.send_local(worker);
 
-    while let Some(event) = inbox.recv().await {
-        match event {
-            Ok(res) => return res.ok_or_else(|| ListenerError::NoResults),
-            Err(worker_error) => return Err(ListenerError::Other(worker_error.into())),
-        }
+    let timeout = Duration::from_secs(REQUEST_TIMEOUT_SECS.load(Ordering::Relaxed));
+    match tokio::time::timeout(timeout, inbox.recv()).await {
+        Ok(Some(Ok(res))) => res.ok_or_else(|| ListenerError::NoResults),
+        Ok(Some(Err(worker_error))) => Err(ListenerError::Other(worker_error.into())),
+        Ok(None) => Err(ListenerError::NoResponseError),
+        Err(_) => Err(ListenerError::Timeout),
     }
-
-    Err(ListenerError::NoResponseError)
 }
 
 async fn page<K, V>(
@@ -353,6 +526,7 @@ async fn page<K, V>(
     page_size: usize,
     state: &mut Option<StateData>,
     partition_config: &PartitionConfig,
+    budget: &mut QueryBudget,
     key: K,
 ) -> Result<Vec<Partitioned<V>>, ListenerError>
 where
@@ -366,6 +540,7 @@ where
         page_size,
         state,
         partition_config,
+        budget,
         key,
         None::<fn(&Partitioned<V>) -> bool>,
     )
@@ -378,6 +553,7 @@ async fn page_filtered<K, V, F: Clone + FnMut(&Partitioned<V>) -> bool>(
     page_size: usize,
     prev_state: &mut Option<StateData>,
     partition_config: &PartitionConfig,
+    budget: &mut QueryBudget,
     key: K,
     filter: Option<F>,
 ) -> Result<Vec<Partitioned<V>>, ListenerError>
@@ -387,7 +563,6 @@ where
     ChronicleKeyspace: Select<Partitioned<K>, Paged<VecDeque<Partitioned<V>>>>,
 {
     let total_start_time = std::time::Instant::now();
-    let mut start_time = total_start_time;
     // The milestone chunk, i.e. how many sequential milestones go on a partition at a time
     let milestone_chunk = partition_config.milestone_chunk_size as usize;
 
@@ -431,9 +606,54 @@ where
             (latest_milestone, partition_ids)
         }
     };
+    debug!(
+        "Setup time: {} ms",
+        (std::time::Instant::now() - total_start_time).as_millis()
+    );
+
+    page_partitions(
+        prev_state,
+        latest_milestone,
+        partition_ids,
+        page_size,
+        milestone_chunk,
+        budget,
+        filter,
+        |partition_id, page_size, paging_state| {
+            query::<Paged<VecDeque<Partitioned<V>>>, _, _>(
+                keyspace.clone(),
+                Partitioned::new(key.clone(), partition_id, latest_milestone),
+                Some(page_size),
+                paging_state,
+            )
+        },
+    )
+    .await
+}
 
-    // This is safe because we set the value above
-    let mut state = prev_state.as_mut().unwrap();
+/// Fill the page with the records of the partitions, fetched with `fetch(partition_id, page_size, paging_state)`,
+/// up to the latest milestone. The state gets updated to resume the next page, or None once all the partitions
+/// are depleted.
+async fn page_partitions<V, F, Q, Fut>(
+    prev_state: &mut Option<StateData>,
+    latest_milestone: u32,
+    partition_ids: Vec<(MilestoneIndex, PartitionId)>,
+    page_size: usize,
+    milestone_chunk: usize,
+    budget: &mut QueryBudget,
+    filter: Option<F>,
+    fetch: Q,
+) -> Result<Vec<Partitioned<V>>, ListenerError>
+where
+    F: Clone + FnMut(&Partitioned<V>) -> bool,
+    Q: Fn(PartitionId, i32, Option<Vec<u8>>) -> Fut,
+    Fut: std::future::Future<Output = Result<Paged<VecDeque<Partitioned<V>>>, ListenerError>>,
+{
+    let total_start_time = std::time::Instant::now();
+    let mut start_time;
+    let state = prev_state
+        .as_mut()
+        .ok_or_else(|| anyhow!("Unexpected error retrieving the paging state!"))?;
 
     // The last partition id that we got results from. This is sent back and forth between
     // the requestor to keep track of pages.
@@ -441,17 +661,15 @@ where
     // The last milestone index we got results from.
     let prev_last_milestone_index = state.last_milestone_index.take();
     let prev_paging_state = state.paging_state.take();
-
-    debug!(
-        "Setup time: {} ms",
-        (std::time::Instant::now() - start_time).as_millis()
-    );
-    start_time = std::time::Instant::now();
-
-    debug!(
-        "Reorder time: {} ms",
-        (std::time::Instant::now() - start_time).as_millis()
-    );
+    // Resume from the last partition, so a page cut short by the query budget makes progress
+    let mut partition_ids = partition_ids;
+    if let Some(i) = prev_last_partition_id.and_then(|last_partition_id| {
+        partition_ids
+            .iter()
+            .position(|(_, partition_id)| *partition_id == last_partition_id)
+    }) {
+        partition_ids.rotate_left(i);
+    }
 
     // This will hold lists of results keyed by partition id
     let mut list_map = HashMap::new();
@@ -486,6 +704,20 @@ where
 
         // Fetch a chunk of results if we need them to fill the page size
         if !list_map.contains_key(partition_id) {
+            // Unless the query budget is exceeded, in which case the next page resumes from this partition
+            if budget.exceeded() {
+                debug!("The query budget is exceeded so returning a short page");
+                // every record above the last returned milestone got returned, and the depleted partitions
+                // have no record left below it
+                state.last_milestone_index = Some(results.last().map_or(latest_milestone, |last: &Partitioned<V>| {
+                    last.milestone_index().saturating_sub(1)
+                }));
+                state.last_partition_id = Some(*partition_id);
+                state
+                    .partition_ids
+                    .retain(|(_, partition_id)| !depleted_partitions.contains(partition_id));
+                return Ok(results);
+            }
             start_time = std::time::Instant::now();
             let fetch_ids =
                 (partition_ind..partition_ind + fetch_size).filter_map(|ind| partition_ids.get(ind).map(|v| v.1));
@@ -496,10 +728,9 @@ where
                     latest_milestone,
                     prev_last_partition_id.map(|id| partition_id == id)
                 );
-                query::<Paged<VecDeque<Partitioned<V>>>, _, _>(
-                    keyspace.clone(),
-                    Partitioned::new(key.clone(), partition_id, latest_milestone),
-                    Some(page_size as i32),
+                fetch(
+                    partition_id,
+                    page_size as i32,
                     prev_last_partition_id.and_then(|id| {
                         if partition_id == id {
                             prev_paging_state.clone()
@@ -516,6 +747,7 @@ where
             );
             for (partition_id, list) in fetch_ids.zip(res) {
                 let mut list = list?;
+                budget.scanned(partition_id, list.len());
                 if let Some(f) = filter.as_ref() {
                     list.retain(f.clone());
                 }
@@ -608,15 +840,21 @@ where
                     return Ok(results);
                 } else {
                     debug!("...and we need more results");
-                    if list.paging_state.is_some() {
+                    if list.paging_state.is_some() && budget.exceeded() {
+                        debug!("......but the query budget is exceeded so returning a short page");
+                        state.paging_state = list.paging_state.take();
+                        state.last_partition_id = Some(*partition_id);
+                        state.last_milestone_index = Some(latest_milestone);
+                        return Ok(results);
+                    } else if list.paging_state.is_some() {
                         debug!("......so we're querying for them");
-                        *list = query::<Paged<VecDeque<Partitioned<V>>>, _, _>(
-                            keyspace.clone(),
-                            Partitioned::new(key.clone(), *partition_id, latest_milestone),
-                            Some((page_size - results.len()) as i32),
+                        *list = fetch(
+                            *partition_id,
+                            (page_size - results.len()) as i32,
                             list.paging_state.clone(),
                         )
                         .await?;
+                        budget.scanned(*partition_id, list.len());
                         if let Some(f) = filter.as_ref() {
                             list.retain(f.clone());
                         }
@@ -653,48 +891,74 @@ where
 }
 
 #[get("/<keyspace>/messages/<message_id>")]
-async fn get_message(keyspace: String, message_id: String, keyspaces: State<'_, HashSet<String>>) -> ListenerResult {
+async fn get_message(
+    _auth: Authorized<DataAccess>,
+    keyspace: String,
+    message_id: String,
+    keyspaces: State<'_, HashSet<String>>,
+    cache: State<'_, ResponseCache>,
+) -> Result<CachedResponse, ListenerError> {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
-    let keyspace = ChronicleKeyspace::new(keyspace);
     let message_id = MessageId::from_str(&message_id).map_err(|e| ListenerError::BadParse(e.into()))?;
-    query::<Message, _, _>(keyspace, message_id, None, None)
+    let key = format!("{}/messages/{}", keyspace, message_id);
+    let keyspace = ChronicleKeyspace::new(keyspace);
+    // messages are content addressed, so they never change
+    cache
+        .get_or_fetch("message", key, async {
+            query::<Message, _, _>(keyspace, message_id, None, None)
+                .await
+                .and_then(|message| message.try_into().map_err(|e: Cow<'static, str>| anyhow!(e).into()))
+                .map(|response| (response, true))
+        })
         .await
-        .and_then(|message| message.try_into().map_err(|e: Cow<'static, str>| anyhow!(e).into()))
 }
 
 #[get("/<keyspace>/messages/<message_id>/metadata")]
 async fn get_message_metadata(
+    _auth: Authorized<DataAccess>,
     keyspace: String,
     message_id: String,
     keyspaces: State<'_, HashSet<String>>,
-) -> ListenerResult {
+    cache: State<'_, ResponseCache>,
+) -> Result<CachedResponse, ListenerError> {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
-    let keyspace = ChronicleKeyspace::new(keyspace);
     let message_id = MessageId::from_str(&message_id).map_err(|e| ListenerError::BadParse(e.into()))?;
-    query::<MessageMetadata, _, _>(keyspace, message_id, None, None)
+    let key = format!("{}/messages/{}/metadata", keyspace, message_id);
+    let keyspace = ChronicleKeyspace::new(keyspace);
+    // the metadata is final once the message got a ledger inclusion state
+    cache
+        .get_or_fetch("message_metadata", key, async {
+            query::<MessageMetadata, _, _>(keyspace, message_id, None, None)
+                .await
+                .map(|metadata| {
+                    let is_final = metadata.ledger_inclusion_state.is_some();
+                    (metadata.into(), is_final)
+                })
+        })
         .await
-        .map(|metadata| metadata.into())
 }
 
 #[get("/<keyspace>/messages/<message_id>/children?<page_size>&<expanded>&<state>")]
 async fn get_message_children(
+    auth: Authorized<DataAccess>,
     keyspace: String,
     message_id: String,
     page_size: Option<usize>,
     expanded: Option<bool>,
     state: Option<String>,
     partition_config: State<'_, PartitionConfig>,
+    api_config: State<'_, ApiConfig>,
     keyspaces: State<'_, HashSet<String>>,
 ) -> ListenerResult {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
     let message_id = MessageId::from_str(&message_id).map_err(|e| ListenerError::BadParse(e.into()))?;
-    let page_size = page_size.unwrap_or(100);
+    let page_size = page_size.unwrap_or(100).min(api_config.max_page_size);
 
     let mut state = state
         .map(|state| {
@@ -704,15 +968,18 @@ async fn get_message_children(
         })
         .transpose()?;
 
+    let mut budget = QueryBudget::new(&api_config);
     let mut messages = page(
         keyspace.clone(),
         Hint::parent(message_id.to_string()),
         page_size,
         &mut state,
         partition_config.borrow(),
+        &mut budget,
         message_id,
     )
     .await?;
+    auth.charge(&budget);
 
     let state = state
         .map(|state| bincode::serialize(&state).map(|v| hex::encode(v)))
@@ -740,6 +1007,7 @@ async fn get_message_children(
 
 #[get("/<keyspace>/messages?<index>&<page_size>&<utf8>&<expanded>&<state>")]
 async fn get_message_by_index(
+    auth: Authorized<DataAccess>,
     keyspace: String,
     mut index: String,
     page_size: Option<usize>,
@@ -747,6 +1015,7 @@ async fn get_message_by_index(
     expanded: Option<bool>,
     state: Option<String>,
     partition_config: State<'_, PartitionConfig>,
+    api_config: State<'_, ApiConfig>,
     keyspaces: State<'_, HashSet<String>>,
 ) -> ListenerResult {
     if !keyspaces.contains(&keyspace) {
@@ -772,17 +1041,20 @@ async fn get_message_by_index(
         .transpose()?;
 
     let indexation = Indexation(index.clone());
-    let page_size = page_size.unwrap_or(1000);
+    let page_size = page_size.unwrap_or(1000).min(api_config.max_page_size);
 
+    let mut budget = QueryBudget::new(&api_config);
     let mut messages = page(
         keyspace.clone(),
         Hint::index(index.clone()),
         page_size,
         &mut state,
         partition_config.borrow(),
+        &mut budget,
         indexation,
     )
     .await?;
+    auth.charge(&budget);
 
     let state = state
         .map(|state| bincode::serialize(&state).map(|v| hex::encode(v)))
@@ -810,12 +1082,14 @@ async fn get_message_by_index(
 
 #[get("/<keyspace>/addresses/ed25519/<address>/outputs?<page_size>&<expanded>&<state>")]
 async fn get_ed25519_outputs(
+    auth: Authorized<DataAccess>,
     keyspace: String,
     address: String,
     page_size: Option<usize>,
     expanded: Option<bool>,
     state: Option<String>,
     partition_config: State<'_, PartitionConfig>,
+    api_config: State<'_, ApiConfig>,
     keyspaces: State<'_, HashSet<String>>,
 ) -> ListenerResult {
     if !keyspaces.contains(&keyspace) {
@@ -830,17 +1104,20 @@ async fn get_ed25519_outputs(
         .transpose()?;
 
     let ed25519_address = Ed25519Address::from_str(&address).map_err(|e| ListenerError::BadParse(e.into()))?;
-    let page_size = page_size.unwrap_or(100);
+    let page_size = page_size.unwrap_or(100).min(api_config.max_page_size);
 
+    let mut budget = QueryBudget::new(&api_config);
     let mut outputs = page(
         keyspace.clone(),
         Hint::address(ed25519_address.to_string()),
         page_size,
         &mut state,
         partition_config.borrow(),
+        &mut budget,
         ed25519_address,
     )
     .await?;
+    auth.charge(&budget);
 
     let state = state
         .map(|state| bincode::serialize(&state).map(|v| hex::encode(v)))
@@ -878,29 +1155,44 @@ async fn get_ed25519_outputs(
 
 #[get("/<keyspace>/outputs/<transaction_id>/<idx>")]
 async fn get_output_by_transaction_id(
+    _auth: Authorized<DataAccess>,
     keyspace: String,
     transaction_id: String,
     idx: u16,
     keyspaces: State<'_, HashSet<String>>,
-) -> ListenerResult {
+    cache: State<'_, ResponseCache>,
+) -> Result<CachedResponse, ListenerError> {
     get_output(
+        _auth,
         keyspace,
         TransactionId::from_str(&transaction_id)
             .and_then(|t| OutputId::new(t, idx))
             .map_err(|e| ListenerError::BadParse(e.into()))?
             .to_string(),
         keyspaces,
+        cache,
     )
     .await
 }
 
 #[get("/<keyspace>/outputs/<output_id>")]
-async fn get_output(keyspace: String, output_id: String, keyspaces: State<'_, HashSet<String>>) -> ListenerResult {
+async fn get_output(
+    _auth: Authorized<DataAccess>,
+    keyspace: String,
+    output_id: String,
+    keyspaces: State<'_, HashSet<String>>,
+    cache: State<'_, ResponseCache>,
+) -> Result<CachedResponse, ListenerError> {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
     let output_id = OutputId::from_str(&output_id).map_err(|e| ListenerError::BadParse(e.into()))?;
+    let key = format!("{}/outputs/{}", keyspace, output_id);
+    // the output is final once it got spent by an included message
+    cache.get_or_fetch("output", key, output(keyspace, output_id)).await
+}
 
+async fn output(keyspace: String, output_id: OutputId) -> Result<(ListenerResponse, bool), ListenerError> {
     let output_data = query::<OutputRes, _, _>(ChronicleKeyspace::new(keyspace.clone()), output_id, None, None).await?;
     let is_spent = if output_data.unlock_blocks.is_empty() {
         false
@@ -932,22 +1224,25 @@ async fn get_output(keyspace: String, output_id: String, keyspaces: State<'_, Ha
         }
         is_spent
     };
-    Ok(ListenerResponse::Output {
+    let response = ListenerResponse::Output {
         message_id: output_data.message_id.to_string(),
         transaction_id: output_id.transaction_id().to_string(),
         output_index: output_id.index(),
         is_spent,
         output: output_data.output.borrow().into(),
-    })
+    };
+    Ok((response, is_spent))
 }
 
 #[get("/<keyspace>/transactions/ed25519/<address>?<ledger_none>&<ledger_included>&<ledger_conflicting>&<page_size>&<state>")]
 async fn get_transactions_for_address(
+    auth: Authorized<DataAccess>,
     keyspace: String,
     address: String,
     page_size: Option<usize>,
     state: Option<String>,
     partition_config: State<'_, PartitionConfig>,
+    api_config: State<'_, ApiConfig>,
     keyspaces: State<'_, HashSet<String>>,
     ledger_none: Option<bool>,
     ledger_included: Option<bool>,
@@ -973,14 +1268,16 @@ async fn get_transactions_for_address(
         .transpose()?;
 
     let ed25519_address = Ed25519Address::from_str(&address).map_err(|e| ListenerError::BadParse(e.into()))?;
-    let page_size = page_size.unwrap_or(100);
+    let page_size = page_size.unwrap_or(100).min(api_config.max_page_size);
 
+    let mut budget = QueryBudget::new(&api_config);
     let outputs = page_filtered(
         keyspace.clone(),
         Hint::address(ed25519_address.to_string()),
         page_size,
         &mut state,
         partition_config.borrow(),
+        &mut budget,
         ed25519_address,
         Some(|a: &Partitioned<AddressRecord>| {
             (ledger_included && a.ledger_inclusion_state == Some(LedgerInclusionState::Included))
@@ -989,6 +1286,7 @@ async fn get_transactions_for_address(
         }),
     )
     .await?;
+    auth.charge(&budget);
 
     let transactions = futures::stream::iter(outputs)
         .map(|o| (o, keyspace.clone()))
@@ -1010,6 +1308,7 @@ async fn get_transactions_for_address(
 
 #[get("/<keyspace>/transactions/<message_id>")]
 async fn get_transaction_for_message(
+    _auth: Authorized<DataAccess>,
     keyspace: String,
     message_id: String,
     keyspaces: State<'_, HashSet<String>>,
@@ -1034,6 +1333,7 @@ async fn get_transaction_for_message(
 
 #[get("/<keyspace>/transactions/<transaction_id>/included-message")]
 async fn get_transaction_included_message(
+    _auth: Authorized<DataAccess>,
     keyspace: String,
     transaction_id: String,
     keyspaces: State<'_, HashSet<String>>,
@@ -1052,23 +1352,39 @@ async fn get_transaction_included_message(
 }
 
 #[get("/<keyspace>/milestones/<index>")]
-async fn get_milestone(keyspace: String, index: u32, keyspaces: State<'_, HashSet<String>>) -> ListenerResult {
+async fn get_milestone(
+    _auth: Authorized<DataAccess>,
+    keyspace: String,
+    index: u32,
+    keyspaces: State<'_, HashSet<String>>,
+    cache: State<'_, ResponseCache>,
+) -> Result<CachedResponse, ListenerError> {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
+    let key = format!("{}/milestones/{}", keyspace, index);
     let keyspace = ChronicleKeyspace::new(keyspace);
 
-    query::<Milestone, _, _>(keyspace, MilestoneIndex::from(index), None, None)
-        .await
-        .map(|milestone| ListenerResponse::Milestone {
-            milestone_index: index,
-            message_id: milestone.message_id().to_string(),
-            timestamp: milestone.timestamp(),
+    // milestones are only stored once confirmed
+    cache
+        .get_or_fetch("milestone", key, async {
+            query::<Milestone, _, _>(keyspace, MilestoneIndex::from(index), None, None)
+                .await
+                .map(|milestone| {
+                    let response = ListenerResponse::Milestone {
+                        milestone_index: index,
+                        message_id: milestone.message_id().to_string(),
+                        timestamp: milestone.timestamp(),
+                    };
+                    (response, true)
+                })
         })
+        .await
 }
 
 #[get("/<keyspace>/analytics?<start>&<end>")]
 async fn get_analytics(
+    _auth: Authorized<AnalyticsAccess>,
     keyspace: String,
     start: Option<u32>,
     end: Option<u32>,
@@ -1098,9 +1414,31 @@ fn not_found() -> ListenerError {
     ListenerError::NotFound
 }
 
+#[catch(401)]
+fn unauthorized() -> ListenerError {
+    ListenerError::Unauthorized
+}
+
+#[catch(403)]
+fn forbidden() -> ListenerError {
+    ListenerError::Forbidden
+}
+
+#[catch(429)]
+fn too_many_requests(request: &Request) -> ListenerError {
+    let RetryAfter(retry_after) = *request.local_cache(|| RetryAfter(None));
+    ListenerError::TooManyRequests(retry_after.unwrap_or(1))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chronicle_common::config::{
+        ApiKey,
+        ApiPermission,
+        ApiScope,
+        AuthConfig,
+    };
     use rocket::{
         http::{
             ContentType,
@@ -1115,9 +1453,13 @@ mod tests {
     use serde_json::Value;
 
     async fn construct_client() -> Client {
+        construct_client_with(&ApiConfig::default()).await
+    }
+
+    async fn construct_client_with(api_config: &ApiConfig) -> Client {
         let mut keyspaces = HashSet::new();
         keyspaces.insert("permanode".to_string());
-        let rocket = construct_rocket(rocket::ignite())
+        let rocket = construct_rocket(rocket::ignite(), api_config)
             .manage(PartitionConfig::default())
             .manage(keyspaces);
         Client::tracked(rocket).await.expect("Invalid rocket instance!")
@@ -1134,7 +1476,7 @@ mod tests {
         );
         assert_eq!(
             res.headers().get_one("Access-Control-Allow-Headers"),
-            Some(Header::new("Access-Control-Allow-Headers", "*").value())
+            Some(Header::new("Access-Control-Allow-Headers", ALLOW_HEADERS).value())
         );
         assert_eq!(
             res.headers().get_one("Access-Control-Allow-Credentials"),
@@ -1153,6 +1495,180 @@ mod tests {
         assert!(res.into_string().await.is_none());
     }
 
+    #[rocket::async_test]
+    async fn allowed_origins() {
+        let api_config = ApiConfig {
+            allowed_origins: vec!["https://explorer.iota.org".to_owned()],
+            ..Default::default()
+        };
+        let client = construct_client_with(&api_config).await;
+
+        let res = client
+            .options("/api/anything")
+            .header(Header::new("Origin", "https://explorer.iota.org"))
+            .dispatch()
+            .await;
+        assert_eq!(
+            res.headers().get_one("Access-Control-Allow-Origin"),
+            Some("https://explorer.iota.org")
+        );
+        assert_eq!(res.headers().get_one("Vary"), Some("Origin"));
+
+        let res = client
+            .options("/api/anything")
+            .header(Header::new("Origin", "https://example.com"))
+            .dispatch()
+            .await;
+        assert_eq!(res.headers().get_one("Access-Control-Allow-Origin"), None);
+    }
+
+    #[rocket::async_test]
+    async fn disabled_route_group() {
+        let mut api_config = ApiConfig::default();
+        api_config.route_groups.remove(&ApiRouteGroup::Info);
+        let client = construct_client_with(&api_config).await;
+
+        let res = client.get("/api/service").dispatch().await;
+        assert_eq!(res.status(), Status::NotFound);
+    }
+
+    #[rocket::async_test]
+    async fn authentication() {
+        let scope = ApiScope {
+            keyspaces: std::iter::once("permanode".to_owned()).collect(),
+            permissions: std::iter::once(ApiPermission::Data).collect(),
+        };
+        let api_config = ApiConfig {
+            auth: Some(AuthConfig {
+                api_keys: vec![ApiKey {
+                    name: "partner".to_owned(),
+                    key: "0123456789abcdef".to_owned(),
+                    scope,
+                }],
+                jwt_secret: Some("0123456789abcdef0123456789abcdef".to_owned()),
+                anonymous_scope: None,
+            }),
+            ..Default::default()
+        };
+        let client = construct_client_with(&api_config).await;
+
+        let res = client.get("/api/permanode/info").dispatch().await;
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .get("/api/permanode/info")
+            .header(Header::new("X-API-Key", "0123456789abcdef"))
+            .dispatch()
+            .await;
+        assert_eq!(res.status(), Status::Ok);
+
+        let res = client
+            .get("/api/service")
+            .header(Header::new("X-API-Key", "0123456789abcdef"))
+            .dispatch()
+            .await;
+        assert_eq!(res.status(), Status::Forbidden);
+
+        let claims = serde_json::json!({
+            "sub": "partner",
+            "exp": SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs() + 60,
+            "keyspaces": ["other"],
+            "permissions": ["Data"],
+        });
+        let token = jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &claims,
+            &jsonwebtoken::EncodingKey::from_secret(b"0123456789abcdef0123456789abcdef"),
+        )
+        .unwrap();
+        let res = client
+            .get("/api/permanode/info")
+            .header(Header::new("Authorization", format!("Bearer {}", token)))
+            .dispatch()
+            .await;
+        assert_eq!(res.status(), Status::Forbidden);
+    }
+
+    #[rocket::async_test]
+    async fn admin_routes() {
+        let client = construct_client().await;
+        let res = client.post("/api/admin/config/rollback").dispatch().await;
+        assert_eq!(res.status(), Status::NotFound);
+
+        let api_key = |key: &str, permission| ApiKey {
+            name: key.to_owned(),
+            key: key.to_owned(),
+            scope: ApiScope {
+                keyspaces: std::iter::once("*".to_owned()).collect(),
+                permissions: std::iter::once(permission).collect(),
+            },
+        };
+        let mut api_config = ApiConfig {
+            auth: Some(AuthConfig {
+                api_keys: vec![
+                    api_key("data-0123456789ab", ApiPermission::Data),
+                    api_key("admin-0123456789a", ApiPermission::Admin),
+                ],
+                jwt_secret: None,
+                anonymous_scope: None,
+            }),
+            ..Default::default()
+        };
+        api_config.route_groups.insert(ApiRouteGroup::Admin);
+        let client = construct_client_with(&api_config).await;
+
+        let res = client.options("/api/admin/feeds").dispatch().await;
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(
+            res.headers().get_one("Access-Control-Allow-Methods"),
+            Some("GET, POST, DELETE, OPTIONS")
+        );
+        assert_eq!(
+            res.headers().get_one("Access-Control-Allow-Headers"),
+            Some(ALLOW_HEADERS)
+        );
+
+        let res = client.post("/api/admin/imports").body("{}").dispatch().await;
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        let res = client
+            .post("/api/admin/imports")
+            .header(Header::new("X-API-Key", "data-0123456789ab"))
+            .body("{}")
+            .dispatch()
+            .await;
+        assert_eq!(res.status(), Status::Forbidden);
+
+        // an import needs either a path or an url
+        let res = client
+            .post("/api/admin/imports")
+            .header(ContentType::JSON)
+            .header(Header::new("X-API-Key", "admin-0123456789a"))
+            .body("{}")
+            .dispatch()
+            .await;
+        assert_eq!(res.status(), Status::BadRequest);
+    }
+
+    #[rocket::async_test]
+    async fn rate_limit() {
+        let api_config = ApiConfig {
+            rate_limit: Some(RateLimitConfig {
+                requests_per_sec: 1,
+                burst: 1,
+            }),
+            ..Default::default()
+        };
+        let client = construct_client_with(&api_config).await;
+
+        let res = client.get("/api/service").dispatch().await;
+        assert_eq!(res.status(), Status::Ok);
+
+        let res = client.get("/api/service").dispatch().await;
+        assert_eq!(res.status(), Status::TooManyRequests);
+        assert_eq!(res.headers().get_one("Retry-After"), Some("1"));
+    }
+
     #[rocket::async_test]
     async fn info() {
         let client = construct_client().await;
@@ -1182,6 +1698,167 @@ mod tests {
             .expect("Failed to deserialize Service Response!");
     }
 
+    #[rocket::async_test]
+    async fn import() {
+        let client = construct_client().await;
+
+        let res = client.get("/api/import").dispatch().await;
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(res.content_type(), Some(ContentType::JSON));
+        check_cors_headers(&res);
+        let _body: ImportReport = serde_json::from_str(&res.into_string().await.expect("No body returned!"))
+            .expect("Failed to deserialize Import Response!");
+    }
+
+    #[rocket::async_test]
+    async fn syncer() {
+        let client = construct_client().await;
+
+        let res = client.get("/api/syncer").dispatch().await;
+        // the syncer is not running within the listener tests
+        assert_eq!(res.status(), Status::NotFound);
+        assert_eq!(res.content_type(), Some(ContentType::JSON));
+        check_cors_headers(&res);
+    }
+
+    #[get("/<keyspace>/cached/<index>?<is_final>")]
+    async fn cached(
+        keyspace: String,
+        index: u32,
+        is_final: bool,
+        cache: State<'_, ResponseCache>,
+    ) -> Result<CachedResponse, ListenerError> {
+        let key = format!("{}/cached/{}", keyspace, index);
+        cache
+            .get_or_fetch("cached", key, async {
+                let response = ListenerResponse::Milestone {
+                    milestone_index: index,
+                    message_id: keyspace,
+                    timestamp: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap()
+                        .as_nanos() as u64,
+                };
+                Ok((response, is_final))
+            })
+            .await
+    }
+
+    #[rocket::async_test]
+    async fn response_cache() {
+        let rocket = construct_rocket(rocket::ignite(), &ApiConfig::default()).mount("/test", routes![cached]);
+        let client = Client::tracked(rocket).await.expect("Invalid rocket instance!");
+
+        let res = client.get("/test/permanode/cached/1?is_final=true").dispatch().await;
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(
+            res.headers().get_one("Cache-Control"),
+            Some("public, max-age=3600, immutable")
+        );
+        let etag = res.headers().get_one("ETag").expect("No ETag returned!").to_owned();
+        let body = res.into_string().await;
+
+        // the final response is served from the cache
+        let res = client.get("/test/permanode/cached/1?is_final=true").dispatch().await;
+        assert_eq!(res.headers().get_one("ETag"), Some(etag.as_str()));
+        assert_eq!(res.into_string().await, body);
+
+        let res = client
+            .get("/test/permanode/cached/1?is_final=true")
+            .header(Header::new("If-None-Match", etag))
+            .dispatch()
+            .await;
+        assert_eq!(res.status(), Status::NotModified);
+        assert!(res.into_string().await.is_none());
+
+        // the other responses are not cached
+        let res = client.get("/test/permanode/cached/2?is_final=false").dispatch().await;
+        assert_eq!(res.headers().get_one("Cache-Control"), Some("no-cache"));
+        let body = res.into_string().await;
+        let res = client.get("/test/permanode/cached/2?is_final=false").dispatch().await;
+        assert_ne!(res.into_string().await, body);
+    }
+
+    #[rocket::async_test]
+    async fn private_response_cache() {
+        let api_config = ApiConfig {
+            auth: Some(AuthConfig {
+                api_keys: vec![],
+                jwt_secret: None,
+                anonymous_scope: Some(ApiScope {
+                    keyspaces: std::iter::once("public".to_owned()).collect(),
+                    permissions: std::iter::once(ApiPermission::Data).collect(),
+                }),
+            }),
+            ..Default::default()
+        };
+        let rocket = construct_rocket(rocket::ignite(), &api_config).mount("/test", routes![cached]);
+        let client = Client::tracked(rocket).await.expect("Invalid rocket instance!");
+
+        // the anonymous requests can't read the partner keyspace, so the shared caches must not store it
+        let res = client.get("/test/partner/cached/1?is_final=true").dispatch().await;
+        assert_eq!(
+            res.headers().get_one("Cache-Control"),
+            Some("private, max-age=3600, immutable")
+        );
+        assert_eq!(res.headers().get_one("Vary"), Some("Authorization, X-API-Key"));
+
+        let res = client.get("/test/public/cached/1?is_final=true").dispatch().await;
+        assert_eq!(
+            res.headers().get_one("Cache-Control"),
+            Some("public, max-age=3600, immutable")
+        );
+        assert_eq!(res.headers().get_one("Vary"), None);
+    }
+
+    #[rocket::async_test]
+    async fn query_budget() {
+        // a record per partition, each within a chunk of its own
+        let records: HashMap<PartitionId, u32> = vec![(0, 10), (1, 9), (2, 8), (3, 7)].into_iter().collect();
+        let partition_ids = vec![
+            (MilestoneIndex(10), 0),
+            (MilestoneIndex(9), 1),
+            (MilestoneIndex(8), 2),
+            (MilestoneIndex(7), 3),
+        ];
+        let api_config = ApiConfig {
+            max_query_partitions: 2,
+            ..Default::default()
+        };
+        let mut state: Option<StateData> = Some((None, None, None, partition_ids).into());
+        let mut pages = Vec::new();
+        while let Some((latest_milestone, partition_ids)) = state
+            .as_ref()
+            .map(|state| (state.last_milestone_index.unwrap_or(10), state.partition_ids.clone()))
+        {
+            assert!(pages.len() < 4, "The pages don't make progress!");
+            let mut budget = QueryBudget::new(&api_config);
+            let page = page_partitions(
+                &mut state,
+                latest_milestone,
+                partition_ids,
+                10,
+                1,
+                &mut budget,
+                None::<fn(&Partitioned<u32>) -> bool>,
+                |partition_id, _, _| {
+                    let list = records
+                        .get(&partition_id)
+                        .filter(|milestone_index| **milestone_index <= latest_milestone)
+                        .map(|milestone_index| Partitioned::new(*milestone_index, partition_id, *milestone_index))
+                        .into_iter()
+                        .collect::<VecDeque<_>>();
+                    async move { Ok::<_, ListenerError>(Paged::new(list, None)) }
+                },
+            )
+            .await
+            .expect("Failed to page the partitions!");
+            pages.push(page.iter().map(|record| record.milestone_index()).collect::<Vec<_>>());
+        }
+        // the budget of two partitions cuts the first page short, and the second one resumes it
+        assert_eq!(pages, vec![vec![10, 9], vec![8, 7]]);
+    }
+
     #[rocket::async_test]
     async fn get_message() {
         let client = construct_client().await;
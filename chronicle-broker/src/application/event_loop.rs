@@ -2,8 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use chronicle_common::get_history_mut;
+use chronicle_common::{
+    get_config_file,
+    get_history_mut,
+    metrics::MQTT_RECONNECTS,
+};
 use futures::SinkExt;
+use rand::Rng;
+
+/// The upper bound of the mqtt reconnect backoff
+const MAX_MQTT_RECONNECT_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+/// The backoff doubled for every consecutive reconnect up to its upper bound, then jittered by ±50%
+fn jittered_backoff(backoff: Duration, reconnects: u32) -> Duration {
+    backoff
+        .saturating_mul(2u32.saturating_pow(reconnects))
+        .min(MAX_MQTT_RECONNECT_BACKOFF)
+        .mul_f64(rand::thread_rng().gen_range(0.5..=1.5))
+}
 
 #[async_trait]
 impl<H: ChronicleBrokerScope> EventLoop<H> for ChronicleBroker<H> {
@@ -14,95 +30,49 @@ impl<H: ChronicleBrokerScope> EventLoop<H> for ChronicleBroker<H> {
             while let Some(event) = self.inbox.recv().await {
                 match event {
                     BrokerEvent::Importer(importer_session) => {
+                        let progress = match importer_session {
+                            ImporterSession::ProgressBar {
+                                log_file_size,
+                                from_ms,
+                                to_ms,
+                                ms_bytes_size,
+                                skipped,
+                                ..
+                            } => {
+                                self.import_progress.schedule_file(from_ms, to_ms, log_file_size);
+                                self.import_progress.progress(ms_bytes_size, skipped);
+                                true
+                            }
+                            _ => false,
+                        };
                         let socket_msg = BrokerSocketMsg::ChronicleBroker(importer_session);
                         self.response_to_sockets(&socket_msg).await;
+                        if progress {
+                            self.report_import_progress(false).await;
+                        }
                     }
-                    BrokerEvent::Passthrough(passthrough_events) => {
-                        match passthrough_events.try_get_my_event() {
-                            Ok(my_event) => match my_event {
-                                ChronicleBrokerThrough::Shutdown => {
-                                    self.shutdown(supervisor, true).await;
-                                    // ensure to drop handle
-                                    self.handle.take();
-                                }
-                                ChronicleBrokerThrough::Topology(mut topology) => {
-                                    if self.service.is_stopping() {
-                                        // response that should not change the topology while is_stopping
-                                        error!("Not supposed to dynamically change the topology while broker service is_stopped");
-                                        let socket_msg = BrokerSocketMsg::ChronicleBroker(Err(topology));
-                                        self.response_to_sockets::<Result<BrokerTopology, BrokerTopology>>(&socket_msg)
-                                            .await;
-                                        continue;
-                                    }
-                                    match topology {
-                                        BrokerTopology::AddMqttMessages(url) => {
-                                            if let Some(mqtt) = self.add_mqtt(Messages, MqttType::Messages, url) {
-                                                tokio::spawn(mqtt.start(self.handle.clone()));
-                                            }
-                                        }
-                                        BrokerTopology::AddMqttMessagesReferenced(url) => {
-                                            if let Some(mqtt) =
-                                                self.add_mqtt(MessagesReferenced, MqttType::MessagesReferenced, url)
-                                            {
-                                                tokio::spawn(mqtt.start(self.handle.clone()));
-                                            }
-                                        }
-                                        BrokerTopology::RemoveMqttMessagesReferenced(url) => {
-                                            self.remove_mqtt::<MessagesReferenced>(MqttType::MessagesReferenced, url)
-                                        }
-                                        BrokerTopology::RemoveMqttMessages(url) => {
-                                            self.remove_mqtt::<Messages>(MqttType::Messages, url)
-                                        }
-                                        BrokerTopology::Import { .. } => {
-                                            self.handle_import(topology).await;
-                                            self.try_close_importer_session().await;
-                                        }
-                                        BrokerTopology::Requesters(ref mut requester_topology) => {
-                                            match requester_topology {
-                                                RequesterTopology::AddEndpoint(ref url) => {
-                                                    let reqwest_client = reqwest::Client::new();
-                                                    if let Some(url) = BrokerConfig::adjust_api_endpoint(url.clone()) {
-                                                        if let Err(e) =
-                                                            BrokerConfig::verify_endpoint(&reqwest_client, &url).await
-                                                        {
-                                                            error!("{}", e);
-                                                            let socket_msg =
-                                                                BrokerSocketMsg::ChronicleBroker(Err(topology.clone()));
-                                                            self.response_to_sockets::<Result<BrokerTopology, BrokerTopology>>(&socket_msg).await;
-                                                        } else {
-                                                            *requester_topology =
-                                                                RequesterTopology::AddEndpoint(url.clone());
-                                                            self.collector_handles.values().for_each(|h| {
-                                                                h.send_requester_topology(requester_topology.clone());
-                                                            });
-                                                            let socket_msg =
-                                                                BrokerSocketMsg::ChronicleBroker(Ok(topology.clone()));
-                                                            self.response_to_sockets::<Result<BrokerTopology, BrokerTopology>>(&socket_msg).await;
-                                                        }
-                                                    } else {
-                                                        let socket_msg =
-                                                            BrokerSocketMsg::ChronicleBroker(Err(topology.clone()));
-                                                        self.response_to_sockets::<Result<BrokerTopology, BrokerTopology>>(&socket_msg).await;
-                                                    };
-                                                }
-                                                RequesterTopology::RemoveEndpoint(_) => {
-                                                    self.collector_handles.values().for_each(|h| {
-                                                        h.send_requester_topology(requester_topology.clone());
-                                                    });
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                                ChronicleBrokerThrough::ExitProgram => {
-                                    supervisor.exit_program(false);
-                                }
-                            },
-                            Err(other_app_event) => {
-                                supervisor.passthrough(other_app_event, self.get_name());
+                    BrokerEvent::Exporter(exporter_session) => {
+                        let socket_msg = BrokerSocketMsg::ChronicleBroker(exporter_session);
+                        self.response_to_sockets(&socket_msg).await;
+                    }
+                    BrokerEvent::Passthrough(passthrough_events) => match passthrough_events.try_get_my_event() {
+                        Ok(ChronicleBrokerThrough::Request { id, command }) => {
+                            let result = self.handle_through(*command, supervisor).await;
+                            if let Err(e) = result.as_ref() {
+                                error!("Request: {}, failed: {}", id, e);
+                            }
+                            let socket_msg = BrokerSocketMsg::ChronicleBroker(ControlReply { id, result });
+                            self.response_to_sockets(&socket_msg).await;
+                        }
+                        Ok(my_event) => {
+                            if let Err(e) = self.handle_through(my_event, supervisor).await {
+                                error!("{}", e);
                             }
                         }
-                    }
+                        Err(other_app_event) => {
+                            supervisor.passthrough(other_app_event, self.get_name());
+                        }
+                    },
                     BrokerEvent::Scylla(service) => {
                         if let Err(Need::Restart) = status.as_ref() {
                             if service.is_running() {
@@ -121,15 +91,20 @@ impl<H: ChronicleBrokerScope> EventLoop<H> for ChronicleBroker<H> {
                             BrokerChild::Collector(service) => {
                                 self.service.update_microservice(service.get_name(), service.clone());
                             }
-                            BrokerChild::Importer(service, _status, parallelism) => {
+                            BrokerChild::Importer(service, importer_status, parallelism) => {
                                 if service.is_stopped() {
+                                    if importer_status.is_err() {
+                                        self.import_progress.failure();
+                                    }
                                     self.in_progress_importers -= 1;
                                     self.service.delete_microservice(&service.get_name());
                                     // return parallelism
                                     self.parallelism_points += parallelism;
                                     // check if there are any pending
                                     if let Some(import_topology) = self.pending_imports.pop() {
-                                        self.handle_import(import_topology).await;
+                                        if let Err(e) = self.handle_import(import_topology).await {
+                                            error!("Unable to start the pending import: {}", e);
+                                        }
                                     }
                                     // remove its handle from importer_handles
                                     self.importer_handles.remove(&service.get_name());
@@ -139,6 +114,14 @@ impl<H: ChronicleBrokerScope> EventLoop<H> for ChronicleBroker<H> {
                                     self.service.update_microservice(service.get_name(), service.clone());
                                 }
                             }
+                            BrokerChild::Exporter(service, _status) => {
+                                if service.is_stopped() {
+                                    self.service.delete_microservice(&service.get_name());
+                                    self.exporter_handles.remove(&service.get_name());
+                                } else {
+                                    self.service.update_microservice(service.get_name(), service.clone());
+                                }
+                            }
                             BrokerChild::Solidifier(service, solidifier_status) => {
                                 // Handle abort
                                 if let Err(Need::Abort) = solidifier_status {
@@ -188,6 +171,8 @@ impl<H: ChronicleBrokerScope> EventLoop<H> for ChronicleBroker<H> {
                                 match mqtt_status {
                                     Ok(()) => {
                                         if let Some(mqtt_handle) = mqtt_handle_opt {
+                                            // connected and subscribed, so the reconnect backoff starts over
+                                            self.mqtt_reconnects.remove(&microservice_name);
                                             if !self.service.is_stopping() {
                                                 self.mqtt_handles.insert(service.get_name(), mqtt_handle);
                                             } else {
@@ -211,33 +196,23 @@ impl<H: ChronicleBrokerScope> EventLoop<H> for ChronicleBroker<H> {
                                         if !self.service.is_stopping() && service.is_stopped() && !asked_to_shutdown {
                                             // restart it by re-adding it, first we delete it
                                             self.service.delete_microservice(&microservice_name);
-                                            // extract the url and topic from the name (topic@url)
-                                            let mut name = microservice_name.split("@");
-                                            let topic = name.next().unwrap();
-                                            let url = Url::parse(name.next().unwrap()).unwrap();
-                                            let restart_after = std::time::Duration::from_secs(5);
+                                            // extract the kind, url and topic from the name (topic@url)
+                                            let (kind, topic, url) =
+                                                FeedKind::parse_feed_name(&microservice_name).unwrap();
+                                            let restart_after = self.mqtt_reconnect_backoff(&microservice_name);
                                             warn!("Restarting Mqtt: {}, after: {:?}", microservice_name, restart_after);
-                                            match Topics::try_from(topic).unwrap() {
-                                                Topics::Messages => {
-                                                    let new_mqtt =
-                                                        self.add_mqtt(Messages, MqttType::Messages, url).unwrap();
-                                                    tokio::spawn(
-                                                        new_mqtt.start_after(restart_after, self.handle.clone()),
-                                                    );
-                                                }
-                                                Topics::MessagesReferenced => {
-                                                    let new_mqtt = self
-                                                        .add_mqtt(MessagesReferenced, MqttType::MessagesReferenced, url)
-                                                        .unwrap();
-                                                    tokio::spawn(
-                                                        new_mqtt.start_after(restart_after, self.handle.clone()),
-                                                    );
-                                                }
-                                            }
+                                            MQTT_RECONNECTS.with_label_values(&[topic, url.as_str()]).inc();
+                                            let mqtt_type = match Topics::try_from(topic).unwrap() {
+                                                Topics::Messages => MqttType::Messages,
+                                                Topics::MessagesReferenced => MqttType::MessagesReferenced,
+                                                Topics::LatestMilestone => MqttType::LatestMilestone,
+                                            };
+                                            self.start_feed(kind, mqtt_type, url, Some(restart_after));
                                         } else if asked_to_shutdown && service.is_stopped() {
                                             self.service.delete_microservice(&microservice_name);
                                             // remove it from asked_to_shutdown, only once the service.is_stopped
                                             self.asked_to_shutdown.remove(&microservice_name);
+                                            self.mqtt_reconnects.remove(&microservice_name);
                                         }
                                     }
                                 }
@@ -274,16 +249,205 @@ impl<H: ChronicleBrokerScope> EventLoop<H> for ChronicleBroker<H> {
 }
 
 impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
-    pub(crate) fn remove_mqtt<T: Topic>(&mut self, mqtt_type: MqttType, url: Url) {
-        let microservice_name = format!("{}@{}", T::name(), url.as_str());
+    /// Handle the command received through the websocket, returns the reason it failed if any
+    async fn handle_through(&mut self, through: ChronicleBrokerThrough, supervisor: &mut H) -> Result<(), String> {
+        match through {
+            ChronicleBrokerThrough::Shutdown => {
+                self.shutdown(supervisor, true).await;
+                // ensure to drop handle
+                self.handle.take();
+            }
+            ChronicleBrokerThrough::Topology(topology) => return self.handle_topology(topology).await,
+            ChronicleBrokerThrough::ExitProgram => {
+                supervisor.exit_program(false);
+            }
+            ChronicleBrokerThrough::RequeueUnreachable(milestone_indexes) => {
+                let syncer_handle = self.syncer_handle.as_ref().ok_or("The syncer is not running")?;
+                let _ = syncer_handle.send(SyncerEvent::Requeue(milestone_indexes));
+            }
+            ChronicleBrokerThrough::SyncerStatus => {
+                let status = SYNCER_STATUS.read().await.clone();
+                let socket_msg = BrokerSocketMsg::ChronicleBroker(SyncerSession::Status(status));
+                self.response_to_sockets(&socket_msg).await;
+            }
+            ChronicleBrokerThrough::EndpointsHealth => {
+                let endpoints = health::endpoints_status().await;
+                let socket_msg = BrokerSocketMsg::ChronicleBroker(RequesterSession::Endpoints(endpoints));
+                self.response_to_sockets(&socket_msg).await;
+            }
+            ChronicleBrokerThrough::FeedsReport => {
+                let feeds = feeds::feeds_status().await;
+                let socket_msg = BrokerSocketMsg::ChronicleBroker(FeedSession::Report(feeds));
+                self.response_to_sockets(&socket_msg).await;
+            }
+            ChronicleBrokerThrough::Request { id, .. } => {
+                return Err(format!("The request: {} is nested in another request", id));
+            }
+        }
+        Ok(())
+    }
+    /// Alter the topology of the broker, returns the reason it failed if any
+    async fn handle_topology(&mut self, mut topology: BrokerTopology) -> Result<(), String> {
+        if self.service.is_stopping() {
+            // response that should not change the topology while is_stopping
+            let socket_msg = BrokerSocketMsg::ChronicleBroker(Err(topology));
+            self.response_to_sockets::<Result<BrokerTopology, BrokerTopology>>(&socket_msg)
+                .await;
+            return Err("Not supposed to dynamically change the topology while broker service is_stopped".to_owned());
+        }
+        match topology {
+            BrokerTopology::AddMqttMessages(url) => {
+                self.add_feeds(FeedKind::Mqtt, &[MqttType::Messages], url)?;
+            }
+            BrokerTopology::AddMqttMessagesReferenced(url) => {
+                self.add_feeds(FeedKind::Mqtt, &[MqttType::MessagesReferenced], url)?;
+            }
+            BrokerTopology::RemoveMqttMessagesReferenced(url) => {
+                self.remove_feeds(FeedKind::Mqtt, &[MqttType::MessagesReferenced], url)?;
+            }
+            BrokerTopology::RemoveMqttMessages(url) => {
+                self.remove_feeds(FeedKind::Mqtt, &[MqttType::Messages], url)?;
+            }
+            BrokerTopology::AddMqttLatestMilestone(url) => {
+                self.add_feeds(FeedKind::Mqtt, &[MqttType::LatestMilestone], url)?;
+            }
+            BrokerTopology::RemoveMqttLatestMilestone(url) => {
+                self.remove_feeds(FeedKind::Mqtt, &[MqttType::LatestMilestone], url)?;
+            }
+            BrokerTopology::AddEventStream(url) => {
+                Self::verify_event_stream(&url).map_err(|e| e.to_string())?;
+                self.add_feeds(
+                    FeedKind::EventStream,
+                    &[
+                        MqttType::Messages,
+                        MqttType::MessagesReferenced,
+                        MqttType::LatestMilestone,
+                    ],
+                    url,
+                )?;
+            }
+            BrokerTopology::RemoveEventStream(url) => {
+                self.remove_feeds(
+                    FeedKind::EventStream,
+                    &[
+                        MqttType::Messages,
+                        MqttType::MessagesReferenced,
+                        MqttType::LatestMilestone,
+                    ],
+                    url,
+                )?;
+            }
+            BrokerTopology::Import { .. } | BrokerTopology::ImportRemote { .. } => {
+                // the import progress is reported through the importer sessions
+                let started = self.handle_import(topology).await;
+                self.try_close_importer_session().await;
+                started?;
+            }
+            BrokerTopology::Export { path, export_range } => {
+                self.spawn_exporter(path, export_range).await?;
+            }
+            BrokerTopology::Requesters(ref mut requester_topology) => match requester_topology {
+                RequesterTopology::AddEndpoint(ref url) => {
+                    let reqwest_client = reqwest::Client::new();
+                    let verified = match BrokerConfig::adjust_api_endpoint(url.clone()) {
+                        Some(url) => BrokerConfig::verify_endpoint(&reqwest_client, &url)
+                            .await
+                            .map(|_| url)
+                            .map_err(|e| e.to_string()),
+                        None => Err(format!("Invalid api endpoint: {}", url)),
+                    };
+                    match verified {
+                        Ok(url) => {
+                            *requester_topology = RequesterTopology::AddEndpoint(url.clone());
+                            health::register_endpoints(std::iter::once(&url)).await;
+                            self.collector_handles.values().for_each(|h| {
+                                h.send_requester_topology(requester_topology.clone());
+                            });
+                            // retry the unreachable milestones against the new endpoint
+                            if let Some(syncer_handle) = self.syncer_handle.as_ref() {
+                                let _ = syncer_handle.send(SyncerEvent::Requeue(None));
+                            }
+                            let socket_msg = BrokerSocketMsg::ChronicleBroker(Ok(topology.clone()));
+                            self.response_to_sockets::<Result<BrokerTopology, BrokerTopology>>(&socket_msg)
+                                .await;
+                        }
+                        Err(e) => {
+                            let socket_msg = BrokerSocketMsg::ChronicleBroker(Err(topology.clone()));
+                            self.response_to_sockets::<Result<BrokerTopology, BrokerTopology>>(&socket_msg)
+                                .await;
+                            return Err(e);
+                        }
+                    }
+                }
+                RequesterTopology::RemoveEndpoint(_) => {
+                    self.collector_handles.values().for_each(|h| {
+                        h.send_requester_topology(requester_topology.clone());
+                    });
+                }
+            },
+            BrokerTopology::UpdateSyncRange(range) => {
+                if range.start == 0 || range.start >= range.end {
+                    return Err(format!("Invalid sync range: {:?}", range));
+                }
+                let sync_range = SyncRange::from(range);
+                self.sync_range = sync_range;
+                if let Some(syncer_handle) = self.syncer_handle.as_ref() {
+                    let _ = syncer_handle.send(SyncerEvent::UpdateSyncRange(sync_range));
+                }
+                let config = get_config_file();
+                let mut new_config = config.clone();
+                new_config.broker_config.sync_range.replace(sync_range);
+                if new_config != config {
+                    get_history_mut().update(new_config.into());
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Add and start the feeds of the given types from the source, fails if they all exist already
+    fn add_feeds(&mut self, kind: FeedKind, mqtt_types: &[MqttType], url: Url) -> Result<(), String> {
+        let added = mqtt_types
+            .iter()
+            .filter(|mqtt_type| self.start_feed(kind, **mqtt_type, url.clone(), None))
+            .count();
+        if added == 0 {
+            return Err(format!("The feed source: {}, you're trying to add already exists", url));
+        }
+        Ok(())
+    }
+    /// Remove the feeds of the given types from the source, fails if none of them exists
+    fn remove_feeds(&mut self, kind: FeedKind, mqtt_types: &[MqttType], url: Url) -> Result<(), String> {
+        let removed = mqtt_types
+            .iter()
+            .filter(|mqtt_type| match mqtt_type {
+                MqttType::Messages => self.remove_mqtt::<Messages>(kind, **mqtt_type, url.clone()),
+                MqttType::MessagesReferenced => self.remove_mqtt::<MessagesReferenced>(kind, **mqtt_type, url.clone()),
+                MqttType::LatestMilestone => self.remove_mqtt::<LatestMilestone>(kind, **mqtt_type, url.clone()),
+            })
+            .count();
+        if removed == 0 {
+            return Err(format!(
+                "The feed source: {}, you're trying to remove doesn't exist",
+                url
+            ));
+        }
+        Ok(())
+    }
+    /// Shutdown the feed of the given type from the source, returns whether it exists
+    pub(crate) fn remove_mqtt<T: Topic>(&mut self, kind: FeedKind, mqtt_type: MqttType, url: Url) -> bool {
+        let microservice_name = kind.feed_name(T::name(), &url);
         if let Some(service) = self.service.microservices.get(&microservice_name) {
             // add it to asked_to_shutdown hashmap
             self.asked_to_shutdown.insert(microservice_name.clone(), ());
             if let Some(mqtt_handle) = self.mqtt_handles.remove(&microservice_name) {
                 mqtt_handle.shutdown();
-                let config = get_config();
+                let config = get_config_file();
                 let mut new_config = config.clone();
-                if let Some(list) = new_config.broker_config.mqtt_brokers.get_mut(&mqtt_type) {
+                let feeds = match kind {
+                    FeedKind::Mqtt => Some(&mut new_config.broker_config.mqtt_brokers),
+                    FeedKind::EventStream => new_config.broker_config.event_streams.as_mut(),
+                };
+                if let Some(list) = feeds.and_then(|feeds| feeds.get_mut(&mqtt_type)) {
                     list.remove(&url);
                 }
                 if new_config != config {
@@ -298,20 +462,88 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
                     service.service_status()
                 )
             };
+            true
         } else {
             // it doesn't exist
             error!(
                 "The Mqtt: {}, you're trying to remove, it doesn't exist as service",
                 microservice_name
             );
-            // Maybe TODO response with something?;
-        };
+            false
+        }
+    }
+    /// The jittered exponential backoff before reconnecting the mqtt, which grows with its consecutive reconnects
+    fn mqtt_reconnect_backoff(&mut self, microservice_name: &str) -> Duration {
+        let reconnects = self.mqtt_reconnects.entry(microservice_name.to_owned()).or_default();
+        let backoff_secs = get_config().broker_config.mqtt_reconnect_backoff_secs.unwrap_or(5);
+        let backoff = jittered_backoff(Duration::from_secs(backoff_secs), *reconnects);
+        *reconnects = reconnects.saturating_add(1);
+        backoff
+    }
+    /// Add the feed of the given type from the source, and start it (after the restart delay if any).
+    /// Returns whether it got added, ie it didn't exist already.
+    pub(crate) fn start_feed(
+        &mut self,
+        kind: FeedKind,
+        mqtt_type: MqttType,
+        url: Url,
+        restart_after: Option<Duration>,
+    ) -> bool {
+        match mqtt_type {
+            MqttType::Messages => {
+                if let Some(mqtt) = self.add_mqtt(Messages, kind, mqtt_type, url) {
+                    if let Some(restart_after) = restart_after {
+                        tokio::spawn(mqtt.start_after(restart_after, self.handle.clone()));
+                    } else {
+                        tokio::spawn(mqtt.start(self.handle.clone()));
+                    }
+                    return true;
+                }
+            }
+            MqttType::MessagesReferenced => {
+                if let Some(mqtt) = self.add_mqtt(MessagesReferenced, kind, mqtt_type, url) {
+                    if let Some(restart_after) = restart_after {
+                        tokio::spawn(mqtt.start_after(restart_after, self.handle.clone()));
+                    } else {
+                        tokio::spawn(mqtt.start(self.handle.clone()));
+                    }
+                    return true;
+                }
+            }
+            MqttType::LatestMilestone => {
+                if let Some(mqtt) = self.add_mqtt(LatestMilestone, kind, mqtt_type, url) {
+                    if let Some(restart_after) = restart_after {
+                        tokio::spawn(mqtt.start_after(restart_after, self.handle.clone()));
+                    } else {
+                        tokio::spawn(mqtt.start(self.handle.clone()));
+                    }
+                    return true;
+                }
+            }
+        }
+        false
+    }
+    /// Ensure the event stream url is a websocket or a recording one
+    fn verify_event_stream(url: &Url) -> anyhow::Result<()> {
+        ensure!(
+            matches!(url.scheme(), "ws" | "wss" | "file"),
+            "Unsupported event stream url: {}, expected a ws://, wss:// or file:// url",
+            url
+        );
+        Ok(())
     }
-    pub(crate) fn add_mqtt<T: Topic>(&mut self, topic: T, mqtt_type: MqttType, url: Url) -> Option<Mqtt<T>> {
+    pub(crate) fn add_mqtt<T: Topic>(
+        &mut self,
+        topic: T,
+        kind: FeedKind,
+        mqtt_type: MqttType,
+        url: Url,
+    ) -> Option<Mqtt<T>> {
         let config = get_config();
         let mqtt = MqttBuilder::new()
             .collectors_handles(self.collector_handles.clone())
             .topic(topic)
+            .kind(kind)
             .url(url.clone())
             .stream_capacity(config.broker_config.mqtt_stream_capacity)
             .build();
@@ -319,11 +551,25 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
         let microservice_name = microservice.get_name();
         if let None = self.service.microservices.get(&microservice_name) {
             self.service.update_microservice(microservice_name, microservice);
-            let mut new_config = config.clone();
-            if let Some(list) = new_config.broker_config.mqtt_brokers.get_mut(&mqtt_type) {
-                list.insert(url);
+            let config_file = get_config_file();
+            let mut new_config = config_file.clone();
+            match kind {
+                FeedKind::Mqtt => {
+                    if let Some(list) = new_config.broker_config.mqtt_brokers.get_mut(&mqtt_type) {
+                        list.insert(url);
+                    }
+                }
+                FeedKind::EventStream => {
+                    new_config
+                        .broker_config
+                        .event_streams
+                        .get_or_insert_with(HashMap::new)
+                        .entry(mqtt_type)
+                        .or_default()
+                        .insert(url);
+                }
             }
-            if new_config != config {
+            if new_config != config_file {
                 get_history_mut().update(new_config.into());
             }
             Some(mqtt)
@@ -333,53 +579,76 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
                 "The Mqtt: {}, you're trying to add it already exists as service",
                 microservice_name
             );
-            // TODO response with something;
             None
         }
     }
-    async fn handle_import(&mut self, import_topology: BrokerTopology) {
-        if let BrokerTopology::Import {
-            ref path,
-            resume,
-            ref import_range,
-            import_type,
-        } = import_topology
-        {
-            // don't do anything if the service is shutting down
-            if self.service.is_stopping() {
-                return ();
-            }
-            // check if we have enough parallelism points
-            if self.parallelism_points == 0 {
-                // add it to pending list
-                self.pending_imports.push(import_topology);
-                return ();
-            }
-            if path.is_file() {
-                // build importer
-                self.spawn_importer(
-                    path.clone(),
-                    resume,
-                    import_range.clone(),
-                    import_type,
-                    self.parallelism_points,
-                )
-                .await;
-            } else if path.is_dir() {
-                self.spawn_importers(path.clone(), resume, import_range.clone(), import_type)
-                    .await;
-            } else {
-                let event = ImporterSession::PathError {
-                    path: path.clone(),
-                    msg: "Invalid path".into(),
-                };
-                let socket_msg = BrokerSocketMsg::ChronicleBroker(event);
-                self.response_to_sockets(&socket_msg).await;
+    /// Start importing the LogFiles of the import topology, fails if none of them could be started
+    async fn handle_import(&mut self, import_topology: BrokerTopology) -> Result<(), String> {
+        // don't do anything if the service is shutting down
+        if self.service.is_stopping() {
+            return Err("The broker is stopping".to_owned());
+        }
+        // check if we have enough parallelism points
+        if self.parallelism_points == 0 {
+            // add it to pending list
+            self.pending_imports.push(import_topology);
+            return Ok(());
+        }
+        match import_topology {
+            BrokerTopology::Import {
+                path,
+                resume,
+                import_range,
+                import_type,
+            } => {
+                if path.is_file() {
+                    // build importer
+                    self.spawn_importer(path, None, resume, import_range, import_type, self.parallelism_points)
+                        .await
+                } else if path.is_dir() {
+                    self.spawn_importers(path, resume, import_range, import_type).await
+                } else {
+                    let msg = format!("Invalid path: {}", path.display());
+                    let event = ImporterSession::PathError {
+                        path,
+                        msg: "Invalid path".into(),
+                    };
+                    let socket_msg = BrokerSocketMsg::ChronicleBroker(event);
+                    self.response_to_sockets(&socket_msg).await;
+                    Err(msg)
+                }
+            }
+            BrokerTopology::ImportRemote {
+                url,
+                resume,
+                import_range,
+                import_type,
+            } => {
+                if url.path().ends_with(".log") {
+                    // build importer
+                    let file_path = self.remote_log_file_path(&url);
+                    self.spawn_importer(
+                        file_path,
+                        Some(url),
+                        resume,
+                        import_range,
+                        import_type,
+                        self.parallelism_points,
+                    )
+                    .await
+                } else {
+                    self.spawn_remote_importers(url, resume, import_range, import_type)
+                        .await
+                }
             }
+            _ => Ok(()),
         }
     }
     async fn try_close_importer_session(&mut self) {
         if self.in_progress_importers == 0 {
+            // publish the final report of the import session
+            self.report_import_progress(true).await;
+            self.import_progress.reset();
             let event = ImporterSession::Close;
             let socket_msg = BrokerSocketMsg::ChronicleBroker(event);
             self.response_to_sockets(&socket_msg).await;
@@ -388,6 +657,7 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
     fn build_and_start_importer<T: ImportMode>(
         &mut self,
         file_path: PathBuf,
+        remote_url: Option<Url>,
         resume: bool,
         import_range: Option<Range<u32>>,
         parallelism: u8,
@@ -396,12 +666,16 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
         if let Some(import_range) = import_range {
             importer_builder = importer_builder.import_range(import_range);
         };
+        if let Some(remote_url) = remote_url {
+            importer_builder = importer_builder.remote_url(remote_url);
+        };
         let importer = importer_builder
             .file_path(file_path)
             .resume(resume)
             .parallelism(parallelism)
             .retries_per_query(50) // TODO get it from config
-            .chronicle_id(0) // TODO get it from config
+            .chronicle_id(get_config().broker_config.chronicle_id.unwrap_or(0))
+            .node_latencies(self.node_latencies.clone())
             .build();
         let handle = importer.clone_handle().expect("Expected existing importer handle");
         self.importer_handles.insert(importer.get_name(), handle);
@@ -412,37 +686,54 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
     async fn spawn_importer(
         &mut self,
         file_path: PathBuf,
+        remote_url: Option<Url>,
         resume: bool,
         import_range: Option<Range<u32>>,
         import_type: ImportType,
         parallelism: u8,
-    ) {
+    ) -> Result<(), String> {
         // don't do anything if the service is shutting down
         if self.service.is_stopping() {
-            return ();
+            return Err("The broker is stopping".to_owned());
         }
         if let Some(path_str) = file_path.to_str() {
-            if self.service.microservices.get(&path_str.to_owned()).is_some() {
-                return ();
+            let name = remote_url
+                .as_ref()
+                .map(|url| url.to_string())
+                .unwrap_or_else(|| path_str.to_owned());
+            if self.service.microservices.get(&name).is_some() {
+                return Err(format!("{} is already in progress", name));
             }
             match import_type {
                 ImportType::All => {
-                    self.build_and_start_importer::<All>(file_path, resume, import_range, parallelism);
+                    self.build_and_start_importer::<All>(file_path, remote_url, resume, import_range, parallelism);
                 }
                 ImportType::Analytics => {
-                    self.build_and_start_importer::<Analytics>(file_path, resume, import_range, parallelism);
+                    self.build_and_start_importer::<Analytics>(
+                        file_path,
+                        remote_url,
+                        resume,
+                        import_range,
+                        parallelism,
+                    );
+                }
+                ImportType::DryRun => {
+                    self.build_and_start_importer::<DryRun>(file_path, remote_url, resume, import_range, parallelism);
                 }
             }
             self.in_progress_importers += 1;
             self.parallelism_points -= parallelism;
+            Ok(())
         } else {
             self.parallelism_points += parallelism;
+            let msg = format!("Unable to convert path to string: {}", file_path.display());
             let event = ImporterSession::PathError {
                 path: file_path,
                 msg: "Unable to convert path to string".into(),
             };
             let socket_msg = BrokerSocketMsg::ChronicleBroker(event);
             self.response_to_sockets(&socket_msg).await;
+            Err(msg)
         }
     }
     async fn spawn_importers(
@@ -451,54 +742,182 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
         resume: bool,
         import_range: Option<Range<u32>>,
         import_type: ImportType,
-    ) {
+    ) -> Result<(), String> {
         let mut import_files = Vec::new();
         if let Ok(mut dir_entry) = tokio::fs::read_dir(&path).await {
             while let Ok(Some(p)) = dir_entry.next_entry().await {
                 let file_path = p.path();
                 if file_path.is_file() {
-                    import_files.push(file_path);
+                    import_files.push(BrokerTopology::Import {
+                        path: file_path,
+                        resume,
+                        import_range: import_range.clone(),
+                        import_type,
+                    });
                 }
             }
         };
-        let import_files_len = import_files.len();
-        if import_files_len == 0 {
+        if import_files.is_empty() {
+            let msg = format!("No LogFiles in the provided path: {}", path.display());
             let event = ImporterSession::PathError {
                 path,
                 msg: "No LogFiles in the provided path".into(),
             };
             let socket_msg = BrokerSocketMsg::ChronicleBroker(event);
             self.response_to_sockets(&socket_msg).await;
-            return ();
+            return Err(msg);
         }
-        if self.parallelism_points as usize > import_files_len {
-            let parallelism = (self.parallelism_points as usize / import_files_len) as u8;
-            for file_path in import_files {
-                self.spawn_importer(file_path, resume, import_range.clone(), import_type, parallelism)
-                    .await
+        self.distribute_imports(import_files).await
+    }
+    async fn spawn_remote_importers(
+        &mut self,
+        base_url: Url,
+        resume: bool,
+        import_range: Option<Range<u32>>,
+        import_type: ImportType,
+    ) -> Result<(), String> {
+        match self
+            .fetch_remote_manifest(&base_url, resume, import_range.clone())
+            .await
+        {
+            Ok(urls) => {
+                if urls.is_empty() {
+                    let msg = format!("No LogFiles to import in the manifest of {}", base_url);
+                    let event = ImporterSession::UrlError {
+                        url: base_url,
+                        msg: "No LogFiles to import in the provided manifest".into(),
+                    };
+                    let socket_msg = BrokerSocketMsg::ChronicleBroker(event);
+                    self.response_to_sockets(&socket_msg).await;
+                    return Err(msg);
+                }
+                let import_files = urls
+                    .into_iter()
+                    .map(|url| BrokerTopology::ImportRemote {
+                        url,
+                        resume,
+                        import_range: import_range.clone(),
+                        import_type,
+                    })
+                    .collect();
+                self.distribute_imports(import_files).await
+            }
+            Err(e) => {
+                error!("Unable to fetch the LogFiles manifest from: {}, error: {}", base_url, e);
+                let msg = format!("Unable to fetch the LogFiles manifest from {}: {}", base_url, e);
+                let event = ImporterSession::UrlError {
+                    url: base_url,
+                    msg: format!("Unable to fetch the LogFiles manifest: {}", e),
+                };
+                let socket_msg = BrokerSocketMsg::ChronicleBroker(event);
+                self.response_to_sockets(&socket_msg).await;
+                Err(msg)
             }
+        }
+    }
+    /// Fetch the manifest of the remote base url, and return the urls of the LogFiles
+    /// which overlap the import range (and are not completed yet in case of resume)
+    async fn fetch_remote_manifest(
+        &self,
+        base_url: &Url,
+        resume: bool,
+        import_range: Option<Range<u32>>,
+    ) -> anyhow::Result<Vec<Url>> {
+        let mut base_url = base_url.clone();
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+        let manifest = crate::importer::fetch_manifest(&base_url, crate::importer::MANIFEST_RETRIES).await?;
+        let import_range = import_range.unwrap_or(1..(i32::MAX as u32));
+        let sync_data = if resume {
+            let sync_range = SyncRange {
+                from: import_range.start.max(1),
+                to: import_range.end,
+            };
+            Some(SyncData::try_fetch(&self.default_keyspace, &sync_range, 10).await?)
         } else {
-            // unwrap is safe
-            let file_path = import_files.pop().expect("Expected import file");
-            self.spawn_importer(
-                file_path,
+            None
+        };
+        let mut urls = Vec::new();
+        for filename in manifest {
+            let split = filename.trim_end_matches(".log").split("to").collect::<Vec<_>>();
+            ensure!(split.len() == 2, "Invalid LogFile name in manifest: {}", filename);
+            let (from, to): (u32, u32) = (split[0].parse()?, split[1].parse()?);
+            // skip the LogFiles which are outside the import range
+            if to <= import_range.start || from >= import_range.end {
+                continue;
+            }
+            // skip the LogFiles which are already imported
+            let completed = sync_data.as_ref().map_or(false, |sync_data| {
+                sync_data
+                    .completed
+                    .iter()
+                    .any(|r| r.start <= from.max(import_range.start) && r.end >= to.min(import_range.end))
+            });
+            if completed {
+                info!("Skipping already imported remote LogFile: {}", filename);
+                continue;
+            }
+            urls.push(base_url.join(&filename)?);
+        }
+        Ok(urls)
+    }
+    /// The local path which is used to stage a remote LogFile while being imported, under the logs directory if
+    /// it's configured
+    fn remote_log_file_path(&self, url: &Url) -> PathBuf {
+        let staging_dir = self.logs_dir_path.clone().unwrap_or_else(std::env::temp_dir);
+        crate::importer::remote_log_file_path(&staging_dir, url)
+    }
+    pub(crate) async fn spawn_import(
+        &mut self,
+        import_topology: BrokerTopology,
+        parallelism: u8,
+    ) -> Result<(), String> {
+        match import_topology {
+            BrokerTopology::Import {
+                path,
                 resume,
-                import_range.clone(),
+                import_range,
                 import_type,
-                self.parallelism_points,
-            )
-            .await;
-            // convert any remaining into pending_imports
-            for file_path in import_files {
-                let topology = BrokerTopology::Import {
-                    path: file_path,
-                    resume,
-                    import_range: import_range.clone(),
-                    import_type,
-                };
-                self.pending_imports.push(topology);
+            } => {
+                self.spawn_importer(path, None, resume, import_range, import_type, parallelism)
+                    .await
+            }
+            BrokerTopology::ImportRemote {
+                url,
+                resume,
+                import_range,
+                import_type,
+            } => {
+                let file_path = self.remote_log_file_path(&url);
+                self.spawn_importer(file_path, Some(url), resume, import_range, import_type, parallelism)
+                    .await
             }
+            _ => Ok(()),
+        }
+    }
+    async fn spawn_exporter(&mut self, path: PathBuf, export_range: Range<u32>) -> Result<(), String> {
+        // don't do anything if the service is shutting down
+        if self.service.is_stopping() {
+            return Err("The broker is stopping".to_owned());
+        }
+        let exporter = ExporterBuilder::new()
+            .dir_path(path.clone())
+            .export_range(export_range)
+            .retries_per_query(get_config().broker_config.retries_per_query)
+            .build();
+        if self.service.microservices.get(&exporter.get_name()).is_some() {
+            let msg = format!("{} is already in progress", exporter.get_name());
+            let event = ExporterSession::PathError { path, msg: msg.clone() };
+            let socket_msg = BrokerSocketMsg::ChronicleBroker(event);
+            self.response_to_sockets(&socket_msg).await;
+            return Err(msg);
         }
+        let handle = exporter.clone_handle().expect("Expected existing exporter handle");
+        self.exporter_handles.insert(exporter.get_name(), handle);
+        self.service.update_microservice(exporter.get_name(), Service::new());
+        tokio::spawn(exporter.start(self.handle.clone()));
+        Ok(())
     }
     pub(crate) async fn response_to_sockets<T: Serialize>(&mut self, msg: &BrokerSocketMsg<T>) {
         for socket in self.websockets.values_mut() {
@@ -545,11 +964,26 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
             if let Some(syncer) = self.syncer_handle.take() {
                 syncer.shutdown();
             }
+            // stop probing the api endpoints
+            if let Some(endpoints_prober) = self.endpoints_prober.take() {
+                endpoints_prober.abort();
+            }
+            // stop serving the mock endpoint
+            if let Some(mock_endpoint) = self.mock_endpoint.take() {
+                mock_endpoint.abort();
+            }
+            // stop recording
+            record::stop_recording().await;
             // shutdown importers
             for (importer_name, importer_handle) in self.importer_handles.drain() {
                 info!("Shutting down importer: {}", importer_name);
                 importer_handle.shutdown();
             }
+            // shutdown exporters
+            for (exporter_name, exporter_handle) in self.exporter_handles.drain() {
+                info!("Shutting down exporter: {}", exporter_name);
+                exporter_handle.shutdown();
+            }
             // drop self handler
             if drop_handle {
                 self.handle.take();
@@ -557,3 +991,24 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mqtt_reconnect_backoff_bounds() {
+        let backoff = Duration::from_secs(5);
+        for reconnects in (0..16).chain([u32::MAX]) {
+            let expected = backoff
+                .saturating_mul(2u32.saturating_pow(reconnects))
+                .min(MAX_MQTT_RECONNECT_BACKOFF);
+            for _ in 0..100 {
+                let jittered = jittered_backoff(backoff, reconnects);
+                assert!(jittered >= expected / 2 && jittered <= expected * 3 / 2);
+            }
+        }
+        assert!(jittered_backoff(backoff, 0) <= Duration::from_millis(7500));
+        assert!(jittered_backoff(backoff, u32::MAX) >= MAX_MQTT_RECONNECT_BACKOFF / 2);
+    }
+}
@@ -35,6 +35,7 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
                     .keyspace(self.default_keyspace.clone())
                     .solidifiers_count(self.collector_count)
                     .max_log_size(max_log_size)
+                    .chronicle_id(config.broker_config.chronicle_id.unwrap_or(0))
                     .oneshot(recv)
                     .build();
                 archiver_handle = archiver.take_handle();
@@ -53,6 +54,41 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
                 .timeout(Duration::from_secs(config.broker_config.request_timeout_secs))
                 .build()
                 .expect("Expected reqwest client to build correctly");
+            let mut api_endpoints = config.broker_config.api_endpoints.clone();
+            // serve the recording as an api endpoint, for offline runs
+            if let Some(previous_mock_endpoint) = self.mock_endpoint.take() {
+                previous_mock_endpoint.abort();
+            }
+            if let Some(recording) = config.broker_config.mock_endpoint.as_ref() {
+                match MockEndpoint::from_recording(recording)
+                    .await
+                    .and_then(|mock_endpoint| mock_endpoint.serve(([127, 0, 0, 1], 0).into()))
+                {
+                    Ok((address, handle)) => {
+                        self.mock_endpoint.replace(handle);
+                        api_endpoints.insert(
+                            Url::parse(&format!("http://{}/api/v1/", address))
+                                .expect("Expected a valid mock endpoint url"),
+                        );
+                    }
+                    Err(e) => error!("Unable to serve the mock endpoint: {}", e),
+                }
+            }
+            // probe the api endpoints to keep their sync status up to date
+            health::register_endpoints(api_endpoints.iter()).await;
+            let probe_interval = Duration::from_secs(config.broker_config.endpoint_probe_interval_secs.unwrap_or(30));
+            let (endpoints_prober, abort_handle) =
+                futures::future::abortable(health::probe_endpoints(reqwest_client.clone(), probe_interval));
+            if let Some(previous_prober) = self.endpoints_prober.replace(abort_handle) {
+                previous_prober.abort();
+            }
+            tokio::spawn(endpoints_prober);
+            // record the feeds and the api endpoints traffic, for later replays
+            if let Some(record_path) = config.broker_config.record_path.as_ref() {
+                if let Err(e) = record::start_recording(record_path).await {
+                    error!("{}", e);
+                }
+            }
             for partition_id in 0..self.collector_count {
                 // create requesters senders
                 let mut requesters_senders = Vec::new();
@@ -72,7 +108,7 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
                     .requester_count(config.broker_config.requester_count)
                     .handle(collector_handle)
                     .inbox(collector_inbox)
-                    .api_endpoints(config.broker_config.api_endpoints.iter().cloned().collect())
+                    .api_endpoints(api_endpoints.iter().cloned().collect())
                     .storage_config(config.storage_config.clone())
                     .reqwest_client(reqwest_client.clone())
                     .retries_per_query(config.broker_config.retries_per_query)
@@ -87,6 +123,7 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
                 let solidifier_inbox = SolidifierInbox { rx };
                 self.solidifier_handles.insert(partition_id, solidifier_handle.clone());
                 let mut solidifier_builder = SolidifierBuilder::new()
+                    .chronicle_id(config.broker_config.chronicle_id.unwrap_or(0))
                     .collector_count(self.collector_count)
                     .syncer_handle(syncer_handle.clone());
                 if let Some(archiver_handle) = archiver_handle.clone().take() {
@@ -102,37 +139,38 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
             }
             // store copy of syncer_handle in broker state in order to be able to shut it down
             self.syncer_handle.replace(syncer_handle);
+            // claim the sync leases only if enabled
+            if let Some(sync_lease_secs) = config.broker_config.sync_lease_secs {
+                syncer_builder = syncer_builder.lease_duration(Duration::from_secs(sync_lease_secs));
+            }
             // Finalize and Spawn Syncer
             let syncer = syncer_builder
                 .solidifier_handles(self.solidifier_handles.clone())
                 .sync_range(self.sync_range)
                 .parallelism(self.parallelism)
                 .update_sync_data_every(self.complete_gaps_interval)
+                .retry_backoff(Duration::from_secs(
+                    config.broker_config.unreachable_retry_backoff_secs.unwrap_or(60),
+                ))
+                .chronicle_id(config.broker_config.chronicle_id.unwrap_or(0))
+                .lease_range(config.broker_config.sync_lease_range.unwrap_or(10000))
                 .build();
             tokio::spawn(syncer.start(self.handle.clone()));
-            // Spawn mqtt brokers
-            for broker_url in config
+            // Spawn the feeds of the mqtt brokers and the event streams
+            let mqtt_brokers = config
                 .broker_config
                 .mqtt_brokers
-                .get(&MqttType::Messages)
                 .iter()
-                .flat_map(|v| v.iter())
-                .cloned()
-            {
-                if let Some(mqtt) = self.add_mqtt(Messages, MqttType::Messages, broker_url) {
-                    tokio::spawn(mqtt.start(self.handle.clone()));
-                }
-            }
-            for broker_url in config
+                .map(|(mqtt_type, urls)| (FeedKind::Mqtt, mqtt_type, urls));
+            let event_streams = config
                 .broker_config
-                .mqtt_brokers
-                .get(&MqttType::MessagesReferenced)
+                .event_streams
                 .iter()
-                .flat_map(|v| v.iter())
-                .cloned()
-            {
-                if let Some(mqtt) = self.add_mqtt(MessagesReferenced, MqttType::MessagesReferenced, broker_url) {
-                    tokio::spawn(mqtt.start(self.handle.clone()));
+                .flatten()
+                .map(|(mqtt_type, urls)| (FeedKind::EventStream, mqtt_type, urls));
+            for (kind, mqtt_type, urls) in mqtt_brokers.chain(event_streams) {
+                for url in urls.iter().cloned() {
+                    self.start_feed(kind, *mqtt_type, url, None);
                 }
             }
             // we finalize them
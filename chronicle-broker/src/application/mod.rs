@@ -3,10 +3,22 @@
 use super::*;
 use crate::{
     archiver::*,
-    collector::*,
+    collector::{
+        feeds,
+        *,
+    },
+    exporter::*,
+    feed::{
+        record,
+        FeedKind,
+    },
     importer::*,
     listener::*,
     mqtt::*,
+    requester::{
+        health,
+        mock::MockEndpoint,
+    },
     solidifier::*,
     syncer::*,
     websocket::*,
@@ -21,6 +33,8 @@ use std::{
 
 mod event_loop;
 mod init;
+mod scheduler;
+pub(crate) use scheduler::NodeLatencies;
 mod starter;
 mod terminating;
 
@@ -60,12 +74,16 @@ pub struct ChronicleBroker<H: ChronicleBrokerScope> {
     listener_handle: Option<ListenerHandle>,
     mqtt_handles: HashMap<String, MqttHandle>,
     importer_handles: HashMap<String, ImporterHandle>,
+    exporter_handles: HashMap<String, ExporterHandle>,
     asked_to_shutdown: HashMap<String, ()>,
+    mqtt_reconnects: HashMap<String, u32>,
     parallelism: u8,
     complete_gaps_interval: Duration,
     parallelism_points: u8,
     pending_imports: Vec<BrokerTopology>,
     in_progress_importers: usize,
+    import_progress: scheduler::ImportProgress,
+    node_latencies: std::sync::Arc<NodeLatencies>,
     collector_count: u8,
     collector_handles: HashMap<u8, CollectorHandle>,
     solidifier_handles: HashMap<u8, SolidifierHandle>,
@@ -76,6 +94,8 @@ pub struct ChronicleBroker<H: ChronicleBrokerScope> {
     sync_range: SyncRange,
     sync_data: SyncData,
     syncer_handle: Option<SyncerHandle>,
+    endpoints_prober: Option<futures::future::AbortHandle>,
+    mock_endpoint: Option<tokio::task::JoinHandle<()>>,
 }
 
 /// SubEvent type, indicates the children
@@ -94,6 +114,8 @@ pub enum BrokerChild {
     Syncer(Service, Result<(), Need>),
     /// Used by Importer to keep Broker up to date with its service, u8 is parallelism
     Importer(Service, Result<(), Need>, u8),
+    /// Used by Exporter to keep Broker up to date with its service
+    Exporter(Service, Result<(), Need>),
     /// Used by Websocket to keep Broker up to date with its service
     Websocket(Service, Option<WsTx>),
 }
@@ -102,6 +124,8 @@ pub enum BrokerChild {
 pub enum BrokerEvent<T> {
     /// Importer Session
     Importer(ImporterSession),
+    /// Exporter Session
+    Exporter(ExporterSession),
     /// It's the passthrough event, which the scylla application will receive from
     Passthrough(T),
     /// Used by broker children to push their service
@@ -157,15 +181,21 @@ impl<H: ChronicleBrokerScope> Builder for ChronicleBrokerBuilder<H> {
             listener_handle: self.listener_handle,
             mqtt_handles: HashMap::new(),
             importer_handles: HashMap::new(),
+            exporter_handles: HashMap::new(),
             asked_to_shutdown: HashMap::new(),
+            mqtt_reconnects: HashMap::new(),
             collector_count: self.collector_count.unwrap_or(10),
             collector_handles: HashMap::new(),
             solidifier_handles: HashMap::new(),
             syncer_handle: None,
+            endpoints_prober: None,
+            mock_endpoint: None,
             parallelism,
             parallelism_points: parallelism,
             pending_imports: Vec::new(),
             in_progress_importers: 0,
+            import_progress: Default::default(),
+            node_latencies: std::sync::Arc::new(NodeLatencies::new(config.storage_config.nodes.len())),
             logs_dir_path,
             handle,
             inbox,
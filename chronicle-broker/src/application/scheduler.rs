@@ -0,0 +1,312 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use chronicle_common::metrics::{
+    IMPORT_BYTES,
+    IMPORT_BYTES_PER_SECOND,
+    IMPORT_ETA_SECONDS,
+    IMPORT_FAILURES,
+    IMPORT_IN_PROGRESS_FILES,
+    IMPORT_MILESTONES,
+    IMPORT_MILESTONES_PER_SECOND,
+    IMPORT_PENDING_FILES,
+    IMPORT_TOTAL_BYTES,
+};
+use std::{
+    collections::HashSet,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+    time::Instant,
+};
+
+/// The minimum interval between two consecutive import reports
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Tracks the global progress of the import session, across all of its LogFiles
+#[derive(Default)]
+pub(crate) struct ImportProgress {
+    /// The start time of the import session
+    started: Option<Instant>,
+    /// The last time a report got published
+    last_report: Option<Instant>,
+    /// The LogFiles (from_ms, to_ms) whose size is already accounted in the total bytes
+    files: HashSet<(u32, u32)>,
+    /// The processed milestones
+    milestones: u64,
+    /// The processed bytes
+    bytes: u64,
+    /// The total bytes of the scheduled LogFiles
+    total_bytes: u64,
+    /// The number of failed LogFiles
+    failures: usize,
+}
+
+impl ImportProgress {
+    /// Account the LogFile size in the total bytes, once per LogFile
+    pub(crate) fn schedule_file(&mut self, from_ms: u32, to_ms: u32, log_file_size: u64) {
+        self.started.get_or_insert_with(Instant::now);
+        if self.files.insert((from_ms, to_ms)) {
+            self.total_bytes += log_file_size;
+        }
+    }
+    /// Account a processed milestone data
+    pub(crate) fn progress(&mut self, ms_bytes_size: usize, skipped: bool) {
+        self.bytes += ms_bytes_size as u64;
+        if !skipped {
+            self.milestones += 1;
+        }
+    }
+    /// Account a failed LogFile
+    pub(crate) fn failure(&mut self) {
+        self.failures += 1;
+    }
+    /// Build the import report and publish it to the metrics,
+    /// returns None if the last report is more recent than the report interval, unless forced.
+    pub(crate) fn report(
+        &mut self,
+        in_progress_files: usize,
+        pending_files: usize,
+        force: bool,
+    ) -> Option<ImportReport> {
+        let now = Instant::now();
+        if !force
+            && self
+                .last_report
+                .map_or(false, |last_report| now.duration_since(last_report) < REPORT_INTERVAL)
+        {
+            return None;
+        }
+        self.last_report.replace(now);
+        let elapsed = self
+            .started
+            .map_or(0.0, |started| now.duration_since(started).as_secs_f64());
+        let (milestones_per_second, bytes_per_second) = if elapsed > 0.0 {
+            (self.milestones as f64 / elapsed, self.bytes as f64 / elapsed)
+        } else {
+            (0.0, 0.0)
+        };
+        let eta_seconds = if bytes_per_second > 0.0 {
+            Some((self.total_bytes.saturating_sub(self.bytes) as f64 / bytes_per_second).ceil() as u64)
+        } else {
+            None
+        };
+        let report = ImportReport {
+            milestones: self.milestones,
+            bytes: self.bytes,
+            total_bytes: self.total_bytes,
+            milestones_per_second,
+            bytes_per_second,
+            eta_seconds,
+            failures: self.failures,
+            in_progress_files,
+            pending_files,
+        };
+        IMPORT_MILESTONES.set(report.milestones as i64);
+        IMPORT_BYTES.set(report.bytes as i64);
+        IMPORT_TOTAL_BYTES.set(report.total_bytes as i64);
+        IMPORT_MILESTONES_PER_SECOND.set(report.milestones_per_second);
+        IMPORT_BYTES_PER_SECOND.set(report.bytes_per_second);
+        IMPORT_ETA_SECONDS.set(report.eta_seconds.map_or(-1, |eta_seconds| eta_seconds as i64));
+        IMPORT_FAILURES.set(report.failures as i64);
+        IMPORT_IN_PROGRESS_FILES.set(report.in_progress_files as i64);
+        IMPORT_PENDING_FILES.set(report.pending_files as i64);
+        Some(report)
+    }
+    /// Reset the progress once the import session is closed
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// The smoothed write latencies of the scylla nodes, shared by all the importers, so a slow node throttles every
+/// importer which writes to it. The token ring is split into as many equal token ranges as the configured nodes,
+/// and every insert is accounted in the range of its token, which approximates the node owning it.
+pub struct NodeLatencies {
+    /// The smoothed latency in milliseconds of each token range, as f64 bits, zero while not observed yet
+    ranges: Vec<AtomicU64>,
+}
+
+impl NodeLatencies {
+    /// Create the latencies of the given number of nodes
+    pub(crate) fn new(nodes: usize) -> Self {
+        Self {
+            ranges: (0..nodes.max(1)).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+    /// The token range of the token
+    fn range(&self, token: i64) -> usize {
+        let offset = (token as i128 - i64::MIN as i128) as u128;
+        ((offset * self.ranges.len() as u128) >> 64) as usize
+    }
+    /// Account the write latency of an insert with the given token
+    pub(crate) fn observe(&self, token: i64, latency_ms: f64) {
+        self.ranges[self.range(token)]
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                let latency = if bits == 0 {
+                    latency_ms
+                } else {
+                    0.8 * f64::from_bits(bits) + 0.2 * latency_ms
+                };
+                Some(latency.max(f64::MIN_POSITIVE).to_bits())
+            })
+            .ok();
+    }
+    /// The smoothed latency of the slowest node, if any got observed
+    pub(crate) fn slowest(&self) -> Option<f64> {
+        self.ranges
+            .iter()
+            .map(|range| range.load(Ordering::Relaxed))
+            .filter(|bits| *bits != 0)
+            .map(f64::from_bits)
+            .fold(None, |slowest, latency| {
+                Some(slowest.map_or(latency, |slowest: f64| slowest.max(latency)))
+            })
+    }
+}
+
+impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
+    /// Schedule the import LogFiles, the largest ones first as they take the longest to import.
+    /// The parallelism points are balanced across the LogFiles by their milestones count, while the milestones
+    /// partitions are spread over the whole ring, so the importers get balanced across the scylla nodes by the shared
+    /// node latencies, which throttle them once any node is slower than the target latency.
+    pub(crate) async fn distribute_imports(&mut self, mut import_files: Vec<BrokerTopology>) -> Result<(), String> {
+        import_files.sort_by_key(|import_topology| Self::import_milestones_count(import_topology));
+        // account the size of the local LogFiles upfront, so the ETA covers the pending ones,
+        // note: the remote LogFiles are accounted once they're fetched.
+        for import_topology in import_files.iter() {
+            if let BrokerTopology::Import { path, .. } = import_topology {
+                if let (Some((from_ms, to_ms)), Ok(metadata)) = (
+                    Self::import_log_file_range(import_topology),
+                    tokio::fs::metadata(path).await,
+                ) {
+                    self.import_progress.schedule_file(from_ms, to_ms, metadata.len());
+                }
+            }
+        }
+        let import_files_len = import_files.len();
+        let mut errors = Vec::new();
+        let mut attempted = 0;
+        if self.parallelism_points as usize > import_files_len {
+            attempted = import_files_len;
+            let total_milestones = import_files
+                .iter()
+                .map(|import_topology| Self::import_milestones_count(import_topology) as u64)
+                .sum::<u64>()
+                .max(1);
+            // every LogFile gets at least one point, and the rest is distributed proportionally
+            let extra_points = (self.parallelism_points as usize - import_files_len) as u64;
+            for import_topology in import_files {
+                let milestones = Self::import_milestones_count(&import_topology) as u64;
+                let parallelism = 1 + (extra_points * milestones / total_milestones) as u8;
+                if let Err(e) = self.spawn_import(import_topology, parallelism).await {
+                    errors.push(e);
+                }
+            }
+        } else {
+            // start the largest LogFile which can be started
+            while let Some(import_topology) = import_files.pop() {
+                attempted += 1;
+                match self.spawn_import(import_topology, self.parallelism_points).await {
+                    Ok(()) => break,
+                    Err(e) => errors.push(e),
+                }
+            }
+            // convert any remaining into pending_imports, the largest is popped first
+            self.pending_imports.extend(import_files);
+        }
+        self.report_import_progress(false).await;
+        // the import fails only if none of its LogFiles got started
+        if errors.len() == attempted {
+            return Err(errors.join(", "));
+        }
+        for e in errors {
+            warn!("Unable to start the import of a LogFile: {}", e);
+        }
+        Ok(())
+    }
+    /// The number of milestones of the import LogFile
+    fn import_milestones_count(import_topology: &BrokerTopology) -> u32 {
+        Self::import_log_file_range(import_topology)
+            .map(|(from_ms, to_ms)| to_ms.saturating_sub(from_ms))
+            .unwrap_or_default()
+    }
+    /// The milestones range of the import LogFile, inferred from its {from}to{to}.log name
+    fn import_log_file_range(import_topology: &BrokerTopology) -> Option<(u32, u32)> {
+        let filename = match import_topology {
+            BrokerTopology::Import { path, .. } => path.file_name().and_then(|f| f.to_str()),
+            BrokerTopology::ImportRemote { url, .. } => url.path_segments().and_then(|segments| segments.last()),
+            _ => None,
+        }?;
+        let mut split = filename.trim_end_matches(".log").split("to");
+        let from_ms = split.next()?.parse::<u32>().ok()?;
+        let to_ms = split.next()?.parse::<u32>().ok()?;
+        Some((from_ms, to_ms))
+    }
+    /// Publish the import report to the sockets, at most once per report interval unless forced
+    pub(crate) async fn report_import_progress(&mut self, force: bool) {
+        if let Some(report) = self
+            .import_progress
+            .report(self.in_progress_importers, self.pending_imports.len(), force)
+        {
+            let socket_msg = BrokerSocketMsg::ChronicleBroker(ImporterSession::Report(report));
+            self.response_to_sockets(&socket_msg).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_latencies() {
+        let latencies = NodeLatencies::new(3);
+        assert_eq!(latencies.slowest(), None);
+        assert_eq!(latencies.range(i64::MIN), 0);
+        assert_eq!(latencies.range(0), 1);
+        assert_eq!(latencies.range(i64::MAX), 2);
+        latencies.observe(i64::MIN, 10.0);
+        latencies.observe(i64::MAX, 100.0);
+        assert_eq!(latencies.slowest(), Some(100.0));
+        // the latencies get smoothed per node
+        latencies.observe(i64::MAX, 50.0);
+        assert!((latencies.slowest().unwrap() - 90.0).abs() < 1e-9);
+        latencies.observe(i64::MIN, 200.0);
+        assert!((latencies.slowest().unwrap() - 90.0).abs() < 1e-9);
+        latencies.observe(i64::MIN, 400.0);
+        assert!((latencies.slowest().unwrap() - 118.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn import_progress_report() {
+        let mut progress = ImportProgress::default();
+        progress.schedule_file(0, 100, 200);
+        progress.schedule_file(100, 200, 100);
+        // a retried LogFile is accounted once
+        progress.schedule_file(0, 100, 200);
+        progress.progress(60, false);
+        progress.progress(40, true);
+        progress.failure();
+        progress.started = Some(Instant::now() - Duration::from_secs(10));
+        let report = progress.report(1, 2, false).unwrap();
+        assert_eq!(report.milestones, 1);
+        assert_eq!(report.bytes, 100);
+        assert_eq!(report.total_bytes, 300);
+        assert_eq!(report.failures, 1);
+        assert_eq!((report.in_progress_files, report.pending_files), (1, 2));
+        assert!(report.bytes_per_second > 9.9 && report.bytes_per_second <= 10.0);
+        assert!(report.milestones_per_second > 0.099 && report.milestones_per_second <= 0.1);
+        // 200 remaining bytes at ~10 bytes per second
+        assert!(matches!(report.eta_seconds, Some(20..=21)));
+        // the reports are throttled unless forced
+        assert!(progress.report(1, 2, false).is_none());
+        assert!(progress.report(1, 2, true).is_some());
+        progress.reset();
+        let report = progress.report(0, 0, true).unwrap();
+        assert_eq!(report.total_bytes, 0);
+        assert_eq!(report.eta_seconds, None);
+    }
+}
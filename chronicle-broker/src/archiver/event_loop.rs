@@ -138,9 +138,7 @@ impl Archiver {
         mut opt_upper_limit: Option<u32>,
     ) -> anyhow::Result<()> {
         let milestone_index = milestone_data.milestone_index();
-        let mut milestone_data_json = serde_json::to_string(&milestone_data).unwrap();
-        milestone_data_json.push('\n');
-        let milestone_data_line: Vec<u8> = milestone_data_json.into();
+        let milestone_data_line = milestone_data_line(&milestone_data)?;
         // check the logs files to find if any has already existing log file
         if let Some(log_file) = self
             .logs
@@ -155,6 +153,7 @@ impl Archiver {
                     milestone_index,
                     &self.keyspace,
                     self.retries_per_query,
+                    self.chronicle_id,
                 )
                 .await?;
                 // check if now the log_file reached an upper limit to finish the file
@@ -222,6 +221,7 @@ impl Archiver {
             milestone_index,
             &self.keyspace,
             self.retries_per_query,
+            self.chronicle_id,
         )
         .await?;
         // check if we hit an upper_ms_limit, as this is possible when the log_file only needs 1 milestone data.
@@ -272,11 +272,12 @@ impl Archiver {
         ms_index: u32,
         keyspace: &ChronicleKeyspace,
         retries_per_query: usize,
+        chronicle_id: u8,
     ) -> anyhow::Result<()> {
         log_file.append_line(&milestone_data_line).await?;
         // insert into the DB, without caring about the response
         let sync_key = chronicle_common::Synckey;
-        let synced_record = SyncRecord::new(MilestoneIndex(ms_index), None, Some(0));
+        let synced_record = SyncRecord::new(MilestoneIndex(ms_index), None, Some(chronicle_id));
         keyspace
             .insert(&sync_key, &synced_record)
             .consistency(Consistency::One)
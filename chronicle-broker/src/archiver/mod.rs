@@ -30,6 +30,7 @@ use tokio::{
     },
     io::{
         AsyncBufReadExt,
+        AsyncSeekExt,
         AsyncWriteExt,
         BufReader,
     },
@@ -42,6 +43,13 @@ mod terminating;
 /// The maximum bytes size for a given log file;
 pub const MAX_LOG_SIZE: u64 = u32::MAX as u64;
 
+/// The line of the milestone data in a LogFile, shared by the archiver and the exporter so their LogFiles are identical
+pub(crate) fn milestone_data_line(milestone_data: &MilestoneData) -> anyhow::Result<Vec<u8>> {
+    let mut milestone_data_json = serde_json::to_string(milestone_data)?;
+    milestone_data_json.push('\n');
+    Ok(milestone_data_json.into())
+}
+
 // Archiver builder
 builder!(ArchiverBuilder {
     keyspace: ChronicleKeyspace,
@@ -49,6 +57,7 @@ builder!(ArchiverBuilder {
     oneshot: Receiver<u32>,
     solidifiers_count: u8,
     retries_per_query: usize,
+    chronicle_id: u8,
     dir_path: PathBuf
 });
 
@@ -219,6 +228,20 @@ impl LogFile {
         }
     }
 
+    /// Skip the given bytes offset of the LogFile, in order to resume importing it from a checkpoint.
+    /// Note: the offset must be at a milestone data line boundary.
+    pub async fn skip(&mut self, offset: u64) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            offset <= self.len,
+            "Unable to skip: {} bytes of LogFile: {}, as it only has: {} bytes",
+            offset,
+            self.filename,
+            self.len
+        );
+        self.file.seek(std::io::SeekFrom::Current(offset as i64)).await?;
+        self.len -= offset;
+        Ok(())
+    }
     /// Get the file length
     pub fn len(&self) -> u64 {
         self.len
@@ -254,6 +277,7 @@ pub struct Archiver {
     oneshot: Option<tokio::sync::oneshot::Receiver<u32>>,
     keyspace: ChronicleKeyspace,
     retries_per_query: usize,
+    chronicle_id: u8,
     solidifiers_count: u8,
     handle: Option<ArchiverHandle>,
     inbox: ArchiverInbox,
@@ -286,6 +310,7 @@ impl Builder for ArchiverBuilder {
             milestones_data: std::collections::BinaryHeap::new(),
             oneshot: self.oneshot,
             retries_per_query: self.retries_per_query.unwrap_or(10),
+            chronicle_id: self.chronicle_id.unwrap_or(0),
             handle,
             inbox,
         }
@@ -45,36 +45,7 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Collector {
                             // close the request
                             self.push_close_to_solidifier(partition_id, message_id, try_ms_index);
                         }
-                        // set the ref_ms to be the current requested message ref_ms
-                        self.ref_ms.0 = *ref_ms;
-                        // check if msg already in lru cache(if so then it's already presisted)
-                        let wrong_msg_est_ms;
-                        if let Some((_, est_ms, _)) = self.lru_msg.get_mut(&message_id) {
-                            // check if est_ms is not identical to ref_ms
-                            if &est_ms.0 != ref_ms {
-                                wrong_msg_est_ms = Some(*est_ms);
-                                // adjust est_ms to match the actual ref_ms
-                                est_ms.0 = *ref_ms;
-                            } else {
-                                wrong_msg_est_ms = None;
-                            }
-                        } else {
-                            // add it to the cache in order to not presist it again.
-                            self.lru_msg.put(message_id, (None, self.ref_ms, message.clone()));
-                            wrong_msg_est_ms = None;
-                        }
-                        // Cache metadata.
-                        self.lru_msg_ref.put(message_id, metadata.clone());
-                        if let Some(wrong_est_ms) = wrong_msg_est_ms {
-                            self.clean_up_wrong_est_msg(&message_id, &message, wrong_est_ms)
-                                .unwrap_or_else(|e| {
-                                    error!("{}", e);
-                                });
-                        }
-                        self.insert_message_with_metadata(message_id, message, metadata)
-                            .unwrap_or_else(|e| {
-                                error!("{}", e);
-                            });
+                        self.cache_and_insert_full_message(message_id, message, metadata);
                     } else {
                         error!(
                             "{} , unable to fetch message: {:?}, from network triggered by milestone_index: {}",
@@ -86,7 +57,23 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Collector {
                         self.send_err_solidifiy(try_ms_index);
                     }
                 }
-                CollectorEvent::Message(message_id, mut message) => {
+                CollectorEvent::MilestoneCone(requester_id, milestone_index, full_messages) => {
+                    self.adjust_heap(requester_id);
+                    let partition_id = (milestone_index % (self.collector_count as u32)) as u8;
+                    // push the whole cone at once to the solidifier, before inserting it, so the solidifier only
+                    // requests the parents outside of the cone
+                    if let Some(solidifier_handle) = self.solidifier_handles.get(&partition_id) {
+                        let cone_event = SolidifierEvent::MilestoneCone(milestone_index, full_messages.clone());
+                        let _ = solidifier_handle.send(cone_event);
+                    }
+                    for FullMessage(message, metadata) in full_messages {
+                        let message_id = metadata.message_id;
+                        self.cache_and_insert_full_message(message_id, message, metadata);
+                    }
+                }
+                CollectorEvent::Message(feed, message_id, mut message) => {
+                    self.messages_feeds.record(&feed, message_id);
+                    self.messages_feeds.settle().await;
                     // check if msg already in lru cache(if so then it's already presisted)
                     if let None = self.lru_msg.get(&message_id) {
                         // store message
@@ -98,7 +85,9 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Collector {
                             .put(message_id, (Some(std::time::Instant::now()), self.est_ms, message));
                     }
                 }
-                CollectorEvent::MessageReferenced(metadata) => {
+                CollectorEvent::MessageReferenced(feed, metadata) => {
+                    self.referenced_feeds.record(&feed, metadata.message_id);
+                    self.referenced_feeds.settle().await;
                     if metadata.referenced_by_milestone_index.is_none() {
                         // metadata is not referenced yet, so we discard it.
                         continue;
@@ -192,75 +181,19 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Collector {
                 }
                 CollectorEvent::Ask(ask) => {
                     match ask {
-                        AskCollector::FullMessage(solidifier_id, try_ms_index, message_id, created_by) => {
-                            let mut message_tuple = None;
-                            if let Some((_, _, message)) = self.lru_msg.get(&message_id) {
-                                if let Some(metadata) = self.lru_msg_ref.get(&message_id) {
-                                    // metadata exist means we already pushed the full message to the solidifier,
-                                    // or the message doesn't belong to the solidifier
-                                    if !metadata.referenced_by_milestone_index.unwrap().eq(&try_ms_index) {
-                                        self.push_close_to_solidifier(solidifier_id, message_id, try_ms_index);
-                                    } else {
-                                        if let Some(solidifier_handle) = self.solidifier_handles.get(&solidifier_id) {
-                                            let full_message = FullMessage::new(message.clone(), metadata.clone());
-                                            let full_msg_event = SolidifierEvent::Message(full_message);
-                                            let _ = solidifier_handle.send(full_msg_event);
-                                        }
-                                        // make sure to insert the message if it's requested from syncer
-                                        if created_by == CreatedBy::Syncer {
-                                            self.ref_ms.0 = try_ms_index;
-                                            message_tuple = Some((message.clone(), metadata.clone()));
-                                        }
-                                    }
-                                } else {
-                                    if !(*self.est_ms).eq(&0) {
-                                        let highest_ms = *self.est_ms - 1;
-                                        if try_ms_index >= highest_ms {
-                                            if let Some((pre_ms_index, _)) = self.pending_requests.get_mut(&message_id)
-                                            {
-                                                // check if other solidifier(other milestone) already requested the
-                                                // message_id with diff try_ms_index
-                                                let old_ms = *pre_ms_index;
-                                                if old_ms < try_ms_index {
-                                                    // close try_ms_index, and keep pre_ms_index to be processed
-                                                    // eventually
-                                                    self.push_close_to_solidifier(
-                                                        solidifier_id,
-                                                        message_id,
-                                                        try_ms_index,
-                                                    );
-                                                } else {
-                                                    // overwrite pre_ms_index by try_ms_index, which it will be
-                                                    // eventually processed;
-                                                    *pre_ms_index = try_ms_index;
-                                                    // close pre_ms_index(old_ms) as it's greater than what we have atm
-                                                    // (try_ms_index).
-                                                    let solidifier_id = (old_ms % (self.collector_count as u32)) as u8;
-                                                    self.push_close_to_solidifier(solidifier_id, message_id, old_ms);
-                                                }
-                                            } else {
-                                                // add it to back_pressured requests
-                                                self.pending_requests
-                                                    .insert(message_id, (try_ms_index, message.clone()));
-                                            };
-                                        } else {
-                                            self.request_full_message(message_id, try_ms_index);
-                                        }
-                                    } else {
-                                        self.request_full_message(message_id, try_ms_index);
-                                    }
-                                }
-                            } else {
-                                self.request_full_message(message_id, try_ms_index);
-                            }
-                            // insert the message if requested by syncer to ensure it gets cql responses for all the
-                            // requested messages
-                            if let Some((message, metadata)) = message_tuple.take() {
-                                self.insert_message_with_metadata(message_id.clone(), message, metadata)
-                                    .unwrap_or_else(|e| {
-                                        error!("{}", e);
-                                    });
+                        AskCollector::FullMessages(solidifier_id, try_ms_index, message_ids, created_by) => {
+                            let mut to_request = Vec::new();
+                            for message_id in message_ids {
+                                self.ask_full_message(
+                                    solidifier_id,
+                                    try_ms_index,
+                                    message_id,
+                                    created_by,
+                                    &mut to_request,
+                                );
                             }
+                            // request all the missing messages of the batch at once
+                            self.request_full_messages(to_request, try_ms_index);
                         }
                         AskCollector::MilestoneMessage(milestone_index) => {
                             // Request it from network
@@ -297,6 +230,117 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Collector {
 }
 
 impl Collector {
+    /// Handle the ask of a solidifier for a full message, the message ids which have to be requested from the network
+    /// are pushed to `to_request`
+    fn ask_full_message(
+        &mut self,
+        solidifier_id: u8,
+        try_ms_index: u32,
+        message_id: MessageId,
+        created_by: CreatedBy,
+        to_request: &mut Vec<MessageId>,
+    ) {
+        let mut message_tuple = None;
+        if let Some((_, _, message)) = self.lru_msg.get(&message_id) {
+            if let Some(metadata) = self.lru_msg_ref.get(&message_id) {
+                // metadata exist means we already pushed the full message to the solidifier,
+                // or the message doesn't belong to the solidifier
+                if !metadata.referenced_by_milestone_index.unwrap().eq(&try_ms_index) {
+                    self.push_close_to_solidifier(solidifier_id, message_id, try_ms_index);
+                } else {
+                    if let Some(solidifier_handle) = self.solidifier_handles.get(&solidifier_id) {
+                        let full_message = FullMessage::new(message.clone(), metadata.clone());
+                        let full_msg_event = SolidifierEvent::Message(full_message);
+                        let _ = solidifier_handle.send(full_msg_event);
+                    }
+                    // make sure to insert the message if it's requested from syncer
+                    if created_by == CreatedBy::Syncer {
+                        self.ref_ms.0 = try_ms_index;
+                        message_tuple = Some((message.clone(), metadata.clone()));
+                    }
+                }
+            } else {
+                if !(*self.est_ms).eq(&0) {
+                    let highest_ms = *self.est_ms - 1;
+                    if try_ms_index >= highest_ms {
+                        if let Some((pre_ms_index, _)) = self.pending_requests.get_mut(&message_id) {
+                            // check if other solidifier(other milestone) already requested the
+                            // message_id with diff try_ms_index
+                            let old_ms = *pre_ms_index;
+                            if old_ms < try_ms_index {
+                                // close try_ms_index, and keep pre_ms_index to be processed
+                                // eventually
+                                self.push_close_to_solidifier(solidifier_id, message_id, try_ms_index);
+                            } else {
+                                // overwrite pre_ms_index by try_ms_index, which it will be
+                                // eventually processed;
+                                *pre_ms_index = try_ms_index;
+                                // close pre_ms_index(old_ms) as it's greater than what we have atm
+                                // (try_ms_index).
+                                let solidifier_id = (old_ms % (self.collector_count as u32)) as u8;
+                                self.push_close_to_solidifier(solidifier_id, message_id, old_ms);
+                            }
+                        } else {
+                            // add it to back_pressured requests
+                            self.pending_requests
+                                .insert(message_id, (try_ms_index, message.clone()));
+                        };
+                    } else {
+                        to_request.push(message_id);
+                    }
+                } else {
+                    to_request.push(message_id);
+                }
+            }
+        } else {
+            to_request.push(message_id);
+        }
+        // insert the message if requested by syncer to ensure it gets cql responses for all the
+        // requested messages
+        if let Some((message, metadata)) = message_tuple.take() {
+            self.insert_message_with_metadata(message_id.clone(), message, metadata)
+                .unwrap_or_else(|e| {
+                    error!("{}", e);
+                });
+        }
+    }
+    /// Cache the requested full message and insert it, along with cleaning up its rows of a wrong estimated milestone
+    /// index (if any)
+    fn cache_and_insert_full_message(&mut self, message_id: MessageId, message: Message, metadata: MessageMetadata) {
+        let ref_ms = metadata
+            .referenced_by_milestone_index
+            .expect("Expected referenced_by_milestone_index");
+        // set the ref_ms to be the current requested message ref_ms
+        self.ref_ms.0 = ref_ms;
+        // check if msg already in lru cache(if so then it's already presisted)
+        let wrong_msg_est_ms;
+        if let Some((_, est_ms, _)) = self.lru_msg.get_mut(&message_id) {
+            // check if est_ms is not identical to ref_ms
+            if est_ms.0 != ref_ms {
+                wrong_msg_est_ms = Some(*est_ms);
+                // adjust est_ms to match the actual ref_ms
+                est_ms.0 = ref_ms;
+            } else {
+                wrong_msg_est_ms = None;
+            }
+        } else {
+            // add it to the cache in order to not presist it again.
+            self.lru_msg.put(message_id, (None, self.ref_ms, message.clone()));
+            wrong_msg_est_ms = None;
+        }
+        // Cache metadata.
+        self.lru_msg_ref.put(message_id, metadata.clone());
+        if let Some(wrong_est_ms) = wrong_msg_est_ms {
+            self.clean_up_wrong_est_msg(&message_id, &message, wrong_est_ms)
+                .unwrap_or_else(|e| {
+                    error!("{}", e);
+                });
+        }
+        self.insert_message_with_metadata(message_id, message, metadata)
+            .unwrap_or_else(|e| {
+                error!("{}", e);
+            });
+    }
     /// Send an error event to the solidifier for a given milestone index
     fn send_err_solidifiy(&self, try_ms_index: u32) {
         // inform solidifier
@@ -306,17 +350,22 @@ impl Collector {
     }
     /// Process the pending requests for a given milestone index
     fn process_pending_requests(&mut self, milestone_index: u32) {
+        let mut to_request: HashMap<u32, Vec<MessageId>> = HashMap::new();
         self.pending_requests = std::mem::take(&mut self.pending_requests)
             .into_iter()
             .filter_map(|(message_id, (ms, msg))| {
                 if ms < milestone_index {
-                    self.request_full_message(message_id, ms);
+                    to_request.entry(ms).or_default().push(message_id);
                     None
                 } else {
                     Some((message_id, (ms, msg)))
                 }
             })
             .collect();
+        // request them in batches per milestone index
+        for (ms, message_ids) in to_request {
+            self.request_full_messages(message_ids, ms);
+        }
     }
     /// Get the cloned solidifier handle
     fn clone_solidifier_handle(&self, milestone_index: u32) -> SolidifierHandle {
@@ -329,11 +378,14 @@ impl Collector {
             requester_handle.send_event(RequesterEvent::RequestMilestone(milestone_index))
         }; // else collector is shutting down
     }
-    /// Request the full message (i.e., including both message and metadata) of a given message id and
+    /// Request the full messages (i.e., including both message and metadata) of the given message ids and
     /// a milestone index
-    fn request_full_message(&mut self, message_id: MessageId, try_ms_index: u32) {
+    fn request_full_messages(&mut self, message_ids: Vec<MessageId>, try_ms_index: u32) {
+        if message_ids.is_empty() {
+            return;
+        }
         if let Some(mut requester_handle) = self.requester_handles.peek_mut() {
-            requester_handle.send_event(RequesterEvent::RequestFullMessage(message_id, try_ms_index))
+            requester_handle.send_event(RequesterEvent::RequestFullMessages(message_ids, try_ms_index))
         }; // else collector is shutting down
     }
     /// Adjust (refresh) the binary heap which stores the requester handels
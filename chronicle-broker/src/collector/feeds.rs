@@ -0,0 +1,257 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use chronicle_common::metrics::{
+    FEED_DELAY_MS,
+    FEED_EXCLUSIVE_MESSAGES,
+    FEED_MISSED_MESSAGES,
+};
+use std::{
+    collections::hash_map::Entry,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// The smoothing factor of the feed delay EWMA
+const EWMA_ALPHA: f64 = 0.2;
+
+/// The deliveries of the feeds, shared by all the collectors
+static FEEDS_STATUS: tokio::sync::RwLock<Option<HashMap<(&'static str, Url), FeedStatus>>> =
+    tokio::sync::RwLock::const_new(None);
+
+/// The feeds which delivered a message, along with the time of its first delivery
+struct Delivery {
+    first_seen: Instant,
+    feeds: Vec<Url>,
+}
+
+/// The deliveries of a feed which are yet to be reported
+#[derive(Default)]
+struct FeedCounters {
+    delivered: u64,
+    first: u64,
+    exclusive: u64,
+    missed: u64,
+    delays_ms: f64,
+    delayed: u64,
+}
+
+/// Tracks the feeds which delivered every message of a topic, and how far behind the first delivery they were.
+/// Once the divergence window of a message elapses, the active feeds which didn't deliver it are reported as missed.
+pub(crate) struct FeedTracker {
+    topic: &'static str,
+    window: Duration,
+    deliveries: HashMap<MessageId, Delivery>,
+    queue: VecDeque<(Instant, MessageId)>,
+    last_seen: HashMap<Url, Instant>,
+    counters: HashMap<Url, FeedCounters>,
+}
+
+impl FeedTracker {
+    pub(crate) fn new(topic: &'static str, window: Duration) -> Self {
+        Self {
+            topic,
+            window,
+            deliveries: HashMap::new(),
+            queue: VecDeque::new(),
+            last_seen: HashMap::new(),
+            counters: HashMap::new(),
+        }
+    }
+    /// Record the delivery of the message by the feed
+    pub(crate) fn record(&mut self, feed: &Url, message_id: MessageId) {
+        let now = Instant::now();
+        self.last_seen.insert(feed.clone(), now);
+        match self.deliveries.entry(message_id) {
+            Entry::Vacant(entry) => {
+                entry.insert(Delivery {
+                    first_seen: now,
+                    feeds: vec![feed.clone()],
+                });
+                self.queue.push_back((now, message_id));
+                let counters = self.counters.entry(feed.clone()).or_default();
+                counters.delivered += 1;
+                counters.first += 1;
+                counters.delayed += 1;
+            }
+            Entry::Occupied(mut entry) => {
+                let delivery = entry.get_mut();
+                // a feed might deliver the same message again after reconnecting
+                if !delivery.feeds.contains(feed) {
+                    delivery.feeds.push(feed.clone());
+                    let counters = self.counters.entry(feed.clone()).or_default();
+                    counters.delivered += 1;
+                    counters.delays_ms += delivery.first_seen.elapsed().as_secs_f64() * 1000.0;
+                    counters.delayed += 1;
+                }
+            }
+        }
+    }
+    /// Cross-check the messages whose divergence window elapsed, and report the feeds deliveries
+    pub(crate) async fn settle(&mut self) {
+        match self.queue.front() {
+            Some((first_seen, _)) if first_seen.elapsed() >= self.window => (),
+            // nothing to settle yet
+            _ => return,
+        }
+        // only the feeds which delivered anything recently are expected to deliver every message
+        let active_feeds = self
+            .last_seen
+            .iter()
+            .filter(|(_, last_seen)| last_seen.elapsed() < self.window)
+            .map(|(feed, _)| feed.clone())
+            .collect::<Vec<_>>();
+        while let Some((first_seen, message_id)) = self.queue.front().cloned() {
+            if first_seen.elapsed() < self.window {
+                break;
+            }
+            self.queue.pop_front();
+            let delivery = match self.deliveries.remove(&message_id) {
+                Some(delivery) => delivery,
+                None => continue,
+            };
+            if active_feeds.len() < 2 {
+                continue;
+            }
+            if let [feed] = delivery.feeds.as_slice() {
+                warn!(
+                    "Message: {} was only delivered by the {} feed of: {}",
+                    message_id, self.topic, feed
+                );
+                self.counters.entry(feed.clone()).or_default().exclusive += 1;
+            }
+            for feed in active_feeds.iter().filter(|feed| !delivery.feeds.contains(feed)) {
+                self.counters.entry(feed.clone()).or_default().missed += 1;
+            }
+        }
+        self.last_seen.retain(|_, last_seen| last_seen.elapsed() < self.window);
+        self.report().await;
+    }
+    /// Report the pending counters to the shared feeds status and the metrics
+    async fn report(&mut self) {
+        let mut feeds_status = FEEDS_STATUS.write().await;
+        let feeds_status = feeds_status.get_or_insert_with(HashMap::new);
+        for (feed, counters) in self.counters.drain() {
+            let labels = [self.topic, feed.as_str()];
+            let feed_status = feeds_status
+                .entry((self.topic, feed.clone()))
+                .or_insert_with(|| FeedStatus::new(self.topic, feed.clone()));
+            feed_status.delivered += counters.delivered;
+            feed_status.first += counters.first;
+            feed_status.exclusive += counters.exclusive;
+            feed_status.missed += counters.missed;
+            if counters.delayed > 0 {
+                let delay_ms = counters.delays_ms / counters.delayed as f64;
+                feed_status.delay_ms = Some(
+                    feed_status
+                        .delay_ms
+                        .map_or(delay_ms, |ewma| EWMA_ALPHA * delay_ms + (1.0 - EWMA_ALPHA) * ewma),
+                );
+                FEED_DELAY_MS
+                    .with_label_values(&labels)
+                    .set(feed_status.delay_ms.unwrap_or_default());
+            }
+            FEED_EXCLUSIVE_MESSAGES
+                .with_label_values(&labels)
+                .inc_by(counters.exclusive);
+            FEED_MISSED_MESSAGES.with_label_values(&labels).inc_by(counters.missed);
+        }
+    }
+}
+
+/// The deliveries of all the feeds
+pub(crate) async fn feeds_status() -> Vec<FeedStatus> {
+    let mut feeds_status = FEEDS_STATUS
+        .read()
+        .await
+        .iter()
+        .flat_map(|feeds_status| feeds_status.values())
+        .cloned()
+        .collect::<Vec<_>>();
+    feeds_status.sort_by(|a, b| (&a.topic, &a.url).cmp(&(&b.topic, &b.url)));
+    feeds_status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A feed url unique to the test, as the feeds status is shared
+    fn feed() -> Url {
+        Url::parse(&format!("tcp://feed-{}.test:1883", rand::random::<u64>())).unwrap()
+    }
+
+    async fn status(feed: &Url) -> FeedStatus {
+        feeds_status()
+            .await
+            .into_iter()
+            .find(|status| &status.url == feed)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn nothing_to_settle_within_the_window() {
+        let mut tracker = FeedTracker::new("messages", Duration::from_secs(60));
+        let feed = feed();
+        tracker.record(&feed, MessageId::new([1; 32]));
+        // the same delivery again, after a reconnect, isn't counted twice
+        tracker.record(&feed, MessageId::new([1; 32]));
+        tracker.settle().await;
+        assert_eq!(tracker.queue.len(), 1);
+        assert_eq!(tracker.counters[&feed].delivered, 1);
+        assert!(feeds_status().await.iter().all(|status| status.url != feed));
+    }
+
+    #[tokio::test]
+    async fn settle_exclusive_missed_and_delays() {
+        let window = Duration::from_millis(100);
+        let mut tracker = FeedTracker::new("messages", window);
+        let (first, second) = (feed(), feed());
+        // only delivered by the first feed
+        tracker.record(&first, MessageId::new([1; 32]));
+        // delivered by both feeds, the second one lagging behind
+        tracker.record(&first, MessageId::new([2; 32]));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        tracker.record(&second, MessageId::new([2; 32]));
+        tokio::time::sleep(window).await;
+        // keeps both feeds active, while its own window is yet to elapse
+        tracker.record(&first, MessageId::new([3; 32]));
+        tracker.record(&second, MessageId::new([3; 32]));
+        tracker.settle().await;
+        assert_eq!(tracker.queue.len(), 1);
+        assert!(tracker.counters.is_empty());
+
+        let first = status(&first).await;
+        assert_eq!(first.delivered, 3);
+        assert_eq!(first.first, 3);
+        assert_eq!(first.exclusive, 1);
+        assert_eq!(first.missed, 0);
+        assert_eq!(first.delay_ms, Some(0.0));
+        let second = status(&second).await;
+        assert_eq!(second.delivered, 2);
+        assert_eq!(second.first, 0);
+        assert_eq!(second.exclusive, 0);
+        assert_eq!(second.missed, 1);
+        // the average delay of its two deliveries, the first one being 20ms late
+        assert!(second.delay_ms.unwrap() >= 10.0);
+    }
+
+    #[tokio::test]
+    async fn inactive_feeds_miss_nothing() {
+        let window = Duration::from_millis(50);
+        let mut tracker = FeedTracker::new("messages", window);
+        let (first, second) = (feed(), feed());
+        tracker.record(&first, MessageId::new([1; 32]));
+        tracker.record(&second, MessageId::new([2; 32]));
+        tokio::time::sleep(window).await;
+        // both feeds went silent for the whole window, so neither is expected to deliver the other's message
+        tracker.settle().await;
+        assert!(tracker.queue.is_empty());
+        assert!(tracker.last_seen.is_empty());
+        assert_eq!(status(&first).await.missed, 0);
+        assert_eq!(status(&second).await.exclusive, 0);
+    }
+}
@@ -3,6 +3,11 @@
 use super::*;
 use crate::{
     application::*,
+    mqtt::{
+        Messages,
+        MessagesReferenced,
+        Topic,
+    },
     requester::*,
     solidifier::*,
 };
@@ -15,6 +20,7 @@ use bee_message::{
     },
     prelude::MilestoneIndex,
 };
+use feeds::FeedTracker;
 use std::collections::{
     BinaryHeap,
     VecDeque,
@@ -31,6 +37,7 @@ use std::ops::{
 };
 
 mod event_loop;
+pub(crate) mod feeds;
 mod init;
 mod terminating;
 use reqwest::Client;
@@ -56,10 +63,12 @@ builder!(CollectorBuilder {
 pub enum CollectorEvent {
     /// Requested Message and Metadata, u32 is the milestoneindex
     MessageAndMeta(RequesterId, u32, Option<MessageId>, Option<FullMessage>),
-    /// Newly seen message from feed source(s)
-    Message(MessageId, Message),
-    /// Newly seen MessageMetadataObj from feed source(s)
-    MessageReferenced(MessageMetadata),
+    /// Requested milestone cone, which includes all the messages referenced by the u32 milestone index
+    MilestoneCone(RequesterId, u32, Vec<FullMessage>),
+    /// Newly seen message from the feed source with the given url
+    Message(Url, MessageId, Message),
+    /// Newly seen MessageMetadataObj from the feed source with the given url
+    MessageReferenced(Url, MessageMetadata),
     /// Ask requests from solidifier(s)
     Ask(AskCollector),
     /// Shutdown the collector
@@ -74,8 +83,8 @@ pub enum Internal {
 }
 /// Messages for asking the collector for missing data
 pub enum AskCollector {
-    /// Solidifier(s) will use this variant, u8 is solidifier_id
-    FullMessage(u8, u32, MessageId, CreatedBy),
+    /// Solidifier(s) will use this variant to ask for a batch of full messages, u8 is solidifier_id
+    FullMessages(u8, u32, Vec<MessageId>, CreatedBy),
     /// Ask for a milestone with the given index
     MilestoneMessage(u32),
 }
@@ -194,6 +203,10 @@ pub struct Collector {
     partition_config: PartitionConfig,
     /// The `Chronicle` keyspace
     default_keyspace: ChronicleKeyspace,
+    /// The feeds which delivered the recent messages
+    messages_feeds: FeedTracker,
+    /// The feeds which delivered the recent referenced messages
+    referenced_feeds: FeedTracker,
 }
 
 impl<H: ChronicleBrokerScope> ActorBuilder<BrokerHandle<H>> for CollectorBuilder {}
@@ -222,6 +235,8 @@ impl Builder for CollectorBuilder {
             .as_ref()
             .map(|config| config.partition_config.clone())
             .unwrap_or(PartitionConfig::default());
+        let feed_divergence_window =
+            std::time::Duration::from_secs(get_config().broker_config.feed_divergence_window_secs.unwrap_or(10));
         Self::State {
             service: Service::new(),
             lru_msg: LruCache::new(lru_cap),
@@ -245,6 +260,8 @@ impl Builder for CollectorBuilder {
             reqwest_client: self.reqwest_client.unwrap(),
             partition_config,
             default_keyspace,
+            messages_feeds: FeedTracker::new(Messages::name(), feed_divergence_window),
+            referenced_feeds: FeedTracker::new(MessagesReferenced::name(), feed_divergence_window),
         }
         .set_name()
     }
@@ -0,0 +1,97 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    BrokerSocketMsg,
+    ChronicleBrokerThrough,
+    ControlReply,
+};
+use futures::{
+    SinkExt,
+    StreamExt,
+};
+use std::{
+    net::SocketAddr,
+    time::Duration,
+};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::Message,
+};
+use url::Url;
+
+/// The reason a relay to the broker websocket got no outcome
+#[derive(Debug)]
+pub enum RelayError {
+    /// The broker websocket is unreachable, or the connection got closed before the replies
+    Unavailable(String),
+    /// The replies didn't arrive within the timeout
+    Timeout(Duration),
+}
+
+impl std::fmt::Display for RelayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RelayError::Unavailable(reason) => write!(f, "The broker is unavailable: {}", reason),
+            RelayError::Timeout(timeout) => write!(f, "No reply from the broker within {:?}", timeout),
+        }
+    }
+}
+
+impl std::error::Error for RelayError {}
+
+/// Send the commands one by one to the broker websocket at the address, waiting for the `ControlReply` to each
+/// request (see `ChronicleBrokerThrough::into_request`), and stop at the first rejection, which is returned as the
+/// inner error. The commands which aren't requests are sent without waiting for a reply.
+pub async fn relay_to_broker(
+    address: SocketAddr,
+    commands: &[ChronicleBrokerThrough],
+    timeout: Duration,
+) -> Result<Result<(), String>, RelayError> {
+    let url = Url::parse(&format!("ws://{}/", address)).map_err(|e| RelayError::Unavailable(e.to_string()))?;
+    let (mut stream, _) = connect_async(url)
+        .await
+        .map_err(|e| RelayError::Unavailable(format!("unable to connect to {}: {}", address, e)))?;
+    let outcome = tokio::time::timeout(timeout, async {
+        for command in commands {
+            let outgoing = serde_json::to_string(&BrokerSocketMsg::ChronicleBroker(command))
+                .map_err(|e| RelayError::Unavailable(e.to_string()))?;
+            stream
+                .send(Message::text(outgoing))
+                .await
+                .map_err(|e| RelayError::Unavailable(format!("unable to send the command: {}", e)))?;
+            let id = match command {
+                ChronicleBrokerThrough::Request { id, .. } => *id,
+                _ => continue,
+            };
+            let result = loop {
+                match stream.next().await {
+                    Some(Ok(Message::Text(text))) => {
+                        if let Ok(BrokerSocketMsg::ChronicleBroker(reply)) =
+                            serde_json::from_str::<BrokerSocketMsg<ControlReply>>(&text)
+                        {
+                            if reply.id == id {
+                                break reply.result;
+                            }
+                        }
+                    }
+                    Some(Ok(_)) => (),
+                    _ => {
+                        return Err(RelayError::Unavailable(format!(
+                            "the connection to {} got closed without a reply",
+                            address
+                        )))
+                    }
+                }
+            };
+            if let Err(error) = result {
+                return Ok(Err(error));
+            }
+        }
+        Ok(Ok(()))
+    })
+    .await
+    .map_err(|_| RelayError::Timeout(timeout))?;
+    stream.close(None).await.ok();
+    outcome
+}
@@ -0,0 +1,130 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+#[async_trait::async_trait]
+impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Exporter {
+    async fn event_loop(
+        &mut self,
+        mut status: Result<(), Need>,
+        supervisor: &mut Option<BrokerHandle<H>>,
+    ) -> Result<(), Need> {
+        status?;
+        info!("{} is running", self.get_name());
+        self.service.update_status(ServiceStatus::Running);
+        let event = BrokerEvent::Children(BrokerChild::Exporter(self.service.clone(), status));
+        if let Some(supervisor) = supervisor {
+            supervisor.send(event).ok();
+            while let Some(event) = self.inbox.recv().await {
+                match event {
+                    ExporterEvent::ProcessMore => {
+                        if self.service.is_stopping() {
+                            continue;
+                        }
+                        if let Some((milestone_index, end_of_range)) = self.next_milestone_index() {
+                            self.export(supervisor, milestone_index).await.map_err(|e| {
+                                error!("Unable to export milestone index: {}, error: {}", milestone_index, e);
+                                Need::Abort
+                            })?;
+                            if end_of_range {
+                                // the next synced milestone is not adjacent, so we close the LogFile
+                                self.finish_log_file().await.map_err(|e| {
+                                    error!("Unable to finish exported LogFile, error: {}", e);
+                                    Need::Abort
+                                })?;
+                            }
+                            // process one more
+                            if let Some(handle) = self.handle.as_ref() {
+                                handle.send(ExporterEvent::ProcessMore).ok();
+                            }
+                        } else {
+                            info!("Exported the synced milestones of: {}", self.get_name());
+                            return Ok(());
+                        }
+                    }
+                    ExporterEvent::Shutdown => {
+                        self.service.update_status(ServiceStatus::Stopping);
+                        self.handle.take();
+                        status = Err(Need::Abort);
+                    }
+                }
+            }
+            status
+        } else {
+            Err(Need::Abort)
+        }
+    }
+}
+
+impl Exporter {
+    /// Takes the lowest milestone index which is yet to be exported,
+    /// and identify whether it's the last one in its synced range.
+    fn next_milestone_index(&mut self) -> Option<(u32, bool)> {
+        let range = self.synced_ranges.last_mut()?;
+        let milestone_index = range.start;
+        range.start += 1;
+        let end_of_range = range.is_empty();
+        if end_of_range {
+            self.synced_ranges.pop();
+        }
+        Some((milestone_index, end_of_range))
+    }
+    async fn export<H: ChronicleBrokerScope>(
+        &mut self,
+        supervisor: &BrokerHandle<H>,
+        milestone_index: u32,
+    ) -> anyhow::Result<()> {
+        let skipped;
+        if let Some(milestone_data) = self.fetch_milestone_data(milestone_index).await? {
+            self.append(milestone_data).await?;
+            skipped = false;
+        } else {
+            warn!(
+                "Skipping milestone index: {}, as it's not stored in the keyspace",
+                milestone_index
+            );
+            // the LogFile must only contain sequential milestones
+            self.finish_log_file().await?;
+            skipped = true;
+        }
+        let exporter_session = ExporterSession::ProgressBar {
+            from_ms: self.export_range.start,
+            to_ms: self.export_range.end,
+            milestone_index,
+            skipped,
+        };
+        supervisor.send(BrokerEvent::Exporter(exporter_session)).ok();
+        Ok(())
+    }
+    pub(crate) async fn append(&mut self, milestone_data: MilestoneData) -> anyhow::Result<()> {
+        let milestone_index = milestone_data.milestone_index();
+        let milestone_data_line = milestone_data_line(&milestone_data)?;
+        if let Some(log_file) = self.log_file.as_ref() {
+            let exceeded = (milestone_data_line.len() as u64) + log_file.len() >= self.max_log_size;
+            if exceeded || log_file.to_ms_index() != milestone_index {
+                self.finish_log_file().await?;
+            }
+        }
+        if self.log_file.is_none() {
+            let log_file = LogFile::create(&self.dir_path, milestone_index, None).await?;
+            self.log_file.replace(log_file);
+        }
+        self.log_file
+            .as_mut()
+            .ok_or_else(|| anyhow!("Expected in progress LogFile"))?
+            .append_line(&milestone_data_line)
+            .await
+    }
+    pub(crate) async fn finish_log_file(&mut self) -> anyhow::Result<()> {
+        if let Some(mut log_file) = self.log_file.take() {
+            log_file.finish(&self.dir_path).await?;
+            info!(
+                "Exported LogFile: {}to{}.log",
+                log_file.from_ms_index(),
+                log_file.to_ms_index()
+            );
+        }
+        Ok(())
+    }
+}
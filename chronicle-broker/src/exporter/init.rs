@@ -0,0 +1,73 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+#[async_trait::async_trait]
+impl<H: ChronicleBrokerScope> Init<BrokerHandle<H>> for Exporter {
+    async fn init(&mut self, status: Result<(), Need>, supervisor: &mut Option<BrokerHandle<H>>) -> Result<(), Need> {
+        info!(
+            "{} is Initializing, with permanode keyspace: {}",
+            self.get_name(),
+            self.default_keyspace.name()
+        );
+        if let Some(supervisor) = supervisor {
+            self.service.update_status(ServiceStatus::Initializing);
+            let event = BrokerEvent::Children(BrokerChild::Exporter(self.service.clone(), Ok(())));
+            supervisor.send(event).ok();
+            // create directory first
+            if let Err(e) = tokio::fs::create_dir_all(&self.dir_path).await {
+                error!("Unable to create export directory: {:?}, error: {}", self.dir_path, e);
+                let event = BrokerEvent::Exporter(ExporterSession::PathError {
+                    path: self.dir_path.clone(),
+                    msg: "Unable to create export directory".into(),
+                });
+                supervisor.send(event).ok();
+                return Err(Need::Abort);
+            }
+            // fetch sync data from the keyspace
+            let sync_range = SyncRange {
+                from: self.export_range.start,
+                to: self.export_range.end,
+            };
+            let sync_data = SyncData::try_fetch(&self.default_keyspace, &sync_range, self.retries_per_query)
+                .await
+                .map_err(|e| {
+                    error!("Unable to fetch SyncData {}", e);
+                    Need::Abort
+                })?;
+            self.init_synced_ranges(sync_data);
+            // trigger the exporting process
+            self.handle
+                .as_ref()
+                .ok_or(Need::Abort)?
+                .send(ExporterEvent::ProcessMore)
+                .map_err(|_| Need::Abort)?;
+            status
+        } else {
+            Err(Need::Abort)
+        }
+    }
+}
+
+impl Exporter {
+    fn init_synced_ranges(&mut self, mut sync_data: SyncData) {
+        // Only synced milestones (whether logged or not) have their data stored in the keyspace
+        let mut ranges = std::mem::take(&mut sync_data.completed);
+        ranges.append(&mut sync_data.synced_but_unlogged);
+        ranges.sort_by_key(|r| r.start);
+        // Merge the adjacent ranges, so their milestones end up in the same LogFile
+        let mut merged: Vec<Range<u32>> = Vec::new();
+        for range in ranges {
+            if let Some(last) = merged.last_mut() {
+                if last.end == range.start {
+                    last.end = range.end;
+                    continue;
+                }
+            }
+            merged.push(range);
+        }
+        merged.reverse();
+        self.synced_ranges = merged;
+    }
+}
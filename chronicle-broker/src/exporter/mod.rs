@@ -0,0 +1,334 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+use crate::{
+    application::{
+        BrokerChild,
+        BrokerEvent,
+        BrokerHandle,
+        ChronicleBrokerScope,
+    },
+    archiver::{
+        milestone_data_line,
+        LogFile,
+        MAX_LOG_SIZE,
+    },
+};
+use bee_message::{
+    milestone::{
+        Milestone,
+        MilestoneIndex,
+    },
+    payload::Payload,
+};
+use std::{
+    collections::HashSet,
+    ops::{
+        Deref,
+        DerefMut,
+        Range,
+    },
+    path::PathBuf,
+};
+
+mod event_loop;
+mod init;
+mod terminating;
+
+// Exporter builder
+builder!(ExporterBuilder {
+    dir_path: PathBuf,
+    export_range: Range<u32>,
+    max_log_size: u64,
+    retries_per_query: usize
+});
+
+/// Exporter events
+pub enum ExporterEvent {
+    /// Indicator to export the next milestone data
+    ProcessMore,
+    /// Shutdown the exporter
+    Shutdown,
+}
+
+/// ExporterHandle to be passed to the supervisor in order to shutdown
+#[derive(Clone)]
+pub struct ExporterHandle {
+    pub(crate) tx: tokio::sync::mpsc::UnboundedSender<ExporterEvent>,
+}
+
+/// ExporterInbox is used to recv events
+pub struct ExporterInbox {
+    pub(crate) rx: tokio::sync::mpsc::UnboundedReceiver<ExporterEvent>,
+}
+
+impl Deref for ExporterHandle {
+    type Target = tokio::sync::mpsc::UnboundedSender<ExporterEvent>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tx
+    }
+}
+
+impl DerefMut for ExporterHandle {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.tx
+    }
+}
+
+impl Deref for ExporterInbox {
+    type Target = tokio::sync::mpsc::UnboundedReceiver<ExporterEvent>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rx
+    }
+}
+
+impl DerefMut for ExporterInbox {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.rx
+    }
+}
+
+impl Shutdown for ExporterHandle {
+    fn shutdown(self) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        self.send(ExporterEvent::Shutdown).ok();
+        None
+    }
+}
+
+/// Exporter state
+pub struct Exporter {
+    /// The exporter service
+    service: Service,
+    /// The directory where the exported LogFiles are written
+    dir_path: PathBuf,
+    /// The range of requested milestones to export
+    export_range: Range<u32>,
+    /// The maximum bytes size for a given log file
+    max_log_size: u64,
+    /// The default Chronicle keyspace
+    default_keyspace: ChronicleKeyspace,
+    /// The number of retires per query
+    retries_per_query: usize,
+    /// The synced milestones ranges which are yet to be exported, the lowest range is the last one
+    synced_ranges: Vec<Range<u32>>,
+    /// The in progress log file
+    log_file: Option<LogFile>,
+    /// The exporter handle
+    handle: Option<ExporterHandle>,
+    /// The exporter inbox to receive events
+    inbox: ExporterInbox,
+}
+
+impl<H: ChronicleBrokerScope> ActorBuilder<BrokerHandle<H>> for ExporterBuilder {}
+
+/// Implementation of builder
+impl Builder for ExporterBuilder {
+    type State = Exporter;
+    fn build(self) -> Self::State {
+        self.build_with(&get_config())
+    }
+}
+
+impl ExporterBuilder {
+    /// Build the exporter with the given config
+    pub(crate) fn build_with(self, config: &chronicle_common::config::Config) -> Exporter {
+        let default_keyspace = ChronicleKeyspace::new(
+            config
+                .storage_config
+                .keyspaces
+                .first()
+                .and_then(|keyspace| Some(keyspace.name.clone()))
+                .unwrap_or("permanode".to_owned()),
+        );
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let handle = Some(ExporterHandle { tx });
+        let inbox = ExporterInbox { rx };
+        Exporter {
+            service: Service::new(),
+            dir_path: self.dir_path.expect("Expected export directory path"),
+            export_range: self.export_range.expect("Expected export range"),
+            max_log_size: self
+                .max_log_size
+                .or(config.broker_config.max_log_size)
+                .unwrap_or(MAX_LOG_SIZE),
+            default_keyspace,
+            retries_per_query: self.retries_per_query.unwrap_or(10),
+            synced_ranges: Vec::new(),
+            log_file: None,
+            handle,
+            inbox,
+        }
+        .set_name()
+    }
+}
+
+impl Exporter {
+    pub(crate) fn clone_handle(&self) -> Option<ExporterHandle> {
+        self.handle.clone()
+    }
+}
+
+/// Implement `Name` trait of the Exporter
+impl Name for Exporter {
+    fn set_name(mut self) -> Self {
+        let name = format!("Exporter{}to{}", self.export_range.start, self.export_range.end);
+        self.service.update_name(name);
+        self
+    }
+    fn get_name(&self) -> String {
+        self.service.get_name()
+    }
+}
+
+#[async_trait::async_trait]
+impl<H: ChronicleBrokerScope> AknShutdown<Exporter> for BrokerHandle<H> {
+    async fn aknowledge_shutdown(self, mut state: Exporter, status: Result<(), Need>) {
+        state.service.update_status(ServiceStatus::Stopped);
+        let event = BrokerEvent::Children(BrokerChild::Exporter(state.service.clone(), status));
+        let _ = self.send(event);
+    }
+}
+
+impl Exporter {
+    /// Reconstruct the milestone data of the given milestone index from the milestones and messages tables.
+    /// Returns None if the milestone is not stored in the keyspace.
+    pub(crate) async fn fetch_milestone_data(&self, milestone_index: u32) -> anyhow::Result<Option<MilestoneData>> {
+        let milestone: Milestone = match self.query(MilestoneIndex(milestone_index)).await? {
+            Some(milestone) => milestone,
+            None => return Ok(None),
+        };
+        let mut milestone_data = MilestoneData::new(milestone_index, CreatedBy::Syncer);
+        let mut visited = HashSet::new();
+        visited.insert(*milestone.message_id());
+        let mut pending = vec![*milestone.message_id()];
+        // Walk the past cone of the milestone message level by level, collecting every message
+        // which got referenced by this milestone, same as the solidifier does.
+        while !pending.is_empty() {
+            let full_messages = futures::future::join_all(
+                pending
+                    .drain(..)
+                    .map(|message_id| self.query::<MessageId, FullMessage>(message_id)),
+            )
+            .await;
+            for full_message in full_messages {
+                // Note: the milestone is only marked as synced once its whole cone got stored,
+                // therefore a parent which is not in the messages table belongs to an older milestone.
+                let full_message = match full_message? {
+                    Some(full_message) => full_message,
+                    None => continue,
+                };
+                if full_message.ref_ms() != Some(milestone_index) {
+                    continue;
+                }
+                if let Some(Payload::Milestone(milestone_payload)) = full_message.message().payload() {
+                    if milestone_payload.essence().index().0 == milestone_index {
+                        milestone_data.set_milestone(milestone_payload.clone());
+                    }
+                }
+                for parent_id in full_message.message().parents().iter() {
+                    if !parent_id.eq(&MessageId::null()) && visited.insert(*parent_id) {
+                        pending.push(*parent_id);
+                    }
+                }
+                milestone_data.add_full_message(full_message);
+            }
+        }
+        ensure!(
+            milestone_data.check_if_completed(),
+            "Unable to reconstruct the milestone data for milestone index: {}",
+            milestone_index
+        );
+        Ok(Some(milestone_data))
+    }
+
+    async fn query<K, V>(&self, key: K) -> anyhow::Result<Option<V>>
+    where
+        ChronicleKeyspace: Select<K, V>,
+        K: 'static + Send + Clone,
+        V: 'static + Send + Clone,
+    {
+        crate::storage::query(&self.default_keyspace, key, self.retries_per_query).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bee_message::{
+        parents::Parents,
+        MessageBuilder,
+    };
+    use bee_pow::providers::miner::Miner;
+
+    fn milestone_data(milestone_index: u32, parents: &[u8]) -> MilestoneData {
+        let mut milestone_data = MilestoneData::new(milestone_index, CreatedBy::Syncer);
+        for parent in parents {
+            let message = MessageBuilder::<Miner>::new()
+                .with_network_id(0)
+                .with_parents(Parents::new(vec![MessageId::new([*parent; 32])]).unwrap())
+                .finish()
+                .unwrap();
+            let metadata = MessageMetadata {
+                message_id: message.id().0,
+                parent_message_ids: message.parents().iter().copied().collect(),
+                is_solid: true,
+                referenced_by_milestone_index: Some(milestone_index),
+                ledger_inclusion_state: Some(LedgerInclusionState::NoTransaction),
+                should_promote: None,
+                should_reattach: None,
+            };
+            milestone_data.add_full_message(FullMessage::new(message, metadata));
+        }
+        milestone_data
+    }
+
+    #[tokio::test]
+    async fn export_round_trip() {
+        let dir = std::env::temp_dir().join(format!("chronicle-{}", rand::random::<u64>()));
+        let (export_dir, archive_dir) = (dir.join("export"), dir.join("archive"));
+        tokio::fs::create_dir_all(&export_dir).await.unwrap();
+        tokio::fs::create_dir_all(&archive_dir).await.unwrap();
+        let mut exporter = ExporterBuilder::new()
+            .dir_path(export_dir.clone())
+            .export_range(1..3)
+            .build_with(&Default::default());
+        // the archiver appends the lines of the consecutive milestones to the same LogFile
+        let mut log_file = LogFile::create(&archive_dir, 1, None).await.unwrap();
+        let mut lines = Vec::new();
+        for milestone_data in vec![milestone_data(1, &[1, 2]), milestone_data(2, &[3])] {
+            let line = milestone_data_line(&milestone_data).unwrap();
+            log_file.append_line(&line).await.unwrap();
+            lines.push(line);
+            exporter.append(milestone_data).await.unwrap();
+        }
+        log_file.finish(&archive_dir).await.unwrap();
+        exporter.finish_log_file().await.unwrap();
+
+        let exported = tokio::fs::read(export_dir.join("1to3.log")).await.unwrap();
+        let archived = tokio::fs::read(archive_dir.join("1to3.log")).await.unwrap();
+        assert_eq!(exported, archived);
+
+        // and the exported LogFile parses back into the same milestone data
+        let mut log_file = LogFile::try_from(export_dir.join("1to3.log")).unwrap();
+        assert_eq!((log_file.from_ms_index(), log_file.to_ms_index()), (1, 3));
+        for line in lines.iter() {
+            let milestone_data: MilestoneData = serde_json::from_slice(line).unwrap();
+            let parsed = log_file.next().await.unwrap().unwrap();
+            assert_eq!(parsed.milestone_index(), milestone_data.milestone_index());
+            assert_eq!(parsed.messages().len(), milestone_data.messages().len());
+            for (message_id, full_message) in milestone_data.messages() {
+                assert_eq!(
+                    serde_json::to_string(&parsed.messages()[message_id]).unwrap(),
+                    serde_json::to_string(full_message).unwrap()
+                );
+            }
+        }
+        assert!(log_file.next().await.unwrap().is_none());
+        tokio::fs::remove_dir_all(&dir).await.ok();
+    }
+}
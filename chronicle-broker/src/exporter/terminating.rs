@@ -0,0 +1,36 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+#[async_trait::async_trait]
+impl<H: ChronicleBrokerScope> Terminating<BrokerHandle<H>> for Exporter {
+    async fn terminating(
+        &mut self,
+        status: Result<(), Need>,
+        supervisor: &mut Option<BrokerHandle<H>>,
+    ) -> Result<(), Need> {
+        info!("{} is terminating", self.get_name());
+        // finish the in progress log file (if any), it only contains fully exported milestones data
+        if let Err(e) = self.finish_log_file().await {
+            error!("Unable to finish in progress exported LogFile, error: {}", e);
+        }
+        let msg;
+        if status.is_ok() {
+            msg = "done".into();
+        } else {
+            msg = "failed".into();
+        }
+        let exporter_session = ExporterSession::Finish {
+            from_ms: self.export_range.start,
+            to_ms: self.export_range.end,
+            msg,
+        };
+        let supervisor = supervisor.as_mut().expect("Expected BrokerHandle");
+        supervisor.send(BrokerEvent::Exporter(exporter_session)).ok();
+        self.service.update_status(ServiceStatus::Stopping);
+        let event = BrokerEvent::Children(BrokerChild::Exporter(self.service.clone(), status));
+        let _ = supervisor.send(event);
+        status
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use futures::stream::StreamExt;
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+
+/// A MQTT broker feed source
+pub(crate) struct MqttSource {
+    url: Url,
+    client: Arc<AsyncClient>,
+    stream: futures::channel::mpsc::Receiver<Option<paho_mqtt::Message>>,
+}
+
+impl MqttSource {
+    /// Create the MQTT client of the broker, which fails only if the url is invalid or has a bad protocol
+    pub(crate) fn new(url: &Url, client_id: &str, stream_capacity: usize) -> anyhow::Result<Self> {
+        let create_opts = CreateOptionsBuilder::new()
+            .server_uri(url.as_str())
+            .client_id(client_id)
+            .persistence(None)
+            .finalize();
+        let mut client = AsyncClient::new(create_opts)?;
+        let stream = client.get_stream(stream_capacity);
+        Ok(Self {
+            url: url.clone(),
+            client: Arc::new(client),
+            stream,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl FeedSource for MqttSource {
+    async fn subscribe(&mut self, topic: &'static str, qos: i32) -> anyhow::Result<Disconnect> {
+        let conn_opts = paho_mqtt::ConnectOptionsBuilder::new()
+            .keep_alive_interval(Duration::from_secs(120))
+            .mqtt_version(paho_mqtt::MQTT_VERSION_3_1_1)
+            .clean_session(false)
+            .connect_timeout(Duration::from_secs(60))
+            .finalize();
+        // connect client with the remote broker
+        self.client
+            .connect(conn_opts)
+            .await
+            .map_err(|e| anyhow!("Unable to connect AsyncClient: {}, error: {}", self.url, e))?;
+        info!("Connected AsyncClient: {}", self.url);
+        self.client
+            .subscribe(topic, qos)
+            .await
+            .map_err(|e| anyhow!("Unable to subscribe AsyncClient: {}, error: {}", self.url, e))?;
+        let client = self.client.clone();
+        Ok(Arc::new(move || {
+            client.disconnect(None);
+        }))
+    }
+    async fn next_payload(&mut self) -> Option<Vec<u8>> {
+        // the stream yields None once the connection is lost
+        self.stream.next().await.flatten().map(|msg| msg.payload().to_vec())
+    }
+}
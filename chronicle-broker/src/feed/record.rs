@@ -0,0 +1,150 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use std::{
+    sync::atomic::{
+        AtomicBool,
+        Ordering,
+    },
+    time::Instant,
+};
+use tokio::{
+    io::AsyncWriteExt,
+    sync::mpsc::{
+        unbounded_channel,
+        UnboundedSender,
+    },
+};
+
+/// The active recording, along with the time it started
+static RECORDER: tokio::sync::RwLock<Option<(Instant, UnboundedSender<Record>)>> = tokio::sync::RwLock::const_new(None);
+/// Whether a recording is active, so the events skip the recorder lock otherwise
+static RECORDING: AtomicBool = AtomicBool::new(false);
+
+/// A recorded event, one json record per line of the recording file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    /// The milliseconds elapsed since the recording started
+    pub elapsed_ms: u64,
+    /// The recorded event
+    pub event: RecordedEvent,
+}
+
+/// The traffic captured by the recorder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RecordedEvent {
+    /// A payload received from a feed
+    Feed {
+        /// The feed topic
+        topic: String,
+        /// The feed url
+        url: Url,
+        /// The hex encoded payload
+        payload: String,
+    },
+    /// A message fetched from an api endpoint
+    Message {
+        /// The hex encoded raw message
+        raw: String,
+        /// The message metadata
+        metadata: MessageMetadata,
+    },
+    /// A milestone fetched from an api endpoint
+    Milestone {
+        /// The milestone index
+        index: u32,
+        /// The milestone message id
+        message_id: MessageId,
+    },
+}
+
+/// Start recording the feeds and the api endpoints traffic into the file, which gets appended to
+pub async fn start_recording(path: &str) -> anyhow::Result<()> {
+    let file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .map_err(|e| anyhow!("Unable to open the recording file: {}, error: {}", path, e))?;
+    let (tx, mut rx) = unbounded_channel::<Record>();
+    let path = path.to_owned();
+    let writer = async move {
+        let mut file = tokio::io::BufWriter::new(file);
+        while let Some(record) = rx.recv().await {
+            let mut line = match serde_json::to_vec(&record) {
+                Ok(line) => line,
+                Err(e) => {
+                    error!("Unable to serialize record: {:?}, error: {}", record, e);
+                    continue;
+                }
+            };
+            line.push(b'\n');
+            if let Err(e) = file.write_all(&line).await {
+                error!("Unable to write to the recording file: {}, error: {}", path, e);
+                break;
+            }
+            // flush once we caught up with the recorded events
+            if rx.is_empty() {
+                file.flush().await.ok();
+            }
+        }
+        file.flush().await.ok();
+        info!("Stopped recording into: {}", path);
+    };
+    tokio::spawn(writer);
+    RECORDER.write().await.replace((Instant::now(), tx));
+    RECORDING.store(true, Ordering::Release);
+    info!("Recording the feeds and the api endpoints traffic");
+    Ok(())
+}
+
+/// Stop the active recording, if any
+pub async fn stop_recording() {
+    RECORDING.store(false, Ordering::Release);
+    RECORDER.write().await.take();
+}
+
+/// Record the event if the recording is active
+pub(crate) async fn record(event: impl FnOnce() -> RecordedEvent) {
+    if !RECORDING.load(Ordering::Acquire) {
+        return;
+    }
+    if let Some((started, tx)) = RECORDER.read().await.as_ref() {
+        let record = Record {
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            event: event(),
+        };
+        tx.send(record).ok();
+    }
+}
+
+/// Read all the records of the recording file
+pub async fn read_recording(path: &str) -> anyhow::Result<Vec<Record>> {
+    let recording = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| anyhow!("Unable to read the recording file: {}, error: {}", path, e))?;
+    recording
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            serde_json::from_str::<Record>(line)
+                .map_err(|e| anyhow!("Invalid record at line {} of: {}, error: {}", i + 1, path, e))
+        })
+        .collect()
+}
+
+/// Record the message fetched from an api endpoint, along with its metadata
+pub(crate) async fn record_full_message(full_message: &FullMessage) {
+    record(|| RecordedEvent::Message {
+        raw: hex::encode(full_message.message().pack_new()),
+        metadata: full_message.metadata().clone(),
+    })
+    .await
+}
+
+/// Record the milestone fetched from an api endpoint
+pub(crate) async fn record_milestone(index: u32, message_id: MessageId) {
+    record(|| RecordedEvent::Milestone { index, message_id }).await
+}
@@ -0,0 +1,113 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use std::{
+    sync::Arc,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+use tokio::sync::Notify;
+
+/// A feed source which replays the feed payloads of a recording, `file:///path/to/recording?speed=10`.
+/// The payloads keep their original pace, divided by the speed (1 by default), or get replayed as fast as
+/// possible with a zero speed. Once the recording is over, the source idles until it gets disconnected.
+pub(crate) struct ReplaySource {
+    url: Url,
+    path: String,
+    speed: f64,
+    payloads: std::vec::IntoIter<(u64, Vec<u8>)>,
+    started: Option<(Instant, u64)>,
+    disconnected: Arc<Notify>,
+}
+
+impl ReplaySource {
+    /// Create the replay source, which fails only if the url is not a valid recording one
+    pub(crate) fn new(url: &Url) -> anyhow::Result<Self> {
+        ensure!(
+            url.scheme() == "file",
+            "Unsupported replay url: {}, expected a file:// url",
+            url
+        );
+        let path = url
+            .to_file_path()
+            .map_err(|_| anyhow!("Invalid replay url: {}, expected an absolute file path", url))?;
+        let speed = match url.query_pairs().find(|(key, _)| key == "speed") {
+            Some((_, speed)) => speed
+                .parse::<f64>()
+                .map_err(|e| anyhow!("Invalid replay speed: {}, error: {}", speed, e))?,
+            None => 1.0,
+        };
+        ensure!(
+            speed.is_finite() && speed >= 0.0,
+            "Invalid replay speed: {}, expected a non-negative number",
+            speed
+        );
+        Ok(Self {
+            url: url.clone(),
+            path: path.to_string_lossy().into_owned(),
+            speed,
+            payloads: Vec::new().into_iter(),
+            started: None,
+            disconnected: Arc::new(Notify::new()),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl FeedSource for ReplaySource {
+    async fn subscribe(&mut self, topic: &'static str, _qos: i32) -> anyhow::Result<Disconnect> {
+        let mut payloads = Vec::new();
+        for record in record::read_recording(&self.path).await? {
+            if let RecordedEvent::Feed {
+                topic: recorded_topic,
+                payload,
+                ..
+            } = record.event
+            {
+                if recorded_topic != topic {
+                    continue;
+                }
+                match hex::decode(&payload) {
+                    Ok(payload) => payloads.push((record.elapsed_ms, payload)),
+                    Err(e) => warn!("Replay: {}, skipping invalid payload, error: {}", self.url, e),
+                }
+            }
+        }
+        info!(
+            "Replaying {} payloads of topic: {}, from: {}",
+            payloads.len(),
+            topic,
+            self.url
+        );
+        self.payloads = payloads.into_iter();
+        self.started = None;
+        let disconnected = self.disconnected.clone();
+        Ok(Arc::new(move || disconnected.notify_one()))
+    }
+    async fn next_payload(&mut self) -> Option<Vec<u8>> {
+        let (elapsed_ms, payload) = match self.payloads.next() {
+            Some(next) => next,
+            None => {
+                info!("Replay: {} is over", self.url);
+                self.disconnected.notified().await;
+                return None;
+            }
+        };
+        if self.speed > 0.0 {
+            let (started, first_ms) = *self.started.get_or_insert((Instant::now(), elapsed_ms));
+            let offset = elapsed_ms.saturating_sub(first_ms) as f64 / 1000.0 / self.speed;
+            let due = started + Duration::from_secs_f64(offset);
+            tokio::select! {
+                _ = self.disconnected.notified() => return None,
+                _ = tokio::time::sleep_until(due.into()) => (),
+            }
+        }
+        Some(payload)
+    }
+    fn supervised(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use futures::{
+    stream::BoxStream,
+    SinkExt,
+    StreamExt,
+};
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{
+        Error,
+        Message,
+    },
+};
+
+/// A websocket event stream feed source. Once connected, it subscribes to the topic with a
+/// `{"subscribe": "<topic>"}` text frame, then every received frame holds the payload of a single event,
+/// as it would be published by the MQTT broker of the node.
+pub(crate) struct WebsocketSource {
+    url: Url,
+    stream: Option<BoxStream<'static, Result<Message, Error>>>,
+    disconnected: Arc<Notify>,
+}
+
+impl WebsocketSource {
+    /// Create the event stream source, which fails only if the url is not a websocket one
+    pub(crate) fn new(url: &Url) -> anyhow::Result<Self> {
+        ensure!(
+            matches!(url.scheme(), "ws" | "wss"),
+            "Unsupported event stream url: {}, expected a ws:// or wss:// url",
+            url
+        );
+        Ok(Self {
+            url: url.clone(),
+            stream: None,
+            disconnected: Arc::new(Notify::new()),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl FeedSource for WebsocketSource {
+    async fn subscribe(&mut self, topic: &'static str, _qos: i32) -> anyhow::Result<Disconnect> {
+        let (ws_stream, _) = connect_async(self.url.as_str())
+            .await
+            .map_err(|e| anyhow!("Unable to connect event stream: {}, error: {}", self.url, e))?;
+        info!("Connected event stream: {}", self.url);
+        let (mut ws_tx, ws_rx) = ws_stream.split();
+        let subscribe = serde_json::json!({ "subscribe": topic }).to_string();
+        ws_tx
+            .send(Message::text(subscribe))
+            .await
+            .map_err(|e| anyhow!("Unable to subscribe event stream: {}, error: {}", self.url, e))?;
+        self.stream.replace(ws_rx.boxed());
+        let disconnected = self.disconnected.clone();
+        Ok(Arc::new(move || disconnected.notify_one()))
+    }
+    async fn next_payload(&mut self) -> Option<Vec<u8>> {
+        let stream = self.stream.as_mut()?;
+        loop {
+            let msg = tokio::select! {
+                _ = self.disconnected.notified() => return None,
+                msg = stream.next() => msg,
+            };
+            match msg {
+                Some(Ok(Message::Binary(payload))) => return Some(payload),
+                Some(Ok(Message::Text(payload))) => return Some(payload.into_bytes()),
+                Some(Ok(Message::Close(_))) | None => return None,
+                Some(Err(e)) => {
+                    warn!("Event stream: {}, error: {}", self.url, e);
+                    return None;
+                }
+                // pings get answered by the websocket itself
+                Some(Ok(_)) => continue,
+            }
+        }
+    }
+}
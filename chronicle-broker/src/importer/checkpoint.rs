@@ -0,0 +1,174 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use std::time::Duration;
+
+/// The minimum interval between two consecutive checkpoint writes
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Take the read offsets of the imported milestones data, returning the last one along with its milestone index.
+/// Everything before the lowest in progress milestone data got imported, or everything read if none is in progress.
+fn take_imported(
+    read_offsets: &mut BTreeMap<u64, u32>,
+    in_progress_offsets: &HashMap<u32, u64>,
+    read_offset: u64,
+) -> Option<(u64, u32)> {
+    let imported_offset = in_progress_offsets.values().min().copied().unwrap_or(read_offset);
+    let remaining = read_offsets.split_off(&(imported_offset + 1));
+    std::mem::replace(read_offsets, remaining).into_iter().next_back()
+}
+
+impl<T: ImportMode> Importer<T> {
+    /// Load the checkpoint of the LogFile, and skip its already imported bytes in case of resume
+    pub(crate) async fn init_checkpoint<H: ChronicleBrokerScope>(
+        &mut self,
+        supervisor: &BrokerHandle<H>,
+    ) -> anyhow::Result<()> {
+        // nothing gets written in DryRun mode, so there is nothing to checkpoint
+        if T::IMPORT_TYPE == ImportType::DryRun {
+            return Ok(());
+        }
+        let name = self.get_name();
+        let log_file = self
+            .log_file
+            .as_mut()
+            .ok_or_else(|| anyhow!("No LogFile in importer state"))?;
+        let keyspace = self.default_keyspace.name().to_string();
+        let dir_path = ImportCheckpoint::dir_path(get_config().broker_config.logs_dir.as_ref());
+        let checkpoint_path = ImportCheckpoint::file_path(
+            &dir_path,
+            &keyspace,
+            T::IMPORT_TYPE,
+            log_file.from_ms_index(),
+            log_file.to_ms_index(),
+        );
+        let mut checkpoint = ImportCheckpoint {
+            keyspace,
+            import_type: T::IMPORT_TYPE,
+            from_ms: log_file.from_ms_index(),
+            to_ms: log_file.to_ms_index(),
+            milestone_index: None,
+            offset: 0,
+            log_file_size: self.log_file_size,
+            done: false,
+        };
+        if self.resume {
+            if let Some(stored_checkpoint) = Self::read_checkpoint(&checkpoint_path).await? {
+                if stored_checkpoint.log_file_size == self.log_file_size {
+                    let offset = if stored_checkpoint.done {
+                        log_file.len()
+                    } else {
+                        stored_checkpoint.offset
+                    };
+                    log_file.skip(offset).await?;
+                    info!(
+                        "Resuming LogFile: {} from its checkpoint, milestone index: {:?}, offset: {}",
+                        name, stored_checkpoint.milestone_index, offset
+                    );
+                    let skipped = true;
+                    Self::imported(
+                        supervisor,
+                        log_file.from_ms_index(),
+                        log_file.to_ms_index(),
+                        self.log_file_size,
+                        stored_checkpoint.milestone_index.unwrap_or(log_file.from_ms_index()),
+                        offset as usize,
+                        skipped,
+                    );
+                    checkpoint = stored_checkpoint;
+                } else {
+                    warn!("Ignoring the checkpoint of LogFile: {}, as its size has changed", name);
+                }
+            }
+        }
+        self.checkpoint.replace((checkpoint_path, checkpoint));
+        Ok(())
+    }
+    async fn read_checkpoint(checkpoint_path: &PathBuf) -> anyhow::Result<Option<ImportCheckpoint>> {
+        match tokio::fs::read(checkpoint_path).await {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => bail!(e),
+        }
+    }
+    /// Advance the checkpoint once the milestone data got imported, up to the lowest in progress one.
+    pub(crate) async fn advance_checkpoint(&mut self, milestone_index: u32) {
+        self.in_progress_offsets.remove(&milestone_index);
+        let read_offset = self.log_file_size - self.log_file.as_ref().map_or(0, |log_file| log_file.len());
+        if let Some((_, checkpoint)) = self.checkpoint.as_mut() {
+            if let Some((offset, milestone_index)) =
+                take_imported(&mut self.read_offsets, &self.in_progress_offsets, read_offset)
+            {
+                checkpoint.offset = offset;
+                checkpoint.milestone_index.replace(milestone_index);
+            }
+            if self.last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL {
+                self.save_checkpoint().await;
+            }
+        }
+    }
+    /// Mark the checkpoint as done, once the whole LogFile got imported
+    pub(crate) fn finish_checkpoint(&mut self) {
+        if let Some((_, checkpoint)) = self.checkpoint.as_mut() {
+            checkpoint.offset = checkpoint.log_file_size;
+            if let Some((_, milestone_index)) = self.read_offsets.iter().next_back() {
+                checkpoint.milestone_index.replace(*milestone_index);
+            }
+            checkpoint.done = true;
+        }
+    }
+    /// Persist the checkpoint, note: a failure doesn't interrupt the import process
+    pub(crate) async fn save_checkpoint(&mut self) {
+        self.last_checkpoint = Instant::now();
+        if let Some((checkpoint_path, checkpoint)) = self.checkpoint.as_ref() {
+            if let Err(e) = Self::write_checkpoint(checkpoint_path, checkpoint).await {
+                warn!(
+                    "Unable to save the checkpoint of LogFile: {}, error: {}",
+                    self.get_name(),
+                    e
+                );
+            }
+        }
+    }
+    async fn write_checkpoint(checkpoint_path: &PathBuf, checkpoint: &ImportCheckpoint) -> anyhow::Result<()> {
+        if let Some(dir_path) = checkpoint_path.parent() {
+            tokio::fs::create_dir_all(dir_path).await?;
+        }
+        // write then rename, to never leave a partially written checkpoint behind
+        let part_path = checkpoint_path.with_extension("json.part");
+        tokio::fs::write(&part_path, serde_json::to_vec(checkpoint)?).await?;
+        tokio::fs::rename(&part_path, checkpoint_path).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoint_offsets() {
+        // the start offsets of the in progress milestones data, and the end offsets of the read ones
+        let mut in_progress_offsets: HashMap<u32, u64> = vec![(1, 0), (2, 100), (3, 250)].into_iter().collect();
+        let mut read_offsets: BTreeMap<u64, u32> = vec![(100, 1), (250, 2), (300, 3)].into_iter().collect();
+        // the milestone 1 is still in progress, so nothing before the milestone 2 is checkpointed
+        in_progress_offsets.remove(&2);
+        assert_eq!(take_imported(&mut read_offsets, &in_progress_offsets, 300), None);
+        assert_eq!(read_offsets.len(), 3);
+        // the milestones 1 and 2 got imported, up to the start of the in progress milestone 3
+        in_progress_offsets.remove(&1);
+        assert_eq!(
+            take_imported(&mut read_offsets, &in_progress_offsets, 300),
+            Some((250, 2))
+        );
+        assert_eq!(read_offsets.keys().copied().collect::<Vec<_>>(), vec![300]);
+        // everything read got imported
+        in_progress_offsets.remove(&3);
+        assert_eq!(
+            take_imported(&mut read_offsets, &in_progress_offsets, 300),
+            Some((300, 3))
+        );
+        assert!(read_offsets.is_empty());
+    }
+}
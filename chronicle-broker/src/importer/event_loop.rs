@@ -45,6 +45,8 @@ impl<H: ChronicleBrokerScope, T: ImportMode> EventLoop<BrokerHandle<H>> for Impo
                                     .in_progress_milestones_data_bytes_size
                                     .remove(&milestone_index)
                                     .expect("Expected size-entry for a milestone data");
+                                self.batch_finished(milestone_index);
+                                self.advance_checkpoint(milestone_index).await;
                                 let skipped = false;
                                 Self::imported(
                                     supervisor,
@@ -57,25 +59,28 @@ impl<H: ChronicleBrokerScope, T: ImportMode> EventLoop<BrokerHandle<H>> for Impo
                                 );
                                 // check if we should process more
                                 if !self.service.is_stopping() {
-                                    // process one more
-                                    if let Some(milestone_data) =
-                                        self.next_milestone_data(supervisor).await.map_err(|e| {
-                                            error!("Unable to fetch next milestone data. Error: {}", e);
-                                            Need::Abort
-                                        })?
-                                    {
-                                        T::handle_milestone_data(milestone_data, self).map_err(|e| {
-                                            error!("{}", e);
-                                            Need::Abort
-                                        })?;
-                                    } else {
-                                        // no more milestone data.
-                                        if self.in_progress_milestones_data.is_empty() {
-                                            // shut it down
-                                            info!("Imported the LogFile: {}", self.get_name());
-                                            return Ok(());
+                                    // process more, up to the adapted concurrency
+                                    while self.in_progress_milestones_data.len() < self.concurrency as usize {
+                                        if let Some(milestone_data) =
+                                            self.next_milestone_data(supervisor).await.map_err(|e| {
+                                                error!("Unable to fetch next milestone data. Error: {}", e);
+                                                Need::Abort
+                                            })?
+                                        {
+                                            T::handle_milestone_data(milestone_data, self).map_err(|e| {
+                                                error!("{}", e);
+                                                Need::Abort
+                                            })?;
+                                        } else {
+                                            break;
                                         }
-                                    };
+                                    }
+                                    // no more milestone data.
+                                    if self.in_progress_milestones_data.is_empty() {
+                                        // shut it down
+                                        info!("Imported the LogFile: {}", self.get_name());
+                                        return Ok(());
+                                    }
                                 }
                             }
                             Err(_milestone_index) => {
@@ -84,11 +89,28 @@ impl<H: ChronicleBrokerScope, T: ImportMode> EventLoop<BrokerHandle<H>> for Impo
                             }
                         }
                     }
+                    // note: we receive this variant in DryRun mode.
+                    ImporterEvent::Diff(milestone_index, diff) => {
+                        let importer_session = ImporterSession::Diff {
+                            from_ms: self.from_ms,
+                            to_ms: self.to_ms,
+                            milestone_index,
+                            diff,
+                        };
+                        supervisor.send(BrokerEvent::Importer(importer_session)).ok();
+                        // proceed as it got imported
+                        if let Some(handle) = self.handle.as_ref() {
+                            handle.send(ImporterEvent::CqlResult(Ok(milestone_index))).ok();
+                        }
+                    }
                     // note: we receive this variant in All mode.
                     ImporterEvent::ProcessMore(milestone_index) => {
                         if self.service.is_stopping() {
                             continue;
                         }
+                        // the previous batch of the milestone data got written
+                        self.batch_finished(milestone_index);
+                        self.batches_started.insert(milestone_index, Instant::now());
                         // extract the remaining milestone data iterator
                         let (mut iter, analytic_record) = self
                             .in_progress_milestones_data
@@ -144,6 +166,33 @@ impl<T> Importer<T> {
     pub(crate) fn get_keyspace(&self) -> ChronicleKeyspace {
         self.default_keyspace.clone()
     }
+    /// Observe the write latency of the milestone data batch, and adapt the concurrency accordingly:
+    /// it shrinks while the smoothed latency, or the latency of the slowest scylla node, exceeds the target,
+    /// and grows back up to the parallelism otherwise.
+    fn batch_finished(&mut self, milestone_index: u32) {
+        if let Some(started) = self.batches_started.remove(&milestone_index) {
+            let latency = started.elapsed().as_millis() as f64;
+            let write_latency = self
+                .write_latency
+                .map_or(latency, |write_latency| 0.8 * write_latency + 0.2 * latency);
+            self.write_latency.replace(write_latency);
+            let slowest_node_latency = self.node_latencies.slowest().unwrap_or_default();
+            if write_latency.max(slowest_node_latency) > self.target_latency_ms as f64 {
+                if self.concurrency > 1 {
+                    self.concurrency -= 1;
+                    debug!(
+                        "{} reduced its concurrency to: {}, write latency: {:.0}ms, slowest node latency: {:.0}ms",
+                        self.get_name(),
+                        self.concurrency,
+                        write_latency,
+                        slowest_node_latency
+                    );
+                }
+            } else if self.concurrency < self.parallelism {
+                self.concurrency += 1;
+            }
+        }
+    }
     fn get_partition_id(&self, milestone_index: MilestoneIndex) -> u16 {
         self.partition_config.partition_id(milestone_index.0)
     }
@@ -464,7 +513,7 @@ impl<T: ImportMode> Importer<T> {
         I: Inherent,
         K: 'static + Send + Clone,
         V: 'static + Send + Clone,
-        ChronicleKeyspace: Insert<K, V>,
+        ChronicleKeyspace: Insert<K, V> + ComputeToken<K>,
     {
         let req = self
             .default_keyspace
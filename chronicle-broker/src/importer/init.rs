@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
 
 #[async_trait::async_trait]
 impl<H: ChronicleBrokerScope, T: ImportMode> Init<BrokerHandle<H>> for Importer<T> {
@@ -15,6 +17,17 @@ impl<H: ChronicleBrokerScope, T: ImportMode> Init<BrokerHandle<H>> for Importer<
             self.service.update_status(ServiceStatus::Initializing);
             let event = BrokerEvent::Children(BrokerChild::Importer(self.service.clone(), Ok(()), self.parallelism));
             supervisor.send(event).ok();
+            if let Some(remote_url) = self.remote_url.clone() {
+                self.fetch_remote_log_file(&remote_url).await.map_err(|e| {
+                    error!("Unable to fetch remote LogFile: {}. Error: {}", remote_url, e);
+                    let event = BrokerEvent::Importer(ImporterSession::UrlError {
+                        url: remote_url.clone(),
+                        msg: format!("Unable to fetch remote LogFile: {}", e),
+                    });
+                    supervisor.send(event).ok();
+                    Need::Abort
+                })?;
+            }
             let log_file = LogFile::try_from(self.file_path.clone()).map_err(|e| {
                 error!("Unable to create LogFile. Error: {}", e);
                 Need::Abort
@@ -33,7 +46,7 @@ impl<H: ChronicleBrokerScope, T: ImportMode> Init<BrokerHandle<H>> for Importer<
                 skipped: true,
             };
             // fetch sync data from the keyspace
-            if self.resume {
+            if self.resume || T::REQUIRES_SYNC_DATA {
                 let sync_range = SyncRange { from, to };
                 self.sync_data = SyncData::try_fetch(&self.default_keyspace, &sync_range, 10)
                     .await
@@ -43,6 +56,10 @@ impl<H: ChronicleBrokerScope, T: ImportMode> Init<BrokerHandle<H>> for Importer<
                     })?;
             }
             self.log_file.replace(log_file);
+            self.init_checkpoint(supervisor).await.map_err(|e| {
+                error!("Unable to init the LogFile checkpoint. Error: {}", e);
+                Need::Abort
+            })?;
             self.init_importing(supervisor).await.map_err(|e| {
                 error!("Unable to init importing process. Error: {}", e);
                 Need::Abort
@@ -55,9 +72,195 @@ impl<H: ChronicleBrokerScope, T: ImportMode> Init<BrokerHandle<H>> for Importer<
     }
 }
 
+impl<T> Importer<T> {
+    /// Download the remote LogFile into the file path, resuming the partially downloaded bytes on failure
+    async fn fetch_remote_log_file(&self, remote_url: &Url) -> anyhow::Result<()> {
+        fetch_remote_log_file(remote_url, &self.file_path, self.retries_per_query).await
+    }
+    /// The path of the validator (ETag or Last-Modified) of the staged remote LogFile
+    pub(crate) fn staged_validator_path(&self) -> PathBuf {
+        staged_validator_path(&self.file_path)
+    }
+}
+
+/// The timeout to connect to the remote of the LogFiles
+const REMOTE_CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// The timeout of a remote manifest request
+const MANIFEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+/// The number of retries of a remote manifest request
+pub(crate) const MANIFEST_RETRIES: usize = 5;
+
+/// The local path which is used to stage a remote LogFile while being imported. It's keyed by a stable digest of the
+/// url, so the LogFiles of different remotes don't collide and a restarted broker resumes the same staged file,
+/// and keeps the LogFile name which tells its range
+pub(crate) fn remote_log_file_path(staging_dir: &Path, url: &Url) -> PathBuf {
+    let filename = url
+        .path_segments()
+        .and_then(|segments| segments.last())
+        .unwrap_or_default();
+    staging_dir
+        .join("imports")
+        .join(format!("{:016x}", stable_digest(url.as_str().as_bytes())))
+        .join(filename)
+}
+
+/// The 64-bit FNV-1a digest, which unlike the std hashers is stable across Rust versions and processes
+fn stable_digest(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |digest, byte| {
+        (digest ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Fetch the LogFile names listed in the manifest of the remote base url, retrying on failure
+pub(crate) async fn fetch_manifest(base_url: &Url, retries: usize) -> anyhow::Result<Vec<String>> {
+    let client = reqwest::Client::builder()
+        .connect_timeout(REMOTE_CONNECT_TIMEOUT)
+        .timeout(MANIFEST_TIMEOUT)
+        .build()?;
+    let manifest_url = base_url.join("manifest.json")?;
+    let mut retries = retries;
+    loop {
+        let manifest = async {
+            anyhow::Result::<Vec<String>>::Ok(
+                client
+                    .get(manifest_url.clone())
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?,
+            )
+        }
+        .await;
+        match manifest {
+            Ok(manifest) => return Ok(manifest),
+            Err(e) => {
+                if retries > 0 {
+                    retries -= 1;
+                    warn!("Retrying the fetch of manifest: {}, after error: {}", manifest_url, e);
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                } else {
+                    bail!(e)
+                }
+            }
+        }
+    }
+}
+
+/// Download the remote LogFile into the file path, resuming the partially downloaded bytes on failure
+async fn fetch_remote_log_file(remote_url: &Url, file_path: &Path, retries: usize) -> anyhow::Result<()> {
+    if let Some(dir_path) = file_path.parent() {
+        tokio::fs::create_dir_all(dir_path).await?;
+    }
+    // only the connection is timed out, as a LogFile download may take long
+    let client = reqwest::Client::builder()
+        .connect_timeout(REMOTE_CONNECT_TIMEOUT)
+        .build()?;
+    let mut retries = retries;
+    loop {
+        match try_fetch_remote_log_file(&client, remote_url, file_path).await {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                if retries > 0 {
+                    retries -= 1;
+                    warn!(
+                        "Resuming the fetch of remote LogFile: {}, after error: {}",
+                        remote_url, e
+                    );
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                } else {
+                    bail!(e)
+                }
+            }
+        }
+    }
+}
+
+async fn try_fetch_remote_log_file(client: &reqwest::Client, remote_url: &Url, file_path: &Path) -> anyhow::Result<()> {
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path)
+        .await?;
+    // the already staged bytes, which are resumed only if the remote LogFile still has the same validator
+    let validator_path = staged_validator_path(file_path);
+    let validator = tokio::fs::read_to_string(&validator_path).await.ok();
+    let offset = match validator {
+        Some(_) => file.metadata().await?.len(),
+        None => 0,
+    };
+    let mut request = client.get(remote_url.clone());
+    if let (Some(validator), true) = (validator.as_ref(), offset > 0) {
+        request = request
+            .header(reqwest::header::RANGE, format!("bytes={}-", offset))
+            .header(reqwest::header::IF_RANGE, validator.as_str());
+    }
+    let mut response = request.send().await?;
+    let (start, complete_length) = content_range(&response);
+    match response.status() {
+        reqwest::StatusCode::PARTIAL_CONTENT if start == Some(offset) => (),
+        // the LogFile is already fully staged
+        reqwest::StatusCode::RANGE_NOT_SATISFIABLE if complete_length == Some(offset) => return Ok(()),
+        reqwest::StatusCode::PARTIAL_CONTENT | reqwest::StatusCode::RANGE_NOT_SATISFIABLE => {
+            // the staged bytes don't match the remote LogFile, so the next attempt starts over
+            tokio::fs::remove_file(&validator_path).await.ok();
+            bail!(
+                "The staged LogFile doesn't match the remote one, staged bytes: {}, remote range: {:?}/{:?}",
+                offset,
+                start,
+                complete_length
+            );
+        }
+        status if status.is_success() => {
+            // a new or changed LogFile, or the server doesn't support range requests, so we start over
+            file.set_len(0).await?;
+            let remote_validator = response
+                .headers()
+                .get(reqwest::header::ETAG)
+                .or_else(|| response.headers().get(reqwest::header::LAST_MODIFIED))
+                .and_then(|remote_validator| remote_validator.to_str().ok());
+            match remote_validator {
+                Some(remote_validator) => tokio::fs::write(&validator_path, remote_validator).await?,
+                None => {
+                    tokio::fs::remove_file(&validator_path).await.ok();
+                }
+            }
+        }
+        status => bail!("Unexpected response status: {}", status),
+    }
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk).await?;
+    }
+    file.sync_all().await?;
+    Ok(())
+}
+
+/// The path of the validator (ETag or Last-Modified) of a staged remote LogFile
+fn staged_validator_path(file_path: &Path) -> PathBuf {
+    file_path.with_extension("validator")
+}
+
+/// The first byte position and the complete length of the Content-Range header, ie `bytes 100-199/200`
+/// or `bytes */200`
+fn content_range(response: &reqwest::Response) -> (Option<u64>, Option<u64>) {
+    let content_range = response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)
+        .and_then(|content_range| content_range.to_str().ok())
+        .and_then(|content_range| content_range.strip_prefix("bytes "))
+        .and_then(|content_range| content_range.split_once('/'));
+    match content_range {
+        Some((range, complete_length)) => (
+            range.split_once('-').and_then(|(start, _)| start.parse().ok()),
+            complete_length.parse().ok(),
+        ),
+        None => (None, None),
+    }
+}
+
 impl<T: ImportMode> Importer<T> {
     async fn init_importing<H: ChronicleBrokerScope>(&mut self, supervisor: &BrokerHandle<H>) -> anyhow::Result<()> {
-        for _ in 0..self.parallelism {
+        for _ in 0..self.concurrency {
             if let Some(milestone_data) = self.next_milestone_data(supervisor).await? {
                 T::handle_milestone_data(milestone_data, self)?;
             } else {
@@ -89,6 +292,10 @@ impl<T: ImportMode> Importer<T> {
                     );
                     let skipped = true;
                     let ms_bytes_size = (pre_len - log_file.len()) as usize;
+                    if self.checkpoint.is_some() {
+                        self.read_offsets
+                            .insert(self.log_file_size - log_file.len(), milestone_index);
+                    }
                     Self::imported(
                         supervisor,
                         log_file.from_ms_index(),
@@ -109,6 +316,13 @@ impl<T: ImportMode> Importer<T> {
                     let ms_bytes_size = (pre_len - log_file.len()) as usize;
                     self.in_progress_milestones_data_bytes_size
                         .insert(milestone_index, ms_bytes_size);
+                    self.batches_started.insert(milestone_index, Instant::now());
+                    if self.checkpoint.is_some() {
+                        self.in_progress_offsets
+                            .insert(milestone_index, self.log_file_size - pre_len);
+                        self.read_offsets
+                            .insert(self.log_file_size - log_file.len(), milestone_index);
+                    }
                     return Ok(Some(milestone_data));
                 }
             } else {
@@ -145,8 +359,13 @@ impl<T: ImportMode> Importer<T> {
             .clone()
             .ok_or_else(|| anyhow!("No importer handle available!"))?;
         let keyspace = self.get_keyspace();
-        let inherent_worker =
-            MilestoneDataWorker::new(importer_handle, keyspace, milestone_index, self.retries_per_query);
+        let inherent_worker = MilestoneDataWorker::new(
+            importer_handle,
+            keyspace,
+            milestone_index,
+            self.retries_per_query,
+            self.node_latencies.clone(),
+        );
         for _ in 0..self.parallelism {
             if let Some((message_id, FullMessage(message, metadata))) = milestone_data.next() {
                 // Insert the message
@@ -159,3 +378,133 @@ impl<T: ImportMode> Importer<T> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::{
+        header,
+        service::{
+            make_service_fn,
+            service_fn,
+        },
+        Body,
+        Request,
+        Response,
+        Server,
+        StatusCode,
+    };
+    use std::{
+        convert::Infallible,
+        sync::{
+            atomic::{
+                AtomicUsize,
+                Ordering,
+            },
+            Arc,
+        },
+    };
+
+    const LOG_FILE: &[u8] = b"the bytes of the remote LogFile";
+
+    /// A local file server, which fails the first manifest request and serves the byte ranges of the LogFile.
+    /// Returns the base url along with the number of the served range requests
+    fn serve() -> (Url, Arc<AtomicUsize>) {
+        let manifest_requests = Arc::new(AtomicUsize::new(0));
+        let range_requests = Arc::new(AtomicUsize::new(0));
+        let counters = (manifest_requests, range_requests.clone());
+        let make_service = make_service_fn(move |_| {
+            let counters = counters.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let response = respond(&request, &counters.0, &counters.1);
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let base_url = Url::parse(&format!("http://{}/logs/", server.local_addr())).unwrap();
+        tokio::spawn(server);
+        (base_url, range_requests)
+    }
+
+    fn respond(
+        request: &Request<Body>,
+        manifest_requests: &AtomicUsize,
+        range_requests: &AtomicUsize,
+    ) -> Response<Body> {
+        let mut response = Response::new(Body::empty());
+        match request.uri().path() {
+            "/logs/manifest.json" => {
+                if manifest_requests.fetch_add(1, Ordering::SeqCst) == 0 {
+                    *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+                } else {
+                    *response.body_mut() = Body::from(r#"["1to3.log"]"#);
+                }
+            }
+            "/logs/1to3.log" => {
+                response.headers_mut().insert(header::ETAG, "\"1to3\"".parse().unwrap());
+                let offset = request
+                    .headers()
+                    .get(header::RANGE)
+                    .and_then(|range| range.to_str().ok())
+                    .and_then(|range| range.strip_prefix("bytes="))
+                    .and_then(|range| range.trim_end_matches('-').parse::<usize>().ok());
+                match offset {
+                    Some(offset) if offset < LOG_FILE.len() => {
+                        range_requests.fetch_add(1, Ordering::SeqCst);
+                        let content_range = format!("bytes {}-{}/{}", offset, LOG_FILE.len() - 1, LOG_FILE.len());
+                        *response.status_mut() = StatusCode::PARTIAL_CONTENT;
+                        response
+                            .headers_mut()
+                            .insert(header::CONTENT_RANGE, content_range.parse().unwrap());
+                        *response.body_mut() = Body::from(&LOG_FILE[offset..]);
+                    }
+                    Some(_) => {
+                        range_requests.fetch_add(1, Ordering::SeqCst);
+                        let content_range = format!("bytes */{}", LOG_FILE.len());
+                        *response.status_mut() = StatusCode::RANGE_NOT_SATISFIABLE;
+                        response
+                            .headers_mut()
+                            .insert(header::CONTENT_RANGE, content_range.parse().unwrap());
+                    }
+                    None => *response.body_mut() = Body::from(LOG_FILE),
+                }
+            }
+            _ => *response.status_mut() = StatusCode::NOT_FOUND,
+        }
+        response
+    }
+
+    #[tokio::test]
+    async fn fetch_from_local_file_server() {
+        let (base_url, range_requests) = serve();
+        // the first manifest request fails, so the manifest is fetched on retry
+        let manifest = fetch_manifest(&base_url, 1).await.unwrap();
+        assert_eq!(manifest, vec!["1to3.log".to_owned()]);
+        let url = base_url.join(&manifest[0]).unwrap();
+        let staging_dir = std::env::temp_dir().join(format!("chronicle-{}", rand::random::<u64>()));
+        let file_path = remote_log_file_path(&staging_dir, &url);
+        assert!(file_path.starts_with(staging_dir.join("imports")));
+        assert!(file_path.ends_with("1to3.log"));
+        assert_eq!(file_path, remote_log_file_path(&staging_dir, &url));
+        fetch_remote_log_file(&url, &file_path, 0).await.unwrap();
+        assert_eq!(tokio::fs::read(&file_path).await.unwrap(), LOG_FILE);
+        assert_eq!(range_requests.load(Ordering::SeqCst), 0);
+        // an interrupted download resumes from the staged bytes
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&file_path)
+            .unwrap()
+            .set_len(10)
+            .unwrap();
+        fetch_remote_log_file(&url, &file_path, 0).await.unwrap();
+        assert_eq!(tokio::fs::read(&file_path).await.unwrap(), LOG_FILE);
+        assert_eq!(range_requests.load(Ordering::SeqCst), 1);
+        // a fully staged LogFile is kept as is
+        fetch_remote_log_file(&url, &file_path, 0).await.unwrap();
+        assert_eq!(tokio::fs::read(&file_path).await.unwrap(), LOG_FILE);
+        assert_eq!(range_requests.load(Ordering::SeqCst), 2);
+        tokio::fs::remove_dir_all(&staging_dir).await.ok();
+    }
+}
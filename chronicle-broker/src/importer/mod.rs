@@ -7,14 +7,23 @@ use crate::{
         BrokerEvent,
         BrokerHandle,
         ChronicleBrokerScope,
+        NodeLatencies,
     },
     archiver::LogFile,
+    storage::query,
 };
 use bee_message::{
+    milestone::{
+        Milestone,
+        MilestoneIndex,
+    },
     output::Output,
-    payload::transaction::{
-        Essence,
-        TransactionPayload,
+    payload::{
+        transaction::{
+            Essence,
+            TransactionPayload,
+        },
+        Payload,
     },
 };
 use chronicle_common::{
@@ -27,31 +36,51 @@ use scylla_rs::{
     prelude::stage::ReporterHandle,
 };
 use std::{
-    collections::hash_map::IntoIter,
+    collections::{
+        hash_map::IntoIter,
+        BTreeMap,
+    },
     ops::{
         Deref,
         DerefMut,
         Range,
     },
     path::PathBuf,
-    sync::atomic::Ordering,
+    sync::{
+        atomic::Ordering,
+        Arc,
+    },
+    time::Instant,
 };
 
+mod checkpoint;
 mod event_loop;
 mod init;
 mod terminating;
+pub(crate) use init::{
+    fetch_manifest,
+    remote_log_file_path,
+    MANIFEST_RETRIES,
+};
 
 /// Import all records to all tables
 pub struct All;
 /// Import analytics records only which are stored in analytics table
 pub struct Analytics;
+/// Compare the milestones against the stored records without writing anything
+pub struct DryRun;
 
 /// Defines the Importer Mode
 pub trait ImportMode: Sized + Send + 'static {
+    /// The import type of the mode
+    const IMPORT_TYPE: ImportType;
+    /// Whether the mode needs the sync data of the LogFile range even if it's not resuming
+    const REQUIRES_SYNC_DATA: bool = false;
     /// Instruct how to import the milestone data
     fn handle_milestone_data(milestone_data: MilestoneData, importer: &mut Importer<Self>) -> anyhow::Result<()>;
 }
 impl ImportMode for All {
+    const IMPORT_TYPE: ImportType = ImportType::All;
     fn handle_milestone_data(milestone_data: MilestoneData, importer: &mut Importer<All>) -> anyhow::Result<()> {
         let analytic_record = milestone_data.get_analytic_record().map_err(|e| {
             error!("Unable to get analytic record for milestone data. Error: {}", e);
@@ -68,6 +97,7 @@ impl ImportMode for All {
 }
 
 impl ImportMode for Analytics {
+    const IMPORT_TYPE: ImportType = ImportType::Analytics;
     fn handle_milestone_data(milestone_data: MilestoneData, importer: &mut Importer<Analytics>) -> anyhow::Result<()> {
         let analytic_record = milestone_data.get_analytic_record().map_err(|e| {
             error!("Unable to get analytic record for milestone data. Error: {}", e);
@@ -84,6 +114,130 @@ impl ImportMode for Analytics {
         Ok(())
     }
 }
+impl ImportMode for DryRun {
+    const IMPORT_TYPE: ImportType = ImportType::DryRun;
+    const REQUIRES_SYNC_DATA: bool = true;
+    fn handle_milestone_data(milestone_data: MilestoneData, importer: &mut Importer<DryRun>) -> anyhow::Result<()> {
+        let analytic_record = milestone_data.get_analytic_record().map_err(|e| {
+            error!("Unable to get analytic record for milestone data. Error: {}", e);
+            e
+        })?;
+        let milestone_index = milestone_data.milestone_index();
+        let importer_handle = importer
+            .handle
+            .clone()
+            .ok_or_else(|| anyhow!("No importer handle available!"))?;
+        let keyspace = importer.get_keyspace();
+        let retries_per_query = importer.retries_per_query;
+        let parallelism = importer.parallelism;
+        let sync_data = &importer.sync_data;
+        let synced = sync_data
+            .completed
+            .iter()
+            .chain(sync_data.synced_but_unlogged.iter())
+            .any(|r| r.contains(&milestone_index));
+        tokio::spawn(async move {
+            let event = match DryRun::diff(&keyspace, retries_per_query, parallelism, synced, milestone_data).await {
+                Ok(diff) => ImporterEvent::Diff(milestone_index, diff),
+                Err(e) => {
+                    error!("Unable to compare milestone index: {}, error: {}", milestone_index, e);
+                    ImporterEvent::CqlResult(Err(milestone_index))
+                }
+            };
+            importer_handle.send(event).ok();
+        });
+        // note: nothing gets inserted in DryRun mode, however we kept the entry for simplicty sake.
+        importer
+            .in_progress_milestones_data
+            .insert(milestone_index, (HashMap::new().into_iter(), analytic_record));
+        Ok(())
+    }
+}
+
+impl DryRun {
+    /// Compare the milestone data against the stored milestone and messages
+    async fn diff(
+        keyspace: &ChronicleKeyspace,
+        retries_per_query: usize,
+        parallelism: u8,
+        synced: bool,
+        milestone_data: MilestoneData,
+    ) -> anyhow::Result<MilestoneDiff> {
+        if !synced {
+            return Ok(MilestoneDiff::New);
+        }
+        let milestone_index = milestone_data.milestone_index();
+        // the message id of the milestone message in the LogFile
+        let milestone_message_id = milestone_data.messages().iter().find_map(|(message_id, full_message)| {
+            match full_message.message().payload() {
+                Some(Payload::Milestone(milestone_payload))
+                    if milestone_payload.essence().index().0 == milestone_index =>
+                {
+                    Some(*message_id)
+                }
+                _ => None,
+            }
+        });
+        let stored_milestone: Option<Milestone> =
+            query(keyspace, MilestoneIndex(milestone_index), retries_per_query).await?;
+        let milestone = stored_milestone.map(|m| *m.message_id()) != milestone_message_id;
+        // look up the stored messages in batches of `parallelism` queries, as they get inserted in Import mode
+        let message_ids = milestone_data.messages().keys().copied().collect::<Vec<_>>();
+        let mut stored_messages = Vec::with_capacity(message_ids.len());
+        for batch in message_ids.chunks(parallelism.max(1) as usize) {
+            let batch_results = futures::future::join_all(
+                batch
+                    .iter()
+                    .map(|message_id| query::<MessageId, FullMessage>(keyspace, *message_id, retries_per_query)),
+            )
+            .await;
+            for stored_message in batch_results {
+                stored_messages.push(stored_message?);
+            }
+        }
+        Ok(Self::classify(
+            milestone,
+            milestone_data
+                .messages()
+                .values()
+                .zip(stored_messages.iter().map(Option::as_ref)),
+        ))
+    }
+    /// Classify a synced milestone, given whether its milestone message differs and its messages paired with the
+    /// stored ones
+    fn classify<'a>(
+        milestone: bool,
+        messages: impl Iterator<Item = (&'a FullMessage, Option<&'a FullMessage>)>,
+    ) -> MilestoneDiff {
+        let mut missing_messages = 0;
+        let mut divergent_messages = 0;
+        for (full_message, stored_message) in messages {
+            match stored_message {
+                Some(stored_message) => {
+                    let (message, metadata) = (full_message.message(), full_message.metadata());
+                    let (stored, stored_metadata) = (stored_message.message(), stored_message.metadata());
+                    if message.pack_new() != stored.pack_new()
+                        || metadata.referenced_by_milestone_index != stored_metadata.referenced_by_milestone_index
+                        || metadata.ledger_inclusion_state != stored_metadata.ledger_inclusion_state
+                    {
+                        divergent_messages += 1;
+                    }
+                }
+                None => missing_messages += 1,
+            }
+        }
+        if milestone || missing_messages > 0 || divergent_messages > 0 {
+            MilestoneDiff::Divergent {
+                milestone,
+                missing_messages,
+                divergent_messages,
+            }
+        } else {
+            MilestoneDiff::Identical
+        }
+    }
+}
+
 // Importer builder
 builder!(ImporterBuilder<T> {
     file_path: PathBuf,
@@ -91,13 +245,18 @@ builder!(ImporterBuilder<T> {
     resume: bool,
     import_range: Range<u32>,
     parallelism: u8,
-    chronicle_id: u8
+    chronicle_id: u8,
+    remote_url: Url,
+    target_latency_ms: u64,
+    node_latencies: Arc<NodeLatencies>
 });
 
 /// Importer events
 pub enum ImporterEvent {
     /// The result of an insert into the database
     CqlResult(Result<u32, u32>),
+    /// The comparison result of a milestone in DryRun mode
+    Diff(u32, MilestoneDiff),
     /// Indicator to continue processing
     ProcessMore(u32),
     /// Shutdown the importer
@@ -158,6 +317,8 @@ pub struct Importer<T> {
     service: Service,
     /// The file path of the importer
     file_path: PathBuf,
+    /// The remote url of the LogFile, which gets staged in the file path
+    remote_url: Option<Url>,
     /// The log file
     log_file: Option<LogFile>,
     /// LogFile total_size,
@@ -176,6 +337,24 @@ pub struct Importer<T> {
     chronicle_id: u8,
     /// The number of parallelism
     parallelism: u8,
+    /// The adapted number of concurrent milestones data, which never exceeds the parallelism
+    concurrency: u8,
+    /// The smoothed write latency in milliseconds of the milestones data batches
+    write_latency: Option<f64>,
+    /// The target write latency in milliseconds, above which the concurrency is reduced
+    target_latency_ms: u64,
+    /// The start time of the in progress milestones data batches
+    batches_started: HashMap<u32, Instant>,
+    /// The write latencies of the scylla nodes, shared by all the importers
+    node_latencies: Arc<NodeLatencies>,
+    /// The checkpoint of the LogFile along with its file path, None in DryRun mode
+    checkpoint: Option<(PathBuf, ImportCheckpoint)>,
+    /// The LogFile start bytes offsets of the in progress milestones data
+    in_progress_offsets: HashMap<u32, u64>,
+    /// The LogFile end bytes offsets of the read milestones data, which are not checkpointed yet
+    read_offsets: BTreeMap<u64, u32>,
+    /// The last time the checkpoint got persisted
+    last_checkpoint: Instant,
     /// The resume flag
     resume: bool,
     /// The range of requested milestones to import
@@ -221,16 +400,32 @@ impl<T: ImportMode> Builder for ImporterBuilder<T> {
             start: 1,
             end: i32::MAX as u32,
         });
+        let parallelism = self.parallelism.unwrap_or(10);
         Self::State {
             service: Service::new(),
             file_path: self.file_path.unwrap(),
+            remote_url: self.remote_url,
             log_file: None,
             log_file_size: 0,
             from_ms: 0,
             to_ms: 0,
             default_keyspace,
             partition_config,
-            parallelism: self.parallelism.unwrap_or(10),
+            parallelism,
+            concurrency: parallelism,
+            write_latency: None,
+            target_latency_ms: self
+                .target_latency_ms
+                .or(config.broker_config.import_target_latency_ms)
+                .unwrap_or(200),
+            batches_started: HashMap::new(),
+            node_latencies: self
+                .node_latencies
+                .unwrap_or_else(|| Arc::new(NodeLatencies::new(config.storage_config.nodes.len()))),
+            checkpoint: None,
+            in_progress_offsets: HashMap::new(),
+            read_offsets: BTreeMap::new(),
+            last_checkpoint: Instant::now(),
             chronicle_id: self.chronicle_id.unwrap(),
             in_progress_milestones_data: HashMap::new(),
             in_progress_milestones_data_bytes_size: HashMap::new(),
@@ -255,7 +450,10 @@ impl<T> Importer<T> {
 /// Implement `Name` trait of the Importer
 impl<T> Name for Importer<T> {
     fn set_name(mut self) -> Self {
-        let name = format!("{}", self.file_path.to_str().unwrap());
+        let name = match self.remote_url.as_ref() {
+            Some(remote_url) => remote_url.to_string(),
+            None => format!("{}", self.file_path.to_str().unwrap()),
+        };
         self.service.update_name(name);
         self
     }
@@ -307,6 +505,10 @@ where
     key: K,
     value: V,
     retries: usize,
+    /// The token of the key, which tells the node the insert is written to
+    token: i64,
+    /// The time the insert got sent, including its retries
+    started: Instant,
 }
 
 /// An atomic importer handle
@@ -324,6 +526,8 @@ where
     pub(crate) any_error: std::sync::atomic::AtomicBool,
     /// The number of retires
     pub(crate) retries: usize,
+    /// The write latencies of the scylla nodes
+    pub(crate) node_latencies: Arc<NodeLatencies>,
 }
 
 impl<S> AtomicImporterHandle<S>
@@ -331,13 +535,14 @@ where
     S: 'static + Insert<Synckey, SyncRecord>,
 {
     /// Create a new atomic importer handle with an importer handle, a keyspace, a milestone index, an atomic error
-    /// indicator, and a number of retires
+    /// indicator, a number of retires, and the node latencies
     pub fn new(
         handle: ImporterHandle,
         keyspace: S,
         milestone_index: u32,
         any_error: std::sync::atomic::AtomicBool,
         retries: usize,
+        node_latencies: Arc<NodeLatencies>,
     ) -> Self {
         Self {
             handle,
@@ -345,12 +550,13 @@ where
             milestone_index,
             any_error,
             retries,
+            node_latencies,
         }
     }
 }
 impl<S: Insert<K, V>, K, V> AtomicImporterWorker<S, K, V>
 where
-    S: 'static + Insert<K, V> + Insert<Synckey, SyncRecord>,
+    S: 'static + Insert<K, V> + Insert<Synckey, SyncRecord> + ComputeToken<K>,
     K: 'static + Send,
     V: 'static + Send,
 {
@@ -359,12 +565,15 @@ where
     pub fn new(handle: std::sync::Arc<AtomicImporterHandle<S>>, key: K, value: V) -> Self {
         let keyspace = handle.keyspace.clone();
         let retries = handle.retries;
+        let token = S::token(&key);
         Self {
             handle,
             keyspace,
             key,
             value,
             retries,
+            token,
+            started: Instant::now(),
         }
     }
     /// Create a new boxed atomic importer worker with an atomic importer handle, a keyspace, a key, a value, and a
@@ -381,6 +590,8 @@ where
     V: 'static + Send + Clone,
 {
     fn handle_response(self: Box<Self>, giveload: Vec<u8>) -> anyhow::Result<()> {
+        let latency_ms = self.started.elapsed().as_secs_f64() * 1000.0;
+        self.handle.node_latencies.observe(self.token, latency_ms);
         Decoder::from(giveload.try_into()?).get_void()
     }
     fn handle_error(
@@ -571,12 +782,24 @@ impl<S> MilestoneDataWorker<S>
 where
     S: 'static + Insert<Synckey, SyncRecord>,
 {
-    /// Create a new milestone data worker with an importer handle, a keyspace, a milestone index, and a number of
-    /// retries
-    fn new(importer_handle: ImporterHandle, keyspace: S, milestone_index: u32, retries: usize) -> Self {
+    /// Create a new milestone data worker with an importer handle, a keyspace, a milestone index, a number of
+    /// retries, and the node latencies
+    fn new(
+        importer_handle: ImporterHandle,
+        keyspace: S,
+        milestone_index: u32,
+        retries: usize,
+        node_latencies: Arc<NodeLatencies>,
+    ) -> Self {
         let any_error = std::sync::atomic::AtomicBool::new(false);
-        let atomic_handle =
-            AtomicImporterHandle::new(importer_handle, keyspace.clone(), milestone_index, any_error, retries);
+        let atomic_handle = AtomicImporterHandle::new(
+            importer_handle,
+            keyspace.clone(),
+            milestone_index,
+            any_error,
+            retries,
+            node_latencies,
+        );
         let arc_handle = std::sync::Arc::new(atomic_handle);
         Self { arc_handle }
     }
@@ -586,7 +809,7 @@ where
 pub(crate) trait Inherent {
     fn inherent_boxed<K, V>(&self, key: K, value: V) -> Box<dyn Worker>
     where
-        ChronicleKeyspace: 'static + Insert<K, V> + Insert<Synckey, SyncRecord>,
+        ChronicleKeyspace: 'static + Insert<K, V> + Insert<Synckey, SyncRecord> + ComputeToken<K>,
         K: 'static + Send + Clone,
         V: 'static + Send + Clone;
 }
@@ -596,7 +819,7 @@ pub(crate) trait Inherent {
 impl Inherent for MilestoneDataWorker<ChronicleKeyspace> {
     fn inherent_boxed<K, V>(&self, key: K, value: V) -> Box<dyn Worker>
     where
-        ChronicleKeyspace: 'static + Insert<K, V> + Insert<Synckey, SyncRecord>,
+        ChronicleKeyspace: 'static + Insert<K, V> + Insert<Synckey, SyncRecord> + ComputeToken<K>,
         K: 'static + Send + Clone,
         V: 'static + Send + Clone,
     {
@@ -682,3 +905,83 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bee_message::{
+        parents::Parents,
+        MessageBuilder,
+    };
+    use bee_pow::providers::miner::Miner;
+
+    fn full_message(parent: u8, referenced_by_milestone_index: u32) -> FullMessage {
+        let message = MessageBuilder::<Miner>::new()
+            .with_network_id(0)
+            .with_parents(Parents::new(vec![MessageId::new([parent; 32])]).unwrap())
+            .finish()
+            .unwrap();
+        let metadata = MessageMetadata {
+            message_id: message.id().0,
+            parent_message_ids: message.parents().iter().copied().collect(),
+            is_solid: true,
+            referenced_by_milestone_index: Some(referenced_by_milestone_index),
+            ledger_inclusion_state: Some(LedgerInclusionState::NoTransaction),
+            should_promote: None,
+            should_reattach: None,
+        };
+        FullMessage::new(message, metadata)
+    }
+
+    #[tokio::test]
+    async fn unsynced_milestone_is_new() {
+        let keyspace = ChronicleKeyspace::new("permanode".to_owned());
+        let milestone_data = MilestoneData::new(1, CreatedBy::Syncer);
+        let diff = DryRun::diff(&keyspace, 1, 2, false, milestone_data).await.unwrap();
+        assert_eq!(diff, MilestoneDiff::New);
+    }
+
+    #[test]
+    fn classify_milestone() {
+        let (first, second) = (full_message(1, 1), full_message(2, 1));
+        let identical = DryRun::classify(
+            false,
+            vec![(&first, Some(&first)), (&second, Some(&second))].into_iter(),
+        );
+        assert_eq!(identical, MilestoneDiff::Identical);
+        // the milestone message differs even though all the messages are stored identically
+        let diff = DryRun::classify(true, vec![(&first, Some(&first))].into_iter());
+        assert_eq!(
+            diff,
+            MilestoneDiff::Divergent {
+                milestone: true,
+                missing_messages: 0,
+                divergent_messages: 0,
+            }
+        );
+        // a message which isn't stored and a message whose metadata differs
+        let referenced_later = full_message(2, 2);
+        let diff = DryRun::classify(
+            false,
+            vec![(&first, None), (&second, Some(&referenced_later))].into_iter(),
+        );
+        assert_eq!(
+            diff,
+            MilestoneDiff::Divergent {
+                milestone: false,
+                missing_messages: 1,
+                divergent_messages: 1,
+            }
+        );
+        // a different message stored under the same message id
+        let diff = DryRun::classify(false, vec![(&first, Some(&second))].into_iter());
+        assert_eq!(
+            diff,
+            MilestoneDiff::Divergent {
+                milestone: false,
+                missing_messages: 0,
+                divergent_messages: 1,
+            }
+        );
+    }
+}
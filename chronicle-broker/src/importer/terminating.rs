@@ -25,13 +25,30 @@ impl<H: ChronicleBrokerScope, T: Send + ImportMode> Terminating<BrokerHandle<H>>
             };
             let event = BrokerEvent::Importer(importer_session);
             supervisor.as_mut().expect("Expected BrokerHandle").send(event).ok();
-        } else {
+        } else if self.remote_url.is_none() {
+            // note: the remote fetch errors are already reported as UrlError
             let event = BrokerEvent::Importer(ImporterSession::PathError {
                 path: self.file_path.clone(),
                 msg: "Invalid LogFile path".into(),
             });
             supervisor.as_mut().expect("Expected BrokerHandle").send(event).ok();
         }
+        // persist the checkpoint, so the LogFile can be resumed from where it has stopped
+        if status.is_ok() && self.log_file.is_some() {
+            self.finish_checkpoint();
+        }
+        self.save_checkpoint().await;
+        // remove the staged remote LogFile once it's fully imported
+        if status.is_ok() && self.remote_url.is_some() {
+            if let Err(e) = tokio::fs::remove_file(&self.file_path).await {
+                warn!("Unable to remove staged LogFile: {:?}, error: {}", self.file_path, e);
+            }
+            tokio::fs::remove_file(self.staged_validator_path()).await.ok();
+            // the staging directory of the remote url, which is left in place if anything else got staged there
+            if let Some(dir_path) = self.file_path.parent() {
+                tokio::fs::remove_dir(dir_path).await.ok();
+            }
+        }
         self.service.update_status(ServiceStatus::Stopping);
         let event = BrokerEvent::Children(BrokerChild::Importer(self.service.clone(), status, self.parallelism));
         let _ = supervisor.as_mut().expect("Expected BrokerHandle").send(event);
@@ -12,6 +12,15 @@ pub mod archiver;
 /// The collector, which gathers data from feeds and APIs on request
 #[cfg(feature = "application")]
 pub mod collector;
+/// The client side of the broker websocket control requests
+#[cfg(feature = "control")]
+pub mod control;
+/// The exporter, which enables to export the stored milestones data into write-ahead-logs
+#[cfg(feature = "application")]
+pub mod exporter;
+/// The feed sources, which stream the node events into the collectors
+#[cfg(feature = "application")]
+pub mod feed;
 /// The importer, which enables to import write-ahead-logs
 #[cfg(feature = "application")]
 pub mod importer;
@@ -27,6 +36,9 @@ pub mod requester;
 /// Data solidifier
 #[cfg(feature = "application")]
 pub mod solidifier;
+/// The keyspace queries shared by the actors
+#[cfg(feature = "application")]
+mod storage;
 /// Milestone syncer
 #[cfg(feature = "application")]
 pub mod syncer;
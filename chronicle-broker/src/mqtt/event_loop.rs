@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
+use chronicle_common::metrics::MQTT_LATEST_MILESTONE_INDEX;
 
 #[async_trait::async_trait]
 impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Mqtt<Messages> {
@@ -14,23 +15,17 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Mqtt<Messages> {
         self.service.update_status(ServiceStatus::Running);
         let event = BrokerEvent::Children(BrokerChild::Mqtt(self.service.clone(), None, status));
         let _ = supervisor.as_mut().unwrap().send(event);
-        let inbox = self.inbox.as_mut().unwrap();
-        while let Some(msg_opt) = inbox.stream.next().await {
-            if let Some(msg) = msg_opt {
-                if let Ok(msg) = Message::unpack(&mut msg.payload()) {
-                    let (message_id, _) = msg.id();
-                    // partitioning based on first byte of the message_id
-                    let collector_partition_id = self.partitioner.partition_id(&message_id);
-                    if let Some(collector_handle) = self.collectors_handles.get(&collector_partition_id) {
-                        let _ = collector_handle.send(CollectorEvent::Message(message_id, msg));
-                    }
-                };
-            } else {
-                warn!("Mqtt: {}, lost connection", self.get_name());
-                return Err(Need::Restart);
-            }
+        loop {
+            let payload = self.next_payload().await?;
+            if let Ok(msg) = Message::unpack(&mut payload.as_slice()) {
+                let (message_id, _) = msg.id();
+                // partitioning based on first byte of the message_id
+                let collector_partition_id = self.partitioner.partition_id(&message_id);
+                if let Some(collector_handle) = self.collectors_handles.get(&collector_partition_id) {
+                    let _ = collector_handle.send(CollectorEvent::Message(self.url.clone(), message_id, msg));
+                }
+            };
         }
-        Ok(())
     }
 }
 
@@ -45,21 +40,44 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Mqtt<MessagesRefere
         self.service.update_status(ServiceStatus::Running);
         let event = BrokerEvent::Children(BrokerChild::Mqtt(self.service.clone(), None, status));
         let _ = supervisor.as_mut().unwrap().send(event);
-        let inbox = self.inbox.as_mut().unwrap();
-        while let Some(msg_ref_opt) = inbox.stream.next().await {
-            if let Some(msg_ref) = msg_ref_opt {
-                if let Ok(msg_ref) = serde_json::from_str::<MessageMetadata>(&msg_ref.payload_str()) {
-                    // partitioning based on first byte of the message_id
-                    let collector_partition_id = self.partitioner.partition_id(&msg_ref.message_id);
-                    if let Some(collector_handle) = self.collectors_handles.get(&collector_partition_id) {
-                        let _ = collector_handle.send(CollectorEvent::MessageReferenced(msg_ref));
-                    }
-                };
-            } else {
-                warn!("Mqtt: {}, lost connection", self.get_name());
-                return Err(Need::Restart);
-            }
+        loop {
+            let payload = self.next_payload().await?;
+            if let Ok(msg_ref) = serde_json::from_slice::<MessageMetadata>(&payload) {
+                // partitioning based on first byte of the message_id
+                let collector_partition_id = self.partitioner.partition_id(&msg_ref.message_id);
+                if let Some(collector_handle) = self.collectors_handles.get(&collector_partition_id) {
+                    let _ = collector_handle.send(CollectorEvent::MessageReferenced(self.url.clone(), msg_ref));
+                }
+            };
+        }
+    }
+}
+
+/// The latest milestone published by the node
+#[derive(Deserialize)]
+struct LatestMilestonePayload {
+    index: u32,
+}
+
+#[async_trait::async_trait]
+impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Mqtt<LatestMilestone> {
+    async fn event_loop(
+        &mut self,
+        status: Result<(), Need>,
+        supervisor: &mut Option<BrokerHandle<H>>,
+    ) -> Result<(), Need> {
+        status?;
+        self.service.update_status(ServiceStatus::Running);
+        let event = BrokerEvent::Children(BrokerChild::Mqtt(self.service.clone(), None, status));
+        let _ = supervisor.as_mut().unwrap().send(event);
+        // the latest milestones are only used to monitor the feed of the node
+        loop {
+            let payload = self.next_payload().await?;
+            if let Ok(latest_milestone) = serde_json::from_slice::<LatestMilestonePayload>(&payload) {
+                MQTT_LATEST_MILESTONE_INDEX
+                    .with_label_values(&[self.url.as_str()])
+                    .set(latest_milestone.index as i64);
+            };
         }
-        Ok(())
     }
 }
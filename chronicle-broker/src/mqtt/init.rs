@@ -7,53 +7,35 @@ use super::*;
 impl<T: Topic, H: ChronicleBrokerScope> Init<BrokerHandle<H>> for Mqtt<T> {
     async fn init(&mut self, status: Result<(), Need>, supervisor: &mut Option<BrokerHandle<H>>) -> Result<(), Need> {
         self.service.update_status(ServiceStatus::Initializing);
-        // create async client
-        let random_id: u64 = rand::random();
-        let create_opts = CreateOptionsBuilder::new()
-            .server_uri(&self.url.as_str()[..])
-            .client_id(&format!("{}|{}", self.get_name(), random_id))
-            .persistence(None)
-            .finalize();
-        let client = AsyncClient::new(create_opts).map_err(|e| {
-            error!("Unable to create AsyncClient: {}, error: {}", &self.url.as_str(), e);
+        // create the feed source
+        let source: anyhow::Result<Box<dyn FeedSource>> = match self.kind {
+            FeedKind::Mqtt => {
+                let random_id: u64 = rand::random();
+                let client_id = format!("{}|{}", self.get_name(), random_id);
+                MqttSource::new(&self.url, &client_id, self.stream_capacity)
+                    .map(|source| Box::new(source) as Box<dyn FeedSource>)
+            }
+            FeedKind::EventStream if self.url.scheme() == "file" => {
+                ReplaySource::new(&self.url).map(|source| Box::new(source) as Box<dyn FeedSource>)
+            }
+            FeedKind::EventStream => {
+                WebsocketSource::new(&self.url).map(|source| Box::new(source) as Box<dyn FeedSource>)
+            }
+        };
+        let mut source = source.map_err(|e| {
+            error!("Unable to create feed source: {}, error: {}", &self.url.as_str(), e);
             Need::Abort
         })?;
-        info!("Created AsyncClient: {}", &self.url.to_string());
-        let conn_opts = paho_mqtt::ConnectOptionsBuilder::new()
-            .keep_alive_interval(Duration::from_secs(120))
-            .mqtt_version(paho_mqtt::MQTT_VERSION_3_1_1)
-            .clean_session(false)
-            .connect_timeout(Duration::from_secs(60))
-            .finalize();
-        let mut arc_client = std::sync::Arc::new(client);
-        let arced_client = std::sync::Arc::get_mut(&mut arc_client).unwrap();
-        let stream = arced_client.get_stream(self.stream_capacity);
-        // create inbox
-        self.inbox.replace(MqttInbox { stream });
-        // connect client with the remote broker
-        arced_client.connect(conn_opts).await.map_err(|e| {
-            error!(
-                "Unable to connect AsyncClient: {}, topic: {}, error: {}",
-                &self.url.as_str(),
-                T::name(),
-                e
-            );
+        info!("Created feed source: {}", &self.url.to_string());
+        // connect the source with the remote node, and subscribe to T::name() topic with T::qos()
+        let disconnect = source.subscribe(T::name(), T::qos()).await.map_err(|e| {
+            error!("{}, topic: {}", e, T::name());
             Need::Restart
         })?;
-        info!("Connected AsyncClient: {}", &self.url.as_str());
-        // subscribe to T::name() topic with T::qos()
-        arced_client.subscribe(T::name(), T::qos()).await.map_err(|e| {
-            error!(
-                "Unable to subscribe AsyncClient: {}, topic: {}, error: {}",
-                &self.url.as_str(),
-                T::name(),
-                e
-            );
-            Need::Restart
-        })?;
-        let handle = MqttHandle { client: arc_client };
+        self.source.replace(source);
+        let handle = MqttHandle { disconnect };
         self.handle.replace(handle);
-        info!("Subscribed AsyncClient: {}, topic: {}", &self.url.as_str(), T::name());
+        info!("Subscribed feed source: {}, topic: {}", &self.url.as_str(), T::name());
         let event = BrokerEvent::Children(BrokerChild::Mqtt(
             self.service.clone(),
             Some(self.handle.as_ref().unwrap().clone()),
@@ -0,0 +1,112 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use chronicle_common::{
+    alert,
+    metrics::{
+        MQTT_LAST_MESSAGE_TIMESTAMP,
+        MQTT_MESSAGES,
+        MQTT_MESSAGE_RATE,
+    },
+};
+use std::time::{
+    Instant,
+    SystemTime,
+};
+
+/// The window over which the message rate of the feed is measured
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// The liveness of a MQTT feed, which gets reconnected once it's stale
+pub(crate) struct Liveness {
+    labels: [String; 2],
+    stale_after: Duration,
+    window_start: Instant,
+    window_count: u64,
+}
+
+impl Liveness {
+    pub(crate) fn new(topic: &str, url: &Url, stale_after: Duration) -> Self {
+        Self {
+            labels: [topic.to_owned(), url.to_string()],
+            stale_after,
+            window_start: Instant::now(),
+            window_count: 0,
+        }
+    }
+    fn labels(&self) -> [&str; 2] {
+        [&self.labels[0], &self.labels[1]]
+    }
+    /// Record a received message, and update the message rate once the window elapsed
+    fn record(&mut self) {
+        let labels = self.labels();
+        MQTT_MESSAGES.with_label_values(&labels).inc();
+        if let Ok(now) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+            MQTT_LAST_MESSAGE_TIMESTAMP
+                .with_label_values(&labels)
+                .set(now.as_secs() as i64);
+        }
+        self.window_count += 1;
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= RATE_WINDOW {
+            MQTT_MESSAGE_RATE
+                .with_label_values(&labels)
+                .set(self.window_count as f64 / elapsed.as_secs_f64());
+            self.window_start = Instant::now();
+            self.window_count = 0;
+        }
+    }
+    fn reset(&mut self) {
+        MQTT_MESSAGE_RATE.with_label_values(&self.labels()).set(0.0);
+        self.window_start = Instant::now();
+        self.window_count = 0;
+    }
+}
+
+impl<T: Topic> Mqtt<T> {
+    /// Receive the payload of the next event from the feed source, Err(Need::Restart) once the connection is lost
+    /// or the feed is stale
+    pub(crate) async fn next_payload(&mut self) -> Result<Vec<u8>, Need> {
+        let source = self.source.as_mut().unwrap();
+        let payload = if source.supervised() {
+            match tokio::time::timeout(self.liveness.stale_after, source.next_payload()).await {
+                Ok(payload) => payload,
+                Err(_) => {
+                    alert!(
+                        "Mqtt: {} is stale, no message received for {:?}, reconnecting",
+                        self.get_name(),
+                        self.liveness.stale_after
+                    )
+                    .await
+                    .ok();
+                    self.liveness.reset();
+                    return Err(Need::Restart);
+                }
+            }
+        } else {
+            source.next_payload().await
+        };
+        match payload {
+            Some(payload) => {
+                self.liveness.record();
+                // replayed payloads are already recorded
+                if self.url.scheme() != "file" {
+                    let url = &self.url;
+                    record::record(|| record::RecordedEvent::Feed {
+                        topic: T::name().to_owned(),
+                        url: url.clone(),
+                        payload: hex::encode(&payload),
+                    })
+                    .await;
+                }
+                Ok(payload)
+            }
+            None => {
+                warn!("Mqtt: {}, lost connection", self.get_name());
+                self.liveness.reset();
+                Err(Need::Restart)
+            }
+        }
+    }
+}
@@ -7,9 +7,18 @@ use super::{
         CollectorHandle,
         MessageIdPartitioner,
     },
+    feed::{
+        record,
+        Disconnect,
+        FeedKind,
+        FeedSource,
+        MqttSource,
+        ReplaySource,
+        WebsocketSource,
+    },
     *,
 };
-use futures::stream::StreamExt;
+use liveness::Liveness;
 use std::{
     collections::HashMap,
     time::Duration,
@@ -17,11 +26,13 @@ use std::{
 
 mod event_loop;
 mod init;
+mod liveness;
 mod terminating;
 
 // Mqtt builder
 builder!(MqttBuilder<T> {
     url: Url,
+    kind: FeedKind,
     topic: T,
     collectors_handles: HashMap<u8, CollectorHandle>,
     stream_capacity: usize
@@ -30,11 +41,7 @@ builder!(MqttBuilder<T> {
 /// MqttHandle to be passed to the supervisor in order to shutdown
 #[derive(Clone)]
 pub struct MqttHandle {
-    client: std::sync::Arc<AsyncClient>,
-}
-/// MqttInbox is used to recv events from topic
-pub struct MqttInbox {
-    stream: futures::channel::mpsc::Receiver<Option<paho_mqtt::Message>>,
+    disconnect: Disconnect,
 }
 
 impl Shutdown for MqttHandle {
@@ -42,19 +49,21 @@ impl Shutdown for MqttHandle {
     where
         Self: Sized,
     {
-        self.client.disconnect(None);
+        (self.disconnect)();
         None
     }
 }
-/// Mqtt state
+/// Mqtt state, which streams the topic T from its feed source (a MQTT broker or a websocket event stream)
 pub struct Mqtt<T> {
     service: Service,
     url: Url,
+    kind: FeedKind,
     stream_capacity: usize,
     collectors_handles: HashMap<u8, CollectorHandle>,
     partitioner: MessageIdPartitioner,
     handle: Option<MqttHandle>,
-    inbox: Option<MqttInbox>,
+    source: Option<Box<dyn FeedSource>>,
+    liveness: Liveness,
     _topic: T,
 }
 
@@ -70,6 +79,8 @@ pub enum Topics {
     Messages,
     /// Messages Referenced topic
     MessagesReferenced,
+    /// Latest Milestone topic
+    LatestMilestone,
 }
 
 impl TryFrom<&str> for Topics {
@@ -78,6 +89,7 @@ impl TryFrom<&str> for Topics {
         match value {
             "messages" => Ok(Topics::Messages),
             "messages/referenced" => Ok(Topics::MessagesReferenced),
+            "milestones/latest" => Ok(Topics::LatestMilestone),
             _ => Err(format!("Unsupported topic: {}", value).into()),
         }
     }
@@ -129,6 +141,7 @@ impl Topic for LatestMilestone {
 
 impl<H: ChronicleBrokerScope> ActorBuilder<BrokerHandle<H>> for MqttBuilder<Messages> {}
 impl<H: ChronicleBrokerScope> ActorBuilder<BrokerHandle<H>> for MqttBuilder<MessagesReferenced> {}
+impl<H: ChronicleBrokerScope> ActorBuilder<BrokerHandle<H>> for MqttBuilder<LatestMilestone> {}
 
 /// implementation of builder
 impl<T: Topic> Builder for MqttBuilder<T> {
@@ -136,14 +149,19 @@ impl<T: Topic> Builder for MqttBuilder<T> {
     fn build(self) -> Self::State {
         let collectors_handles = self.collectors_handles.expect("Expected collectors handles");
         let collector_count = collectors_handles.len() as u8;
+        let url = self.url.unwrap();
+        let config = get_config();
+        let stale_after = Duration::from_secs(config.broker_config.mqtt_stale_feed_secs.unwrap_or(60));
         Self::State {
             service: Service::new(),
-            url: self.url.unwrap(),
+            liveness: Liveness::new(T::name(), &url, stale_after),
+            url,
+            kind: self.kind.unwrap_or(FeedKind::Mqtt),
             collectors_handles,
             partitioner: MessageIdPartitioner::new(collector_count),
             stream_capacity: self.stream_capacity.unwrap_or(10000),
             handle: None,
-            inbox: None,
+            source: None,
             _topic: self.topic.unwrap(),
         }
         .set_name()
@@ -153,7 +171,7 @@ impl<T: Topic> Builder for MqttBuilder<T> {
 /// impl name of the Mqtt<T>
 impl<T: Topic> Name for Mqtt<T> {
     fn set_name(mut self) -> Self {
-        let name = format!("{}@{}", T::name(), self.url.as_str());
+        let name = self.kind.feed_name(T::name(), &self.url);
         self.service.update_name(name);
         self
     }
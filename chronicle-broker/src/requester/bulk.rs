@@ -0,0 +1,255 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use bee_message::{
+    payload::Payload,
+    Message,
+};
+use chronicle_common::Wrapper;
+use reqwest::StatusCode;
+use std::{
+    collections::HashSet,
+    io::Cursor,
+    time::Instant,
+};
+
+/// Whether the api endpoints support the bulk retrieval paths, unknown ones get tried first
+static BULK_SUPPORT: tokio::sync::RwLock<Option<HashMap<(Url, BulkPath), bool>>> = tokio::sync::RwLock::const_new(None);
+
+/// The bulk retrieval paths of the api endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum BulkPath {
+    /// The raw message bytes, `messages/{message_id}/raw`
+    Raw,
+    /// The referenced messages of a milestone, as configured by the milestone cone path
+    MilestoneCone,
+}
+
+/// A message of the milestone cone along with its metadata
+#[derive(Deserialize)]
+struct ConeMessage {
+    message: MessageDto,
+    metadata: MessageMetadata,
+}
+
+/// The milestone cone response, which lists all the messages referenced by the milestone
+#[derive(Deserialize)]
+struct ConeResponse {
+    messages: Vec<ConeMessage>,
+}
+
+/// Whether the endpoint supports the bulk path, None if it's not known yet
+pub(crate) async fn supports(url: &Url, bulk_path: BulkPath) -> Option<bool> {
+    BULK_SUPPORT
+        .read()
+        .await
+        .as_ref()
+        .and_then(|bulk_support| bulk_support.get(&(url.clone(), bulk_path)).copied())
+}
+
+/// Record whether the endpoint supports the bulk path
+pub(crate) async fn set_support(url: &Url, bulk_path: BulkPath, supported: bool) {
+    if !supported {
+        info!(
+            "Endpoint: {} doesn't support {:?} bulk path, falling back to message by message requests",
+            url, bulk_path
+        );
+    }
+    BULK_SUPPORT
+        .write()
+        .await
+        .get_or_insert_with(HashMap::new)
+        .insert((url.clone(), bulk_path), supported);
+}
+
+/// Forget the bulk paths support of a removed endpoint
+pub(crate) async fn forget_endpoint(url: &Url) {
+    if let Some(bulk_support) = BULK_SUPPORT.write().await.as_mut() {
+        bulk_support.retain(|(u, _), _| u != url);
+    }
+}
+
+impl Requester {
+    /// Request the raw bytes of the message
+    pub(crate) async fn request_raw_message(&self, remote_url: &Url, message_id: MessageId) -> Result<Message, ()> {
+        let get_raw_message_url = remote_url.join(&format!("messages/{}/raw", message_id)).unwrap();
+        let raw_message_response = self
+            .reqwest_client
+            .get(get_raw_message_url)
+            .send()
+            .await
+            .map_err(|e| error!("Error sending request for raw message: {}", e))?;
+        if !raw_message_response.status().is_success() {
+            return Err(());
+        }
+        let bytes = raw_message_response
+            .bytes()
+            .await
+            .map_err(|e| error!("Error receiving raw message: {}", e))?;
+        Message::unpack(&mut Cursor::new(bytes.as_ref())).map_err(|e| error!("Error unpacking raw message: {:?}", e))
+    }
+    /// Request all the messages referenced by the milestone at once, from an endpoint which supports the milestone
+    /// cone path. None if no endpoint supports it, or the cone is not valid.
+    pub(crate) async fn request_milestone_cone(&self, milestone_index: u32) -> Option<Vec<FullMessage>> {
+        let milestone_cone_path = self.milestone_cone_path.as_ref()?;
+        let mut candidates = Vec::new();
+        for url in self.api_endpoints.iter() {
+            if supports(url, BulkPath::MilestoneCone).await != Some(false) {
+                candidates.push(url.clone());
+            }
+        }
+        let remote_url = self.select_endpoint_from(candidates.iter()).await?;
+        let get_cone_url = remote_url
+            .join(&milestone_cone_path.replace("{index}", &milestone_index.to_string()))
+            .ok()?;
+        let started = Instant::now();
+        let cone_response = match self.reqwest_client.get(get_cone_url).send().await {
+            Ok(cone_response) => cone_response,
+            Err(e) => {
+                error!("Error sending request for milestone cone: {}", e);
+                self.record_failure(&remote_url).await;
+                return None;
+            }
+        };
+        match cone_response.status() {
+            status if status.is_success() => (),
+            StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED => {
+                // the endpoint is alive, it just doesn't serve the milestone cones
+                self.record_success(&remote_url, started.elapsed()).await;
+                set_support(&remote_url, BulkPath::MilestoneCone, false).await;
+                return None;
+            }
+            status => {
+                error!(
+                    "Received error: {}, requesting milestone cone from {}",
+                    status, remote_url
+                );
+                self.record_failure(&remote_url).await;
+                return None;
+            }
+        }
+        let cone = match cone_response.json::<JsonData<ConeResponse>>().await {
+            Ok(cone) => cone.into_inner(),
+            Err(e) => {
+                error!("Error deserializing milestone cone from {}: {}", remote_url, e);
+                self.record_failure(&remote_url).await;
+                return None;
+            }
+        };
+        let (full_messages, boundary) = match self.validate_cone(milestone_index, cone) {
+            Ok(validated) => validated,
+            Err(e) => {
+                self.reject(
+                    &remote_url,
+                    anyhow!("invalid cone of milestone index: {}, {}", milestone_index, e),
+                )
+                .await;
+                self.record_failure(&remote_url).await;
+                return None;
+            }
+        };
+        // the parents outside of the cone must be referenced by older milestones, otherwise the cone is incomplete
+        let boundary_metadata = futures::future::join_all(
+            boundary
+                .iter()
+                .map(|parent_id| self.request_metadata(&remote_url, *parent_id)),
+        )
+        .await;
+        for (parent_id, metadata) in boundary.iter().zip(boundary_metadata) {
+            let referenced_by = match metadata {
+                Ok(metadata) => metadata.referenced_by_milestone_index,
+                Err(()) => {
+                    self.record_failure(&remote_url).await;
+                    return None;
+                }
+            };
+            if referenced_by.map_or(true, |ref_ms| ref_ms >= milestone_index) {
+                self.reject(
+                    &remote_url,
+                    anyhow!(
+                        "incomplete cone of milestone index: {}, parent: {} is missing, referenced by milestone index: {:?}",
+                        milestone_index,
+                        parent_id,
+                        referenced_by
+                    ),
+                )
+                .await;
+                self.record_failure(&remote_url).await;
+                return None;
+            }
+        }
+        self.record_success(&remote_url, started.elapsed()).await;
+        if supports(&remote_url, BulkPath::MilestoneCone).await.is_none() {
+            set_support(&remote_url, BulkPath::MilestoneCone, true).await;
+        }
+        for full_message in full_messages.iter() {
+            if let Some(Payload::Milestone(milestone_payload)) = full_message.message().payload() {
+                if milestone_payload.essence().index().0 == milestone_index {
+                    record::record_milestone(milestone_index, *full_message.message_id()).await;
+                }
+            }
+            record::record_full_message(full_message).await;
+        }
+        info!(
+            "Fetched the cone of milestone index: {}, with {} messages from: {}",
+            milestone_index,
+            full_messages.len(),
+            remote_url
+        );
+        Some(full_messages)
+    }
+    /// Ensure all the messages are valid and referenced by the milestone, and the cone includes the milestone itself
+    /// along with every message it references through the cone. Returns the full messages, and the parents outside
+    /// of the cone, which must be referenced by older milestones.
+    fn validate_cone(
+        &self,
+        milestone_index: u32,
+        cone: ConeResponse,
+    ) -> anyhow::Result<(Vec<FullMessage>, Vec<MessageId>)> {
+        let mut full_messages = Vec::with_capacity(cone.messages.len());
+        let mut milestone_id = None;
+        for ConeMessage { message, metadata } in cone.messages {
+            ensure!(
+                metadata.referenced_by_milestone_index == Some(milestone_index),
+                "message: {} is not referenced by the milestone",
+                metadata.message_id
+            );
+            let message = Message::try_from(&message).map_err(|e| anyhow!("{:?}", e))?;
+            self.validate_full_message(metadata.message_id, milestone_index, &message, &metadata)?;
+            if let Some(Payload::Milestone(milestone_payload)) = message.payload() {
+                if milestone_payload.essence().index().0 == milestone_index {
+                    milestone_id.replace(metadata.message_id);
+                }
+            }
+            full_messages.push(FullMessage::new(message, metadata));
+        }
+        let milestone_id = milestone_id.ok_or_else(|| anyhow!("the cone doesn't include the milestone"))?;
+        let cone = full_messages
+            .iter()
+            .map(|full_message| (*full_message.message_id(), full_message.message()))
+            .collect::<HashMap<_, _>>();
+        // walk the cone from the milestone down to the messages referenced by the previous milestones
+        let mut reached = HashSet::new();
+        let mut boundary = HashSet::new();
+        let mut to_visit = vec![milestone_id];
+        while let Some(message_id) = to_visit.pop() {
+            if !reached.insert(message_id) {
+                continue;
+            }
+            for parent_id in cone[&message_id].parents().iter() {
+                if cone.contains_key(parent_id) {
+                    to_visit.push(*parent_id);
+                } else if *parent_id != MessageId::null() {
+                    boundary.insert(*parent_id);
+                }
+            }
+        }
+        ensure!(
+            reached.len() == cone.len(),
+            "{} messages of the cone are not reachable from the milestone",
+            cone.len() - reached.len()
+        );
+        Ok((full_messages, boundary.into_iter().collect()))
+    }
+}
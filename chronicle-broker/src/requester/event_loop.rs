@@ -2,9 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use bee_message::Message;
+use bee_message::{
+    payload::Payload,
+    Message,
+};
 use chronicle_common::Wrapper;
 use serde_json::Value;
+use std::time::Instant;
 
 #[async_trait::async_trait]
 impl EventLoop<CollectorHandle> for Requester {
@@ -19,13 +23,25 @@ impl EventLoop<CollectorHandle> for Requester {
         let _ = collector_handle.send(event);
         while let Some(event) = self.inbox.recv().await {
             match event {
-                RequesterEvent::RequestFullMessage(message_id, try_ms_index) => {
-                    self.request_full_message_with_retries(collector_handle, message_id, try_ms_index)
-                        .await;
+                RequesterEvent::RequestFullMessages(message_ids, try_ms_index) => {
+                    // request the whole batch concurrently
+                    let requester = &*self;
+                    let collector_handle = &*collector_handle;
+                    let requests = message_ids.into_iter().map(move |message_id| {
+                        requester.request_full_message_with_retries(collector_handle, message_id, try_ms_index)
+                    });
+                    futures::future::join_all(requests).await;
                 }
                 RequesterEvent::RequestMilestone(milestone_index) => {
-                    self.request_milestone_message_with_retries(collector_handle, milestone_index)
-                        .await;
+                    // try to fetch the whole milestone cone at once, otherwise fallback to message by message
+                    if let Some(full_messages) = self.request_milestone_cone(milestone_index).await {
+                        let collector_event =
+                            CollectorEvent::MilestoneCone(self.requester_id, milestone_index, full_messages);
+                        let _ = collector_handle.send(collector_event);
+                    } else {
+                        self.request_milestone_message_with_retries(collector_handle, milestone_index)
+                            .await;
+                    }
                 }
                 RequesterEvent::Topology(topology) => match topology {
                     RequesterTopology::AddEndpoint(url) => {
@@ -42,6 +58,8 @@ impl EventLoop<CollectorHandle> for Requester {
                             info!("RemovedEndpoint: {}", url);
                             self.api_endpoints.remove(p);
                         }
+                        health::forget_endpoint(&url).await;
+                        bulk::forget_endpoint(&url).await;
                     }
                 },
             }
@@ -51,28 +69,48 @@ impl EventLoop<CollectorHandle> for Requester {
 }
 use std::str::FromStr;
 
+/// The reason a request didn't deliver the full message
+#[derive(Debug)]
+pub(super) enum RequestFailure {
+    /// The endpoint failed to answer or served invalid data
+    Faulty,
+    /// The message is not referenced by a milestone yet on the endpoint, which doesn't make it faulty
+    NotReferenced,
+}
+
 impl Requester {
     async fn request_full_message_with_retries(
-        &mut self,
-        collector_handle: &mut CollectorHandle,
+        &self,
+        collector_handle: &CollectorHandle,
         message_id: MessageId,
         try_ms_index: u32,
     ) {
         let mut retries = self.retries;
         loop {
             if retries > 0 {
-                if let Some(remote_url) = self.api_endpoints.pop_front() {
-                    if let Ok(full_message) = self.request_message_and_metadata(&remote_url, message_id).await {
-                        self.respond_to_collector(collector_handle, try_ms_index, Some(message_id), Some(full_message));
-                        self.api_endpoints.push_front(remote_url);
-                        break;
-                    } else {
-                        self.api_endpoints.push_back(remote_url);
-                        retries -= 1;
-                        // keep retrying, but yield to keep the system responsive
-                        tokio::task::yield_now().await;
-                        continue;
+                if let Some(remote_url) = self.select_endpoint().await {
+                    let started = Instant::now();
+                    match self
+                        .request_message_and_metadata(&remote_url, message_id, try_ms_index)
+                        .await
+                    {
+                        Ok(full_message) => {
+                            self.record_success(&remote_url, started.elapsed()).await;
+                            self.respond_to_collector(
+                                collector_handle,
+                                try_ms_index,
+                                Some(message_id),
+                                Some(full_message),
+                            );
+                            break;
+                        }
+                        // the endpoint answered properly, another endpoint might have caught up with the milestone
+                        Err(RequestFailure::NotReferenced) => self.record_success(&remote_url, started.elapsed()).await,
+                        Err(RequestFailure::Faulty) => self.record_failure(&remote_url).await,
                     }
+                    retries -= 1;
+                    // keep retrying, but yield to keep the system responsive
+                    tokio::task::yield_now().await;
                 } else {
                     self.respond_to_collector(collector_handle, try_ms_index, None, None);
                     break;
@@ -83,31 +121,29 @@ impl Requester {
             }
         }
     }
-    async fn request_milestone_message_with_retries(
-        &mut self,
-        collector_handle: &mut CollectorHandle,
-        milestone_index: u32,
-    ) {
+    async fn request_milestone_message_with_retries(&self, collector_handle: &CollectorHandle, milestone_index: u32) {
         let mut retries = self.retries;
         loop {
             if retries > 0 {
-                if let Some(remote_url) = self.api_endpoints.pop_front() {
-                    if let Ok(full_message) = self.request_milestone_message(&remote_url, milestone_index).await {
-                        self.respond_to_collector(
-                            collector_handle,
-                            milestone_index,
-                            Some(full_message.metadata().message_id),
-                            Some(full_message),
-                        );
-                        self.api_endpoints.push_front(remote_url);
-                        break;
-                    } else {
-                        self.api_endpoints.push_back(remote_url);
-                        retries -= 1;
-                        // keep retrying, but yield to keep the system responsive
-                        tokio::task::yield_now().await;
-                        continue;
+                if let Some(remote_url) = self.select_endpoint().await {
+                    let started = Instant::now();
+                    match self.request_milestone_message(&remote_url, milestone_index).await {
+                        Ok(full_message) => {
+                            self.record_success(&remote_url, started.elapsed()).await;
+                            self.respond_to_collector(
+                                collector_handle,
+                                milestone_index,
+                                Some(full_message.metadata().message_id),
+                                Some(full_message),
+                            );
+                            break;
+                        }
+                        Err(RequestFailure::NotReferenced) => self.record_success(&remote_url, started.elapsed()).await,
+                        Err(RequestFailure::Faulty) => self.record_failure(&remote_url).await,
                     }
+                    retries -= 1;
+                    // keep retrying, but yield to keep the system responsive
+                    tokio::task::yield_now().await;
                 } else {
                     self.respond_to_collector(collector_handle, milestone_index, None, None);
                     break;
@@ -129,7 +165,11 @@ impl Requester {
             CollectorEvent::MessageAndMeta(self.requester_id, ms_index, opt_message_id, opt_full_message);
         let _ = collector_handle.send(collector_event);
     }
-    async fn request_milestone_message(&mut self, remote_url: &Url, milestone_index: u32) -> Result<FullMessage, ()> {
+    async fn request_milestone_message(
+        &self,
+        remote_url: &Url,
+        milestone_index: u32,
+    ) -> Result<FullMessage, RequestFailure> {
         let get_milestone_url = remote_url.join(&format!("milestones/{}", milestone_index)).unwrap();
         let milestone_response = self
             .reqwest_client
@@ -145,8 +185,38 @@ impl Requester {
                     .map_err(|e| error!("Error deserializing milestone: {}", e));
                 if let Ok(milestone) = milestone {
                     let milestone = milestone.into_inner();
-                    let message_id = MessageId::from_str(&milestone.message_id).expect("Expected message_id as string");
-                    return self.request_message_and_metadata(remote_url, message_id).await;
+                    let message_id = match MessageId::from_str(&milestone.message_id) {
+                        Ok(message_id) => message_id,
+                        Err(e) => {
+                            let error = anyhow!(
+                                "milestone: {} has a malformed message id: {}, error: {:?}",
+                                milestone_index,
+                                milestone.message_id,
+                                e
+                            );
+                            self.reject(remote_url, error).await;
+                            return Err(RequestFailure::Faulty);
+                        }
+                    };
+                    let full_message = self
+                        .request_message_and_metadata(remote_url, message_id, milestone_index)
+                        .await?;
+                    match full_message.message().payload() {
+                        Some(Payload::Milestone(milestone_payload))
+                            if milestone_payload.essence().index().0 == milestone_index =>
+                        {
+                            record::record_milestone(milestone_index, message_id).await;
+                            return Ok(full_message);
+                        }
+                        _ => {
+                            let error = anyhow!(
+                                "message: {} is not the milestone with index: {}",
+                                message_id,
+                                milestone_index
+                            );
+                            self.reject(remote_url, error).await;
+                        }
+                    }
                 }
             } else {
                 if !milestone_response.status().is_success() {
@@ -156,59 +226,99 @@ impl Requester {
                 }
             }
         }
-        Err(())
+        Err(RequestFailure::Faulty)
     }
-    async fn request_message_and_metadata(
-        &mut self,
+    pub(super) async fn request_message_and_metadata(
+        &self,
         remote_url: &Url,
         message_id: MessageId,
-    ) -> Result<FullMessage, ()> {
+        try_ms_index: u32,
+    ) -> Result<FullMessage, RequestFailure> {
+        // request the message and its metadata concurrently
+        let (message, metadata) = match futures::join!(
+            self.request_message(remote_url, message_id),
+            self.request_metadata(remote_url, message_id)
+        ) {
+            (Ok(message), Ok(metadata)) => (message, metadata),
+            _ => return Err(RequestFailure::Faulty),
+        };
+        if metadata.referenced_by_milestone_index.is_none() {
+            return Err(RequestFailure::NotReferenced);
+        }
+        if let Err(e) = self.validate_full_message(message_id, try_ms_index, &message, &metadata) {
+            self.reject(remote_url, e).await;
+            return Err(RequestFailure::Faulty);
+        }
+        if let Some(ref_ms) = metadata.referenced_by_milestone_index {
+            if let Err(e) = self.verify_referenced_milestone(remote_url, ref_ms, try_ms_index).await {
+                self.reject(remote_url, e).await;
+                return Err(RequestFailure::Faulty);
+            }
+        }
+        let full_message = FullMessage::new(message, metadata);
+        record::record_full_message(&full_message).await;
+        Ok(full_message)
+    }
+    async fn request_message(&self, remote_url: &Url, message_id: MessageId) -> Result<Message, ()> {
+        // prefer the raw message bytes, unless the endpoint doesn't support them
+        let raw_support = bulk::supports(remote_url, BulkPath::Raw).await;
+        if raw_support != Some(false) {
+            if let Ok(message) = self.request_raw_message(remote_url, message_id).await {
+                if raw_support.is_none() {
+                    bulk::set_support(remote_url, BulkPath::Raw, true).await;
+                }
+                return Ok(message);
+            }
+        }
         let get_message_url = remote_url.join(&format!("messages/{}", message_id)).unwrap();
-        let get_metadata_url = remote_url.join(&format!("messages/{}/metadata", message_id)).unwrap();
         let message_response = self
             .reqwest_client
             .get(get_message_url)
             .send()
             .await
-            .map_err(|e| error!("Error sending request for message: {}", e));
+            .map_err(|e| error!("Error sending request for message: {}", e))?;
+        if message_response.status().is_success() {
+            let message = message_response
+                .json::<JsonData<MessageDto>>()
+                .await
+                .map_err(|e| error!("Error deserializing message: {}", e))?;
+            let message_dto = message.into_inner();
+            let message = Message::try_from(&message_dto).map_err(|e| error!("Error converting message: {:?}", e))?;
+            // the raw message failed while the json one succeeded, so the endpoint doesn't serve raw messages
+            if raw_support.is_none() {
+                bulk::set_support(remote_url, BulkPath::Raw, false).await;
+            }
+            Ok(message)
+        } else {
+            let url = message_response.url().clone();
+            let err = message_response.json::<Value>().await;
+            error!("Received error requesting message from {}:\n {:#?}", url, err);
+            Err(())
+        }
+    }
+    pub(super) async fn request_metadata(
+        &self,
+        remote_url: &Url,
+        message_id: MessageId,
+    ) -> Result<MessageMetadata, ()> {
+        let get_metadata_url = remote_url.join(&format!("messages/{}/metadata", message_id)).unwrap();
         let metadata_response = self
             .reqwest_client
             .get(get_metadata_url)
             .send()
             .await
-            .map_err(|e| error!("Error sending request for metadata: {}", e));
-        if let (Ok(message_response), Ok(metadata_response)) = (message_response, metadata_response) {
-            if message_response.status().is_success() && metadata_response.status().is_success() {
-                let message = message_response
-                    .json::<JsonData<MessageDto>>()
-                    .await
-                    .map_err(|e| error!("Error deserializing message: {}", e));
-                let metadata = metadata_response
-                    .json::<JsonData<MessageMetadata>>()
-                    .await
-                    .map_err(|e| error!("Error deserializing metadata: {}", e));
-                if let (Ok(message), Ok(metadata)) = (message, metadata) {
-                    let message_dto = message.into_inner();
-                    let message = Message::try_from(&message_dto).unwrap();
-                    let metadata = metadata.into_inner();
-                    if metadata.referenced_by_milestone_index.is_some() {
-                        let full_message = FullMessage::new(message, metadata);
-                        return Ok(full_message);
-                    }
-                }
-            } else {
-                if !message_response.status().is_success() {
-                    let url = message_response.url().clone();
-                    let err = message_response.json::<Value>().await;
-                    error!("Received error requesting message from {}:\n {:#?}", url, err);
-                }
-                if !metadata_response.status().is_success() {
-                    let url = metadata_response.url().clone();
-                    let err = metadata_response.json::<Value>().await;
-                    error!("Received error requesting metadata from {}:\n {:#?}", url, err);
-                }
-            }
+            .map_err(|e| error!("Error sending request for metadata: {}", e))?;
+        if metadata_response.status().is_success() {
+            let metadata = metadata_response
+                .json::<JsonData<MessageMetadata>>()
+                .await
+                .map_err(|e| error!("Error deserializing metadata: {}", e))?;
+            Ok(metadata.into_inner())
+        } else {
+            let url = metadata_response.url().clone();
+            let err = metadata_response.json::<Value>().await;
+            error!("Received error requesting metadata from {}:\n {:#?}", url, err);
+            Err(())
         }
-        Err(())
     }
 }
@@ -0,0 +1,414 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use chronicle_common::metrics::{
+    ENDPOINT_CIRCUIT_STATE,
+    ENDPOINT_ERROR_RATE,
+    ENDPOINT_HEALTHY,
+    ENDPOINT_LATENCY_MS,
+    ENDPOINT_REQUESTS,
+};
+use rand::Rng;
+use serde_json::Value;
+use std::time::{
+    Duration,
+    Instant,
+};
+
+/// The smoothing factor of the latency and error rate EWMAs
+const EWMA_ALPHA: f64 = 0.2;
+/// The latency assumed for the endpoints without any successful request, so they get tried early
+const DEFAULT_LATENCY_MS: f64 = 100.0;
+/// The weight factor of the endpoints which report themselves as not synced
+const UNHEALTHY_PENALTY: f64 = 0.1;
+
+/// The health of the api endpoints, shared by all the requesters
+pub(crate) static ENDPOINTS_HEALTH: tokio::sync::RwLock<Option<HashMap<Url, EndpointHealth>>> =
+    tokio::sync::RwLock::const_new(None);
+
+/// The health of an api endpoint along with its circuit breaker
+pub(crate) struct EndpointHealth {
+    circuit: CircuitState,
+    opened_at: Option<Instant>,
+    probing: bool,
+    quarantined_until: Option<Instant>,
+    healthy: Option<bool>,
+    confirmed_milestone_index: Option<u32>,
+    latency_ms: Option<f64>,
+    error_rate: f64,
+    consecutive_failures: u32,
+    requests: u64,
+    failures: u64,
+}
+
+impl Default for EndpointHealth {
+    fn default() -> Self {
+        Self {
+            circuit: CircuitState::Closed,
+            opened_at: None,
+            probing: false,
+            quarantined_until: None,
+            healthy: None,
+            confirmed_milestone_index: None,
+            latency_ms: None,
+            error_rate: 0.0,
+            consecutive_failures: 0,
+            requests: 0,
+            failures: 0,
+        }
+    }
+}
+
+impl EndpointHealth {
+    /// The selection weight of the endpoint, None if its circuit doesn't allow any request
+    fn weight(&mut self, open_duration: Duration) -> Option<f64> {
+        if self.is_quarantined() {
+            return None;
+        }
+        if let CircuitState::Open = self.circuit {
+            if self
+                .opened_at
+                .map_or(true, |opened_at| opened_at.elapsed() >= open_duration)
+            {
+                self.circuit = CircuitState::HalfOpen;
+            }
+        }
+        match self.circuit {
+            CircuitState::Open => return None,
+            // only a single probe is allowed while half open
+            CircuitState::HalfOpen if self.probing => return None,
+            _ => (),
+        }
+        let mut weight = (1.0 - self.error_rate).max(0.01) / self.latency_ms.unwrap_or(DEFAULT_LATENCY_MS).max(1.0);
+        if let Some(false) = self.healthy {
+            weight *= UNHEALTHY_PENALTY;
+        }
+        Some(weight)
+    }
+    fn record_success(&mut self, latency: Duration) {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        self.latency_ms = Some(
+            self.latency_ms
+                .map_or(latency_ms, |ewma| EWMA_ALPHA * latency_ms + (1.0 - EWMA_ALPHA) * ewma),
+        );
+        self.error_rate *= 1.0 - EWMA_ALPHA;
+        self.consecutive_failures = 0;
+        self.requests += 1;
+        self.circuit = CircuitState::Closed;
+        self.opened_at = None;
+        self.probing = false;
+    }
+    fn record_failure(&mut self, failure_threshold: u32) {
+        self.error_rate = EWMA_ALPHA + (1.0 - EWMA_ALPHA) * self.error_rate;
+        self.consecutive_failures += 1;
+        self.requests += 1;
+        self.failures += 1;
+        let open = match self.circuit {
+            // the half open probe failed
+            CircuitState::HalfOpen => true,
+            CircuitState::Closed => self.consecutive_failures >= failure_threshold,
+            CircuitState::Open => false,
+        };
+        if open {
+            self.circuit = CircuitState::Open;
+            self.opened_at.replace(Instant::now());
+            self.probing = false;
+        }
+    }
+    fn is_quarantined(&self) -> bool {
+        self.quarantined_until
+            .map_or(false, |quarantined_until| quarantined_until > Instant::now())
+    }
+    fn status(&self, url: &Url) -> EndpointStatus {
+        EndpointStatus {
+            url: url.clone(),
+            circuit: self.circuit,
+            quarantined: self.is_quarantined(),
+            healthy: self.healthy,
+            confirmed_milestone_index: self.confirmed_milestone_index,
+            latency_ms: self.latency_ms,
+            error_rate: self.error_rate,
+            consecutive_failures: self.consecutive_failures,
+            requests: self.requests,
+            failures: self.failures,
+        }
+    }
+    fn update_metrics(&self, url: &Url) {
+        let endpoint = url.as_str();
+        if let Some(latency_ms) = self.latency_ms {
+            ENDPOINT_LATENCY_MS.with_label_values(&[endpoint]).set(latency_ms);
+        }
+        ENDPOINT_ERROR_RATE.with_label_values(&[endpoint]).set(self.error_rate);
+        let circuit_state = match self.circuit {
+            CircuitState::Closed => 0,
+            CircuitState::HalfOpen => 1,
+            CircuitState::Open => 2,
+        };
+        ENDPOINT_CIRCUIT_STATE.with_label_values(&[endpoint]).set(circuit_state);
+        if let Some(healthy) = self.healthy {
+            ENDPOINT_HEALTHY.with_label_values(&[endpoint]).set(healthy as i64);
+        }
+    }
+}
+
+impl Requester {
+    /// Select an api endpoint using weighted random selection over the endpoints allowed by their circuit breaker
+    pub(crate) async fn select_endpoint(&self) -> Option<Url> {
+        self.select_endpoint_from(self.api_endpoints.iter()).await
+    }
+    /// Select an api endpoint among the given ones, using weighted random selection
+    pub(crate) async fn select_endpoint_from<'a>(&self, urls: impl Iterator<Item = &'a Url>) -> Option<Url> {
+        let mut endpoints_health = ENDPOINTS_HEALTH.write().await;
+        let endpoints_health = endpoints_health.get_or_insert_with(HashMap::new);
+        let mut candidates = Vec::new();
+        let mut total_weight = 0.0;
+        for url in urls {
+            let endpoint_health = endpoints_health.entry(url.clone()).or_default();
+            if let Some(weight) = endpoint_health.weight(self.open_duration) {
+                total_weight += weight;
+                candidates.push((url, weight));
+            }
+        }
+        let mut point = rand::thread_rng().gen_range(0.0..=total_weight);
+        let url = candidates
+            .iter()
+            .find(|(_, weight)| {
+                point -= weight;
+                point <= 0.0
+            })
+            .or(candidates.last())
+            .map(|(url, _)| (*url).clone())?;
+        let endpoint_health = endpoints_health.get_mut(&url)?;
+        if let CircuitState::HalfOpen = endpoint_health.circuit {
+            info!("Probing the half open endpoint: {}", url);
+            endpoint_health.probing = true;
+        }
+        Some(url)
+    }
+    /// Record a successful request along with its latency
+    pub(crate) async fn record_success(&self, url: &Url, latency: Duration) {
+        let mut endpoints_health = ENDPOINTS_HEALTH.write().await;
+        let endpoint_health = endpoints_health
+            .get_or_insert_with(HashMap::new)
+            .entry(url.clone())
+            .or_default();
+        if let CircuitState::HalfOpen = endpoint_health.circuit {
+            info!("Closed the circuit of endpoint: {}", url);
+        }
+        endpoint_health.record_success(latency);
+        endpoint_health.update_metrics(url);
+        ENDPOINT_REQUESTS.with_label_values(&[url.as_str(), "success"]).inc();
+    }
+    /// Record a failed request, which might open the circuit of the endpoint
+    pub(crate) async fn record_failure(&self, url: &Url) {
+        let mut endpoints_health = ENDPOINTS_HEALTH.write().await;
+        let endpoint_health = endpoints_health
+            .get_or_insert_with(HashMap::new)
+            .entry(url.clone())
+            .or_default();
+        let was_open = endpoint_health.circuit == CircuitState::Open;
+        endpoint_health.record_failure(self.failure_threshold);
+        if !was_open && endpoint_health.circuit == CircuitState::Open {
+            warn!(
+                "Opened the circuit of endpoint: {}, after {} consecutive failures",
+                url, endpoint_health.consecutive_failures
+            );
+        }
+        endpoint_health.update_metrics(url);
+        ENDPOINT_REQUESTS.with_label_values(&[url.as_str(), "failure"]).inc();
+    }
+    /// Exclude the endpoint from the selection for the quarantine duration, as it served invalid data.
+    /// Returns true if the endpoint wasn't quarantined already
+    pub(crate) async fn quarantine(&self, url: &Url) -> bool {
+        let mut endpoints_health = ENDPOINTS_HEALTH.write().await;
+        let endpoint_health = endpoints_health
+            .get_or_insert_with(HashMap::new)
+            .entry(url.clone())
+            .or_default();
+        let was_quarantined = endpoint_health.is_quarantined();
+        endpoint_health
+            .quarantined_until
+            .replace(Instant::now() + self.quarantine_duration);
+        ENDPOINT_REQUESTS.with_label_values(&[url.as_str(), "invalid"]).inc();
+        !was_quarantined
+    }
+}
+
+/// Register the api endpoints, so they get probed before their first request
+pub(crate) async fn register_endpoints<'a>(urls: impl Iterator<Item = &'a Url>) {
+    let mut endpoints_health = ENDPOINTS_HEALTH.write().await;
+    let endpoints_health = endpoints_health.get_or_insert_with(HashMap::new);
+    for url in urls {
+        endpoints_health.entry(url.clone()).or_default();
+    }
+}
+
+/// Forget the health of a removed api endpoint
+pub(crate) async fn forget_endpoint(url: &Url) {
+    if let Some(endpoints_health) = ENDPOINTS_HEALTH.write().await.as_mut() {
+        if endpoints_health.remove(url).is_some() {
+            let endpoint = url.as_str();
+            ENDPOINT_LATENCY_MS.remove_label_values(&[endpoint]).ok();
+            ENDPOINT_ERROR_RATE.remove_label_values(&[endpoint]).ok();
+            ENDPOINT_CIRCUIT_STATE.remove_label_values(&[endpoint]).ok();
+            ENDPOINT_HEALTHY.remove_label_values(&[endpoint]).ok();
+        }
+    }
+}
+
+/// The health of all the api endpoints
+pub(crate) async fn endpoints_status() -> Vec<EndpointStatus> {
+    let mut endpoints_status = ENDPOINTS_HEALTH
+        .read()
+        .await
+        .iter()
+        .flat_map(|endpoints_health| endpoints_health.iter())
+        .map(|(url, endpoint_health)| endpoint_health.status(url))
+        .collect::<Vec<_>>();
+    endpoints_status.sort_by(|a, b| a.url.cmp(&b.url));
+    endpoints_status
+}
+
+/// Periodically fetch the info of the api endpoints, to keep their sync status up to date
+pub(crate) async fn probe_endpoints(reqwest_client: Client, interval: Duration) {
+    loop {
+        let urls = ENDPOINTS_HEALTH
+            .read()
+            .await
+            .iter()
+            .flat_map(|endpoints_health| endpoints_health.keys())
+            .cloned()
+            .collect::<Vec<Url>>();
+        for url in urls {
+            let info = fetch_info(&reqwest_client, &url).await;
+            if let Some(endpoint_health) = ENDPOINTS_HEALTH
+                .write()
+                .await
+                .as_mut()
+                .and_then(|endpoints_health| endpoints_health.get_mut(&url))
+            {
+                match info {
+                    Ok((healthy, confirmed_milestone_index)) => {
+                        if endpoint_health.healthy == Some(true) && !healthy {
+                            warn!("Endpoint: {} reports itself as not synced", url);
+                        }
+                        endpoint_health.healthy.replace(healthy);
+                        endpoint_health.confirmed_milestone_index = confirmed_milestone_index;
+                    }
+                    Err(e) => {
+                        warn!("Unable to fetch the info of endpoint: {}, error: {}", url, e);
+                        endpoint_health.healthy.replace(false);
+                    }
+                }
+                endpoint_health.update_metrics(&url);
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Fetch the sync status and the confirmed milestone index of the node
+async fn fetch_info(reqwest_client: &Client, url: &Url) -> anyhow::Result<(bool, Option<u32>)> {
+    let info = reqwest_client
+        .get(url.join("info")?)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Value>()
+        .await?;
+    let info = info.get("data").unwrap_or(&info);
+    let healthy = info
+        .get("isHealthy")
+        .and_then(Value::as_bool)
+        .ok_or_else(|| anyhow!("Missing isHealthy field"))?;
+    let confirmed_milestone_index = info
+        .get("confirmedMilestoneIndex")
+        .and_then(Value::as_u64)
+        .map(|index| index as u32);
+    Ok((healthy, confirmed_milestone_index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A requester of a single endpoint, unique to the test as the endpoints health is shared
+    fn requester(open_secs: u64) -> (Requester, Url) {
+        let url = Url::parse(&format!("http://endpoint-{}.test/api/v1/", rand::random::<u64>())).unwrap();
+        let mut config = chronicle_common::config::Config::default();
+        config.broker_config.endpoint_failure_threshold = Some(3);
+        config.broker_config.endpoint_open_secs = Some(open_secs);
+        let (_, rx) = tokio::sync::mpsc::unbounded_channel();
+        let requester = RequesterBuilder::new()
+            .requester_id(0)
+            .inbox(RequesterInbox { rx })
+            .api_endpoints(VecDeque::from(vec![url.clone()]))
+            .reqwest_client(Client::new())
+            .retries_per_endpoint(1)
+            .build_with(&config);
+        (requester, url)
+    }
+
+    async fn status(url: &Url) -> EndpointStatus {
+        endpoints_status()
+            .await
+            .into_iter()
+            .find(|status| &status.url == url)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn circuit_opens_at_failure_threshold() {
+        let (requester, url) = requester(30);
+        for _ in 0..2 {
+            requester.record_failure(&url).await;
+            assert_eq!(status(&url).await.circuit, CircuitState::Closed);
+            assert_eq!(requester.select_endpoint().await, Some(url.clone()));
+        }
+        requester.record_failure(&url).await;
+        let endpoint_status = status(&url).await;
+        assert_eq!(endpoint_status.circuit, CircuitState::Open);
+        assert_eq!(endpoint_status.consecutive_failures, 3);
+        // the open circuit doesn't allow any request until the open duration elapsed
+        assert_eq!(requester.select_endpoint().await, None);
+    }
+
+    #[tokio::test]
+    async fn single_half_open_probe() {
+        let (requester, url) = requester(0);
+        for _ in 0..3 {
+            requester.record_failure(&url).await;
+        }
+        assert_eq!(status(&url).await.circuit, CircuitState::Open);
+        // the open duration elapsed, so a single probe is allowed
+        assert_eq!(requester.select_endpoint().await, Some(url.clone()));
+        assert_eq!(status(&url).await.circuit, CircuitState::HalfOpen);
+        assert_eq!(requester.select_endpoint().await, None);
+        assert_eq!(requester.select_endpoint().await, None);
+        // the failed probe opens the circuit again, right away
+        requester.record_failure(&url).await;
+        assert_eq!(status(&url).await.circuit, CircuitState::Open);
+    }
+
+    #[tokio::test]
+    async fn half_open_probe_recovery() {
+        let (requester, url) = requester(0);
+        for _ in 0..3 {
+            requester.record_failure(&url).await;
+        }
+        assert_eq!(requester.select_endpoint().await, Some(url.clone()));
+        requester.record_success(&url, Duration::from_millis(50)).await;
+        let endpoint_status = status(&url).await;
+        assert_eq!(endpoint_status.circuit, CircuitState::Closed);
+        assert_eq!(endpoint_status.consecutive_failures, 0);
+        assert_eq!(endpoint_status.failures, 3);
+        // the closed circuit allows any number of requests
+        for _ in 0..3 {
+            assert_eq!(requester.select_endpoint().await, Some(url.clone()));
+        }
+        // and it takes the whole failure threshold to open it again
+        requester.record_failure(&url).await;
+        assert_eq!(status(&url).await.circuit, CircuitState::Closed);
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::feed::record::RecordedEvent;
+use hyper::{
+    service::{
+        make_service_fn,
+        service_fn,
+    },
+    Body,
+    Response,
+    Server,
+    StatusCode,
+};
+use serde_json::Value;
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    str::FromStr,
+    sync::Arc,
+};
+
+/// A mock api endpoint, which serves the messages and milestones of a recording to the requesters,
+/// so the broker can run offline along with the replayed feeds
+#[derive(Debug, Default)]
+pub struct MockEndpoint {
+    messages: HashMap<MessageId, (Vec<u8>, MessageMetadata)>,
+    milestones: HashMap<u32, MessageId>,
+}
+
+impl MockEndpoint {
+    /// Load the messages and milestones of the recording
+    pub async fn from_recording(path: &str) -> anyhow::Result<Self> {
+        let mut endpoint = Self::default();
+        for record in record::read_recording(path).await? {
+            match record.event {
+                RecordedEvent::Message { raw, metadata } => {
+                    let raw = hex::decode(&raw)
+                        .map_err(|e| anyhow!("Invalid raw message: {}, error: {}", metadata.message_id, e))?;
+                    endpoint.messages.insert(metadata.message_id, (raw, metadata));
+                }
+                RecordedEvent::Milestone { index, message_id } => {
+                    endpoint.milestones.insert(index, message_id);
+                }
+                RecordedEvent::Feed { .. } => (),
+            }
+        }
+        Ok(endpoint)
+    }
+    /// Serve the endpoint on the address, returns the bound address once it's listening.
+    /// The endpoint answers on any base path, ie `http://{address}/api/v1/`
+    pub fn serve(self, address: SocketAddr) -> anyhow::Result<(SocketAddr, tokio::task::JoinHandle<()>)> {
+        let endpoint = Arc::new(self);
+        let make_service = make_service_fn(move |_| {
+            let endpoint = endpoint.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let response = endpoint.respond(request.uri().path());
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+        let server = Server::try_bind(&address)
+            .map_err(|e| anyhow!("Unable to bind the mock endpoint: {}, error: {}", address, e))?
+            .serve(make_service);
+        let address = server.local_addr();
+        info!("Serving the mock endpoint on: {}", address);
+        let handle = tokio::spawn(async move {
+            if let Err(e) = server.await {
+                error!("Mock endpoint error: {}", e);
+            }
+        });
+        Ok((address, handle))
+    }
+    fn respond(&self, path: &str) -> Response<Body> {
+        let segments = path.trim_end_matches('/').rsplit('/').take(3).collect::<Vec<_>>();
+        let message = |id: &str| MessageId::from_str(id).ok().and_then(|id| self.messages.get(&id));
+        match segments.as_slice() {
+            ["info", ..] => {
+                let latest = self.milestones.keys().max().copied().unwrap_or_default();
+                json_response(serde_json::json!({
+                    "isHealthy": true,
+                    "latestMilestoneIndex": latest,
+                    "confirmedMilestoneIndex": latest,
+                }))
+            }
+            ["raw", id, "messages"] => match message(id) {
+                Some((raw, _)) => Response::new(Body::from(raw.clone())),
+                None => not_found(),
+            },
+            ["metadata", id, "messages"] => match message(id) {
+                Some((_, metadata)) => match serde_json::to_value(metadata) {
+                    Ok(metadata) => json_response(metadata),
+                    Err(_) => not_found(),
+                },
+                None => not_found(),
+            },
+            [index, "milestones", ..] => {
+                let milestone = index
+                    .parse::<u32>()
+                    .ok()
+                    .and_then(|index| self.milestones.get(&index).map(|message_id| (index, message_id)));
+                match milestone {
+                    Some((index, message_id)) => json_response(serde_json::json!({
+                        "index": index,
+                        "messageId": message_id.to_string(),
+                        "timestamp": 0,
+                    })),
+                    None => not_found(),
+                }
+            }
+            _ => not_found(),
+        }
+    }
+}
+
+fn json_response(data: Value) -> Response<Body> {
+    let mut response = Response::new(Body::from(serde_json::json!({ "data": data }).to_string()));
+    response
+        .headers_mut()
+        .insert(hyper::header::CONTENT_TYPE, "application/json".parse().unwrap());
+    response
+}
+
+fn not_found() -> Response<Body> {
+    let mut response = Response::new(Body::from(
+        serde_json::json!({ "error": { "code": "404", "message": "not found" } }).to_string(),
+    ));
+    *response.status_mut() = StatusCode::NOT_FOUND;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::{
+        record::Record,
+        FeedSource,
+        ReplaySource,
+    };
+    use bee_message::{
+        parents::Parents,
+        MessageBuilder,
+    };
+    use bee_pow::providers::miner::Miner;
+
+    #[tokio::test]
+    async fn replay_recording() {
+        let message = MessageBuilder::<Miner>::new()
+            .with_network_id(0)
+            .with_parents(Parents::new(vec![MessageId::new([1; 32]), MessageId::new([2; 32])]).unwrap())
+            .finish()
+            .unwrap();
+        let (message_id, _) = message.id();
+        let raw = hex::encode(message.pack_new());
+        let metadata = MessageMetadata {
+            message_id,
+            parent_message_ids: message.parents().iter().copied().collect(),
+            is_solid: true,
+            referenced_by_milestone_index: Some(1),
+            ledger_inclusion_state: Some(LedgerInclusionState::NoTransaction),
+            should_promote: None,
+            should_reattach: None,
+        };
+        let records = vec![
+            Record {
+                elapsed_ms: 0,
+                event: RecordedEvent::Feed {
+                    topic: "messages".to_owned(),
+                    url: Url::parse("tcp://localhost:1883").unwrap(),
+                    payload: raw.clone(),
+                },
+            },
+            Record {
+                elapsed_ms: 10,
+                event: RecordedEvent::Message { raw, metadata },
+            },
+        ];
+        let path = std::env::temp_dir().join(format!("chronicle-{}.rec", rand::random::<u64>()));
+        let recording = records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap() + "\n")
+            .collect::<String>();
+        tokio::fs::write(&path, recording).await.unwrap();
+        // the replayed feed delivers the recorded message
+        let mut url = Url::from_file_path(&path).unwrap();
+        url.set_query(Some("speed=0"));
+        let mut replay = ReplaySource::new(&url).unwrap();
+        let disconnect = replay.subscribe("messages", 0).await.unwrap();
+        let payload = replay.next_payload().await.unwrap();
+        let replayed = Message::unpack(&mut payload.as_slice()).unwrap();
+        assert_eq!(replayed.id().0, message_id);
+        disconnect();
+        assert!(replay.next_payload().await.is_none());
+        // the requester fetches the missing message from the mock endpoint
+        let mock_endpoint = MockEndpoint::from_recording(path.to_str().unwrap()).await.unwrap();
+        let (address, handle) = mock_endpoint.serve(([127, 0, 0, 1], 0).into()).unwrap();
+        let endpoint = Url::parse(&format!("http://{}/api/v1/", address)).unwrap();
+        let (_, rx) = tokio::sync::mpsc::unbounded_channel();
+        let requester = RequesterBuilder::new()
+            .requester_id(0)
+            .inbox(RequesterInbox { rx })
+            .api_endpoints(VecDeque::from(vec![endpoint.clone()]))
+            .reqwest_client(Client::new())
+            .retries_per_endpoint(1)
+            .build_with(&Default::default());
+        let full_message = requester
+            .request_message_and_metadata(&endpoint, message_id, 1)
+            .await
+            .unwrap();
+        assert_eq!(full_message.message(), &message);
+        assert_eq!(full_message.metadata().referenced_by_milestone_index, Some(1));
+        // the messages which are not recorded are not served
+        assert!(requester
+            .request_message_and_metadata(&endpoint, MessageId::new([3; 32]), 1)
+            .await
+            .is_err());
+        handle.abort();
+        tokio::fs::remove_file(&path).await.ok();
+    }
+}
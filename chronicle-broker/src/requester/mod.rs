@@ -6,12 +6,14 @@ use super::{
         CollectorHandle,
         Internal,
     },
+    feed::record,
     *,
 };
 use bee_rest_api::types::{
     dtos::MessageDto,
     responses::MilestoneResponse,
 };
+use bulk::BulkPath;
 use reqwest::Client;
 use std::{
     collections::VecDeque,
@@ -23,9 +25,13 @@ use std::{
 };
 use url::Url;
 
+mod bulk;
 mod event_loop;
+pub(crate) mod health;
 mod init;
+pub mod mock;
 mod terminating;
+mod validation;
 
 /// Requester Tokio handle
 pub type RequesterSender = tokio::sync::mpsc::UnboundedSender<RequesterEvent>;
@@ -44,8 +50,8 @@ pub(crate) type RequesterId = u8;
 
 /// Requester events
 pub enum RequesterEvent {
-    /// Requesting MessageId in order to solidifiy u32 MilestoneIndex
-    RequestFullMessage(MessageId, u32),
+    /// Requesting a batch of MessageIds in order to solidifiy u32 MilestoneIndex
+    RequestFullMessages(Vec<MessageId>, u32),
     /// Requesting Milestone for u32 milestone index;
     RequestMilestone(u32),
     /// RequesterTopology event, to update the api endpoints
@@ -66,8 +72,13 @@ impl RequesterHandle {
         self.processed_count -= 1;
     }
     pub(crate) fn send_event(&mut self, event: RequesterEvent) {
+        // every requested message gets its own response
+        let count = match &event {
+            RequesterEvent::RequestFullMessages(message_ids, _) => message_ids.len() as u64,
+            _ => 1,
+        };
         let _ = self.tx.send(event);
-        self.processed_count += 1;
+        self.processed_count += count;
     }
 }
 impl std::cmp::Ord for RequesterHandle {
@@ -127,6 +138,13 @@ pub struct Requester {
     api_endpoints: VecDeque<Url>,
     reqwest_client: Client,
     retries: usize,
+    failure_threshold: u32,
+    open_duration: std::time::Duration,
+    milestone_cone_path: Option<String>,
+    min_pow_score: Option<u64>,
+    quarantine_duration: std::time::Duration,
+    keyspace: ChronicleKeyspace,
+    retries_per_query: usize,
 }
 
 impl ActorBuilder<CollectorHandle> for RequesterBuilder {}
@@ -135,17 +153,40 @@ impl ActorBuilder<CollectorHandle> for RequesterBuilder {}
 impl Builder for RequesterBuilder {
     type State = Requester;
     fn build(self) -> Self::State {
+        self.build_with(&chronicle_common::get_config())
+    }
+}
+
+impl RequesterBuilder {
+    /// Build the requester with the given config
+    pub(crate) fn build_with(self, config: &chronicle_common::config::Config) -> Requester {
         let api_endpoints = self.api_endpoints.unwrap();
         // we retry up to N times per api endpoint for a given request
         let retries_per_endpoint = self.retries_per_endpoint.unwrap_or(5);
         let retries = api_endpoints.len() * retries_per_endpoint;
-        Self::State {
+        Requester {
             service: Service::new(),
             inbox: self.inbox.unwrap(),
             requester_id: self.requester_id.unwrap(),
             api_endpoints,
             reqwest_client: self.reqwest_client.unwrap(),
             retries,
+            failure_threshold: config.broker_config.endpoint_failure_threshold.unwrap_or(5),
+            open_duration: std::time::Duration::from_secs(config.broker_config.endpoint_open_secs.unwrap_or(30)),
+            milestone_cone_path: config.broker_config.milestone_cone_path.clone(),
+            min_pow_score: config.broker_config.min_pow_score,
+            quarantine_duration: std::time::Duration::from_secs(
+                config.broker_config.endpoint_quarantine_secs.unwrap_or(60 * 60),
+            ),
+            keyspace: ChronicleKeyspace::new(
+                config
+                    .storage_config
+                    .keyspaces
+                    .first()
+                    .map(|keyspace| keyspace.name.clone())
+                    .unwrap_or("permanode".to_owned()),
+            ),
+            retries_per_query: config.broker_config.retries_per_query,
         }
         .set_name()
     }
@@ -0,0 +1,316 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use bee_message::{
+    milestone::Milestone,
+    payload::Payload,
+    prelude::MilestoneIndex,
+};
+use bee_pow::score::compute_pow_score;
+use chronicle_common::{
+    alert,
+    Wrapper,
+};
+
+impl Requester {
+    /// Validate the message and its metadata served by the api endpoint before they get persisted.
+    /// The message must be referenced by a milestone not newer than the given milestone index.
+    pub(crate) fn validate_full_message(
+        &self,
+        message_id: MessageId,
+        milestone_index: u32,
+        message: &Message,
+        metadata: &MessageMetadata,
+    ) -> anyhow::Result<()> {
+        let (actual_message_id, _) = message.id();
+        ensure!(
+            actual_message_id == message_id,
+            "the hash of message: {} is {}",
+            message_id,
+            actual_message_id
+        );
+        ensure!(
+            metadata.message_id == message_id,
+            "the metadata of message: {} belongs to message: {}",
+            message_id,
+            metadata.message_id
+        );
+        ensure!(
+            metadata.parent_message_ids.iter().eq(message.parents().iter()),
+            "the metadata parents of message: {} don't match the message parents",
+            message_id
+        );
+        let ref_ms = metadata
+            .referenced_by_milestone_index
+            .ok_or_else(|| anyhow!("message: {} is not referenced by any milestone", message_id))?;
+        // a message can't be referenced by a milestone newer than the ones referencing its children
+        ensure!(
+            ref_ms != 0 && ref_ms <= milestone_index,
+            "message: {} is referenced by milestone index: {}, while expected up to: {}",
+            message_id,
+            ref_ms,
+            milestone_index
+        );
+        let is_transaction = matches!(message.payload(), Some(Payload::Transaction(_)));
+        match metadata.ledger_inclusion_state {
+            Some(LedgerInclusionState::Included) | Some(LedgerInclusionState::Conflicting) if !is_transaction => {
+                bail!(
+                    "message: {} has a ledger inclusion state without a transaction",
+                    message_id
+                )
+            }
+            Some(LedgerInclusionState::NoTransaction) if is_transaction => {
+                bail!("message: {} has a transaction marked as noTransaction", message_id)
+            }
+            None => bail!("referenced message: {} has no ledger inclusion state", message_id),
+            _ => (),
+        }
+        if let Some(Payload::Milestone(milestone_payload)) = message.payload() {
+            ensure!(
+                milestone_payload.essence().index().0 == ref_ms,
+                "milestone message: {} with index: {} is referenced by milestone index: {}",
+                message_id,
+                milestone_payload.essence().index().0,
+                ref_ms
+            );
+        } else if let Some(min_pow_score) = self.min_pow_score {
+            // the milestones are issued by the coordinator, so only the other messages are subject to the PoW
+            let pow_score = compute_pow_score(&message.pack_new());
+            ensure!(
+                pow_score >= min_pow_score as f64,
+                "message: {} has PoW score: {:.0}, below the network minimum: {}",
+                message_id,
+                pow_score,
+                min_pow_score
+            );
+        }
+        Ok(())
+    }
+    /// Ensure the milestone referencing a message exists: it's either the milestone being solidified, a stored one,
+    /// or one served by the api endpoint, as the older milestones may not be synced yet.
+    pub(crate) async fn verify_referenced_milestone(
+        &self,
+        remote_url: &Url,
+        ref_ms: u32,
+        milestone_index: u32,
+    ) -> anyhow::Result<()> {
+        if ref_ms == milestone_index {
+            return Ok(());
+        }
+        match crate::storage::query::<MilestoneIndex, Milestone>(
+            &self.keyspace,
+            MilestoneIndex(ref_ms),
+            self.retries_per_query,
+        )
+        .await
+        {
+            Ok(Some(_)) => return Ok(()),
+            Ok(None) => (),
+            Err(e) => warn!("Unable to look up the stored milestone index: {}, error: {}", ref_ms, e),
+        }
+        let get_milestone_url = remote_url.join(&format!("milestones/{}", ref_ms))?;
+        let milestone = self
+            .reqwest_client
+            .get(get_milestone_url)
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(|e| anyhow!("the referenced milestone index: {} is missing, error: {}", ref_ms, e))?
+            .json::<JsonData<MilestoneResponse>>()
+            .await?
+            .into_inner();
+        ensure!(
+            milestone.milestone_index == ref_ms,
+            "the referenced milestone index: {} is served as milestone index: {}",
+            ref_ms,
+            milestone.milestone_index
+        );
+        Ok(())
+    }
+    /// Quarantine the api endpoint which served invalid data, and raise an alert the first time
+    pub(crate) async fn reject(&self, remote_url: &Url, error: anyhow::Error) {
+        if self.quarantine(remote_url).await {
+            alert!(
+                "Quarantined api endpoint: {} for {}s, as it served invalid data: {}",
+                remote_url,
+                self.quarantine_duration.as_secs(),
+                error
+            )
+            .await
+            .ok();
+        } else {
+            error!(
+                "Rejected invalid data from api endpoint: {}, error: {}",
+                remote_url, error
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bee_message::{
+        parents::Parents,
+        payload::milestone::{
+            MilestonePayload,
+            MilestonePayloadEssence,
+            MILESTONE_MERKLE_PROOF_LENGTH,
+        },
+        MessageBuilder,
+    };
+    use bee_pow::providers::{
+        constant::Constant,
+        miner::Miner,
+    };
+
+    fn requester(min_pow_score: Option<u64>) -> Requester {
+        let mut config = chronicle_common::config::Config::default();
+        config.broker_config.min_pow_score = min_pow_score;
+        let (_, rx) = tokio::sync::mpsc::unbounded_channel();
+        RequesterBuilder::new()
+            .requester_id(0)
+            .inbox(RequesterInbox { rx })
+            .api_endpoints(VecDeque::new())
+            .reqwest_client(Client::new())
+            .build_with(&config)
+    }
+
+    fn parents() -> Parents {
+        Parents::new(vec![MessageId::new([1; 32]), MessageId::new([2; 32])]).unwrap()
+    }
+
+    fn metadata_of(message: &Message, ledger_inclusion_state: Option<LedgerInclusionState>) -> MessageMetadata {
+        MessageMetadata {
+            message_id: message.id().0,
+            parent_message_ids: message.parents().iter().copied().collect(),
+            is_solid: true,
+            referenced_by_milestone_index: Some(10),
+            ledger_inclusion_state,
+            should_promote: None,
+            should_reattach: None,
+        }
+    }
+
+    fn mined_message() -> Message {
+        MessageBuilder::<Miner>::new()
+            .with_network_id(0)
+            .with_parents(parents())
+            .finish()
+            .unwrap()
+    }
+
+    #[test]
+    fn valid_message() {
+        let requester = requester(Some(4000));
+        let message = mined_message();
+        let metadata = metadata_of(&message, Some(LedgerInclusionState::NoTransaction));
+        assert!(requester
+            .validate_full_message(message.id().0, 10, &message, &metadata)
+            .is_ok());
+        // the message can't be referenced by a milestone newer than the solidified one
+        assert!(requester
+            .validate_full_message(message.id().0, 9, &message, &metadata)
+            .is_err());
+    }
+
+    #[test]
+    fn hash_mismatch() {
+        let requester = requester(Some(4000));
+        let message = mined_message();
+        let mut metadata = metadata_of(&message, Some(LedgerInclusionState::NoTransaction));
+        let message_id = MessageId::new([3; 32]);
+        metadata.message_id = message_id;
+        assert!(requester
+            .validate_full_message(message_id, 10, &message, &metadata)
+            .is_err());
+    }
+
+    #[test]
+    fn parent_mismatch() {
+        let requester = requester(Some(4000));
+        let message = mined_message();
+        let mut metadata = metadata_of(&message, Some(LedgerInclusionState::NoTransaction));
+        metadata.parent_message_ids = vec![MessageId::new([1; 32]), MessageId::new([3; 32])];
+        assert!(requester
+            .validate_full_message(message.id().0, 10, &message, &metadata)
+            .is_err());
+    }
+
+    #[test]
+    fn implausible_inclusion_state() {
+        let requester = requester(Some(4000));
+        let message = mined_message();
+        for ledger_inclusion_state in vec![
+            Some(LedgerInclusionState::Included),
+            Some(LedgerInclusionState::Conflicting),
+            None,
+        ] {
+            let metadata = metadata_of(&message, ledger_inclusion_state);
+            assert!(requester
+                .validate_full_message(message.id().0, 10, &message, &metadata)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn low_pow_score() {
+        let message = MessageBuilder::<Constant>::new()
+            .with_network_id(0)
+            .with_parents(parents())
+            .finish()
+            .unwrap();
+        let metadata = metadata_of(&message, Some(LedgerInclusionState::NoTransaction));
+        assert!(requester(Some(4000))
+            .validate_full_message(message.id().0, 10, &message, &metadata)
+            .is_err());
+        // the PoW score is only checked when the network minimum is configured
+        assert!(requester(None)
+            .validate_full_message(message.id().0, 10, &message, &metadata)
+            .is_ok());
+    }
+
+    #[test]
+    fn milestone_exempt_from_pow() {
+        let milestone_message = |index| {
+            let essence = MilestonePayloadEssence::new(
+                MilestoneIndex(index),
+                0,
+                parents(),
+                [0; MILESTONE_MERKLE_PROOF_LENGTH],
+                0,
+                0,
+                vec![[0; 32]],
+                None,
+            )
+            .unwrap();
+            let milestone = MilestonePayload::new(essence, vec![vec![0; 64].into_boxed_slice()]).unwrap();
+            MessageBuilder::<Constant>::new()
+                .with_network_id(0)
+                .with_parents(parents())
+                .with_payload(Payload::Milestone(Box::new(milestone)))
+                .finish()
+                .unwrap()
+        };
+        let requester = requester(Some(4000));
+        let message = milestone_message(10);
+        let metadata = metadata_of(&message, Some(LedgerInclusionState::NoTransaction));
+        assert!(requester
+            .validate_full_message(message.id().0, 10, &message, &metadata)
+            .is_ok());
+        // a milestone must be referenced by itself
+        let message = milestone_message(9);
+        let metadata = metadata_of(&message, Some(LedgerInclusionState::NoTransaction));
+        assert!(requester
+            .validate_full_message(message.id().0, 10, &message, &metadata)
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn solidified_milestone_exists() {
+        let requester = requester(Some(4000));
+        let remote_url = Url::parse("http://127.0.0.1:1/api/v1/").unwrap();
+        assert!(requester.verify_referenced_milestone(&remote_url, 10, 10).await.is_ok());
+    }
+}
@@ -28,6 +28,12 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Solidifier {
                         error!("{}", e);
                     });
                 }
+                SolidifierEvent::MilestoneCone(milestone_index, full_messages) => {
+                    self.handle_milestone_cone(milestone_index, full_messages)
+                        .unwrap_or_else(|e| {
+                            error!("{}", e);
+                        });
+                }
                 SolidifierEvent::CqlResult(result) => {
                     match result {
                         Ok(cql_result) => {
@@ -159,7 +165,9 @@ impl Solidifier {
                         "Solidifier id: {}, failed to solidify syncer requested index: {} milestone data",
                         self.partition_id, milestone_index
                     );
-                    let _ = self.syncer_handle.send(SyncerEvent::Unreachable(milestone_index));
+                    let _ = self
+                        .syncer_handle
+                        .send(SyncerEvent::Unreachable(milestone_index, UnreachableReason::NoPeers));
                 } else {
                     // there is a glitch in the new incoming data, however the archiver and syncer will take care of
                     // that.
@@ -179,7 +187,10 @@ impl Solidifier {
                 self.partition_id, milestone_index, self.expected
             );
             // tell syncer to skip this atm
-            let _ = self.syncer_handle.send(SyncerEvent::Unreachable(milestone_index));
+            let _ = self.syncer_handle.send(SyncerEvent::Unreachable(
+                milestone_index,
+                UnreachableReason::AheadOfExpected,
+            ));
             return ();
         }
         // remove it from unreachable (if we already tried to solidify it before)
@@ -208,7 +219,9 @@ impl Solidifier {
                     .or_insert_with(|| InDatabase::new(milestone_index));
             } else {
                 // tell syncer to skip this atm
-                let _ = self.syncer_handle.send(SyncerEvent::Unreachable(milestone_index));
+                let _ = self
+                    .syncer_handle
+                    .send(SyncerEvent::Unreachable(milestone_index, UnreachableReason::Conflict));
             }
         } else {
             // Asking any collector (as we don't know the message id of the milestone)
@@ -386,6 +399,52 @@ impl Solidifier {
         }
         Ok(())
     }
+    fn handle_milestone_cone(&mut self, milestone_index: u32, full_messages: Vec<FullMessage>) -> anyhow::Result<()> {
+        let partitioner = &self.message_id_partitioner;
+        let collectors_handles = &self.collector_handles;
+        let solidifier_id = self.partition_id;
+        if let Some(milestone_data) = self.milestones_data.get_mut(&milestone_index) {
+            info!(
+                "solidifier_id: {}, got the cone of milestone {}, with {} messages",
+                solidifier_id,
+                milestone_index,
+                full_messages.len()
+            );
+            // add the whole cone first, so only the parents outside of the cone get requested
+            let mut parents = Vec::new();
+            for full_message in full_messages {
+                if let Some(bee_message::payload::Payload::Milestone(milestone_payload)) = full_message.0.payload() {
+                    if milestone_payload.essence().index().0 == milestone_index {
+                        milestone_data.set_milestone(milestone_payload.clone());
+                    }
+                }
+                parents.extend_from_slice(&full_message.metadata().parent_message_ids);
+                milestone_data.remove_from_pending(full_message.message_id());
+                milestone_data.add_full_message(full_message);
+            }
+            Self::process_parents(
+                &parents,
+                milestone_data,
+                collectors_handles,
+                partitioner,
+                solidifier_id,
+                milestone_index,
+            );
+            let check_if_completed = milestone_data.check_if_completed();
+            let created_by = milestone_data.created_by;
+            if check_if_completed && !created_by.eq(&CreatedBy::Syncer) {
+                self.push_to_logger(milestone_index)?;
+            } else if check_if_completed {
+                self.push_to_syncer(milestone_index)?;
+            };
+        } else {
+            warn!(
+                "solidifier_id: {}, got the cone of milestone {}, without milestone data",
+                solidifier_id, milestone_index
+            );
+        }
+        Ok(())
+    }
     fn process_parents(
         parents: &[MessageId],
         milestone_data: &mut MilestoneData,
@@ -398,41 +457,47 @@ impl Solidifier {
         // Note: Some or all parents might belong to older milestone,
         // and it's the job of the collector to tell us when to close message_id
         // and remove it from pending
+        let mut missing_parents: HashMap<u8, Vec<MessageId>> = HashMap::new();
         parents.iter().for_each(|parent_id| {
             let in_messages = milestone_data.messages().contains_key(&parent_id);
             let in_pending = milestone_data.pending().contains_key(&parent_id);
             let genesis = parent_id.eq(&MessageId::null());
             // Check if parent NOT in messages nor pending
             if !in_messages && !in_pending && !genesis {
-                // Request it from collector
-                Self::request_full_message(
-                    collectors_handles,
-                    partitioner,
-                    solidifier_id,
-                    milestone_index,
-                    *parent_id,
-                    *milestone_data.created_by(),
-                );
+                // Batch it per collector
+                missing_parents
+                    .entry(partitioner.partition_id(parent_id))
+                    .or_default()
+                    .push(*parent_id);
                 // Add it to pending
                 milestone_data.pending.insert(*parent_id, ());
             };
         });
+        // Request them from the collectors
+        for (collector_id, parent_ids) in missing_parents {
+            Self::request_full_messages(
+                collectors_handles,
+                collector_id,
+                solidifier_id,
+                milestone_index,
+                parent_ids,
+                *milestone_data.created_by(),
+            );
+        }
     }
-    fn request_full_message(
+    fn request_full_messages(
         collectors_handles: &HashMap<u8, CollectorHandle>,
-        partitioner: &MessageIdPartitioner,
+        collector_id: u8,
         solidifier_id: u8,
         milestone_index: u32,
-        parent_id: MessageId,
+        parent_ids: Vec<MessageId>,
         created_by: CreatedBy,
     ) {
-        // Request it from collector
-        let collector_id = partitioner.partition_id(&parent_id);
         if let Some(collector_handle) = collectors_handles.get(&collector_id) {
-            let ask_event = CollectorEvent::Ask(AskCollector::FullMessage(
+            let ask_event = CollectorEvent::Ask(AskCollector::FullMessages(
                 solidifier_id,
                 milestone_index,
-                parent_id,
+                parent_ids,
                 created_by,
             ));
             let _ = collector_handle.send(ask_event);
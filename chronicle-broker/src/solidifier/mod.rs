@@ -106,6 +106,8 @@ pub enum SolidifierEvent {
     Milestone(MilestoneMessage),
     /// Pushed or requested messages, that definitely belong to self solidifier
     Message(FullMessage),
+    /// Requested milestone cone, which includes all the messages referenced by the u32 milestone index
+    MilestoneCone(u32, Vec<FullMessage>),
     /// Close MessageId that doesn't belong at all to Solidifier of milestone u32
     Close(MessageId, u32),
     /// Solidifiy request from Syncer.
@@ -0,0 +1,29 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+
+/// Select a single value from the keyspace, returns None if it's not stored
+pub(crate) async fn query<K, V>(
+    keyspace: &ChronicleKeyspace,
+    key: K,
+    retries_per_query: usize,
+) -> anyhow::Result<Option<V>>
+where
+    ChronicleKeyspace: Select<K, V>,
+    K: 'static + Send + Clone,
+    V: 'static + Send + Clone,
+{
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    keyspace
+        .select::<V>(&key)
+        .consistency(Consistency::One)
+        .build()?
+        .send_local(ValueWorker::boxed(
+            tx,
+            keyspace.clone(),
+            key,
+            retries_per_query,
+            std::marker::PhantomData,
+        ));
+    Ok(rx.recv().await.ok_or_else(|| anyhow!("No response from scylla!"))??)
+}
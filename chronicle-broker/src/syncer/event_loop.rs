@@ -18,7 +18,7 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Syncer {
             match event {
                 SyncerEvent::Ask(ask) => {
                     // Don't accept ask events when there is something already in progress.
-                    if let None = self.active {
+                    if self.active.is_none() && self.claiming.is_none() {
                         match ask {
                             AskSyncer::Complete => {
                                 if !self.highest.eq(&0) {
@@ -39,28 +39,64 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Syncer {
                                 self.update_sync().await;
                             }
                         }
+                    } else if let AskSyncer::UpdateSyncData = ask {
+                        // the syncer is busy (i.e retrying an unreachable milestone), so we try to update it later
+                        self.schedule_update_sync_data();
                     } else {
                         error!(
                             "Cannot accept Ask request: {:?}, while processing: {:?}",
-                            &ask, self.active
+                            &ask,
+                            self.active.as_ref().or(self.claiming.as_ref())
                         );
                     }
                 }
                 SyncerEvent::MilestoneData(milestone_data) => {
                     self.handle_milestone_data(milestone_data).await;
                 }
-                SyncerEvent::Unreachable(milestone_index) => {
+                SyncerEvent::Unreachable(milestone_index, reason) => {
                     self.pending -= 1;
+                    self.in_flight.remove(&milestone_index);
                     // This happens when all the peers don't have the requested milestone_index
                     alert!(
-                        "Chronicle syncer is unable to reach milestone index {} because no peers were able to provide it!",
-                        milestone_index
-                    ).await.ok();
+                        "Chronicle syncer is unable to reach milestone index {} because {}!",
+                        milestone_index,
+                        reason
+                    )
+                    .await
+                    .ok();
+                    self.record_unreachable(milestone_index, reason);
                     self.handle_skip();
                     self.trigger_process_more();
                 }
+                SyncerEvent::RetryUnreachable => {
+                    self.retry_unreachable();
+                    self.schedule_retry_unreachable();
+                }
+                SyncerEvent::Requeue(milestone_indexes) => {
+                    self.requeue_unreachable(milestone_indexes);
+                    self.retry_unreachable();
+                }
+                SyncerEvent::UpdateSyncRange(sync_range) => {
+                    info!("Updating the sync range to {:?}", sync_range);
+                    self.sync_range.replace(sync_range);
+                    // the current sync data gets refetched with the new range once it reached EOF
+                    if self.active.is_none() && self.claiming.is_none() {
+                        self.update_sync().await;
+                    }
+                }
+                SyncerEvent::Claimed(bucket, lease) => {
+                    self.handle_claimed(bucket, lease);
+                }
+                SyncerEvent::RenewLease => {
+                    self.renew_lease();
+                    self.schedule_renew_lease();
+                }
+                SyncerEvent::Renewed(bucket, lease) => {
+                    self.handle_renewed(bucket, lease);
+                }
                 SyncerEvent::Shutdown => break,
             }
+            self.publish_status().await;
         }
         Ok(())
     }
@@ -85,6 +121,10 @@ impl Syncer {
 
     pub(crate) async fn handle_milestone_data(&mut self, milestone_data: MilestoneData) {
         self.pending -= 1;
+        if self.in_flight.remove(&milestone_data.milestone_index()) {
+            self.synced += 1;
+        }
+        self.recover_unreachable(milestone_data.milestone_index());
         self.milestones_data.push(Ascending::new(milestone_data));
         if self.highest.eq(&0) && self.pending.eq(&0) {
             // these are the first milestones data, which we didn't even request it.
@@ -164,6 +204,11 @@ impl Syncer {
                     // we just consume the range in order for the trigger_process_more to move further
                     while let Some(_) = range.next() {}
                 }
+                Active::Retry(ref mut range) => {
+                    error!("Retry: Skipping the remaining gap range: {:?}", range);
+                    // we just consume the range in order for the trigger_process_more to move further
+                    while let Some(_) = range.next() {}
+                }
             };
             // reset skip back to false
             self.skip = false;
@@ -203,13 +248,34 @@ impl Syncer {
         match self.active.as_mut().unwrap() {
             Active::Complete(ref mut range) => {
                 if let Some(milestone_index) = range.next() {
-                    Self::request_solidify(self.solidifier_count, &self.solidifier_handles, milestone_index);
+                    Self::request_solidify(
+                        self.solidifier_count,
+                        &self.solidifier_handles,
+                        &mut self.in_flight,
+                        milestone_index,
+                    );
                     self.pending += 1;
                 }
             }
             Active::FillGaps(ref mut range) => {
                 if let Some(milestone_index) = range.next() {
-                    Self::request_solidify(self.solidifier_count, &self.solidifier_handles, milestone_index);
+                    Self::request_solidify(
+                        self.solidifier_count,
+                        &self.solidifier_handles,
+                        &mut self.in_flight,
+                        milestone_index,
+                    );
+                    self.pending += 1;
+                }
+            }
+            Active::Retry(ref mut range) => {
+                if let Some(milestone_index) = range.next() {
+                    Self::request_solidify(
+                        self.solidifier_count,
+                        &self.solidifier_handles,
+                        &mut self.in_flight,
+                        milestone_index,
+                    );
                     self.pending += 1;
                 }
             }
@@ -221,7 +287,12 @@ impl Syncer {
                 Active::Complete(range) => {
                     for _ in 0..self.parallelism {
                         if let Some(milestone_index) = range.next() {
-                            Self::request_solidify(self.solidifier_count, &self.solidifier_handles, milestone_index);
+                            Self::request_solidify(
+                                self.solidifier_count,
+                                &self.solidifier_handles,
+                                &mut self.in_flight,
+                                milestone_index,
+                            );
                             // update pending
                             self.pending += 1;
                         } else {
@@ -240,7 +311,12 @@ impl Syncer {
                 Active::FillGaps(range) => {
                     for _ in 0..self.parallelism {
                         if let Some(milestone_index) = range.next() {
-                            Self::request_solidify(self.solidifier_count, &self.solidifier_handles, milestone_index);
+                            Self::request_solidify(
+                                self.solidifier_count,
+                                &self.solidifier_handles,
+                                &mut self.in_flight,
+                                milestone_index,
+                            );
                             // update pending
                             self.pending += 1;
                         } else {
@@ -256,6 +332,30 @@ impl Syncer {
                         }
                     }
                 }
+                Active::Retry(range) => {
+                    for _ in 0..self.parallelism {
+                        if let Some(milestone_index) = range.next() {
+                            Self::request_solidify(
+                                self.solidifier_count,
+                                &self.solidifier_handles,
+                                &mut self.in_flight,
+                                milestone_index,
+                            );
+                            // update pending
+                            self.pending += 1;
+                        } else {
+                            // move to the next due unreachable milestone (only if pending is zero)
+                            if self.pending.eq(&0) {
+                                // We should close any part file related to the retried milestone
+                                self.close_log_file();
+                                // Finished the current active range, therefore we drop it
+                                self.active.take();
+                                self.retry_unreachable();
+                            }
+                            break;
+                        }
+                    }
+                }
             }
         } else {
             self.eof = true;
@@ -277,16 +377,18 @@ impl Syncer {
     fn request_solidify(
         solidifier_count: u8,
         solidifier_handles: &HashMap<u8, SolidifierHandle>,
+        in_flight: &mut BTreeSet<u32>,
         milestone_index: u32,
     ) {
+        in_flight.insert(milestone_index);
         let solidifier_id = (milestone_index % (solidifier_count as u32)) as u8;
         let solidifier_handle = solidifier_handles.get(&solidifier_id).unwrap();
         let solidify_event = SolidifierEvent::Solidify(Ok(milestone_index));
         let _ = solidifier_handle.send(solidify_event);
     }
-    fn trigger_process_more(&mut self) {
-        // move to next range (only if pending is zero)
-        if self.pending.eq(&0) {
+    pub(crate) fn trigger_process_more(&mut self) {
+        // move to next range (only if pending is zero and no sync lease is being claimed)
+        if self.pending.eq(&0) && self.claiming.is_none() {
             // start processing it
             self.process_more();
         }
@@ -301,8 +403,7 @@ impl Syncer {
                 self.next = gap.start;
                 self.initial_gap_start = self.next;
                 self.initial_gap_end = gap.end;
-                self.active.replace(Active::Complete(gap));
-                self.trigger_process_more();
+                self.activate(Active::Complete(gap));
             } else {
                 // fill this with the gap.start up to self.highest
                 // this is the last gap in our sync data
@@ -315,8 +416,7 @@ impl Syncer {
                     gap.end = self.highest;
                     self.initial_gap_end = gap.end;
                     info!("Completing the last gap {:?}", gap);
-                    self.active.replace(Active::Complete(gap));
-                    self.trigger_process_more();
+                    self.activate(Active::Complete(gap));
                 } else {
                     info!("There are no more gaps neither unlogged in the current sync data");
                     self.trigger_process_more();
@@ -337,8 +437,7 @@ impl Syncer {
                 self.next = gap.start;
                 self.initial_gap_start = self.next;
                 self.initial_gap_end = gap.end;
-                self.active.replace(Active::FillGaps(gap));
-                self.trigger_process_more();
+                self.activate(Active::FillGaps(gap));
             } else {
                 // fill this with the gap.start up to self.highest
                 // this is the last gap in our sync data
@@ -351,8 +450,7 @@ impl Syncer {
                     // update the end of the gap
                     gap.end = self.highest;
                     self.initial_gap_end = gap.end;
-                    self.active.replace(Active::FillGaps(gap));
-                    self.trigger_process_more();
+                    self.activate(Active::FillGaps(gap));
                 } else {
                     info!("There are no more gaps in the current sync data");
                     self.trigger_process_more();
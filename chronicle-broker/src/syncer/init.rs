@@ -8,6 +8,9 @@ impl<H: ChronicleBrokerScope> Init<BrokerHandle<H>> for Syncer {
     async fn init(&mut self, status: Result<(), Need>, _supervisor: &mut Option<BrokerHandle<H>>) -> Result<(), Need> {
         info!("Syncer is initializing with parallelism: {}", self.parallelism);
         self.service.update_status(ServiceStatus::Initializing);
+        self.load_unreachable().await;
+        self.schedule_retry_unreachable();
+        self.schedule_renew_lease();
         let event = BrokerEvent::Children(BrokerChild::Syncer(self.service.clone(), Ok(())));
         let _ = _supervisor.as_mut().expect("Syncer expected BrokerHandle").send(event);
         status
@@ -0,0 +1,301 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! The sync leases let the chronicle instances which share a keyspace sync disjoint milestone ranges.
+//! The archiver isn't coordinated separately: the unlogged ranges are archived by the syncer in `Complete` mode,
+//! so they're already covered by the bucket lease, while the live milestones are archived by every instance
+//! which collects them, as the LogFiles are local to each instance and `logged_by` records which one logged them.
+
+use super::*;
+use crate::storage::query;
+
+impl Syncer {
+    /// Activate the task, once the sync lease of its milestone range got claimed (if the sync leases are enabled)
+    pub(crate) fn activate(&mut self, mut active: Active) {
+        self.started.get_or_insert_with(Instant::now);
+        let lease_duration = match self.lease_duration {
+            Some(lease_duration) => lease_duration,
+            None => {
+                self.active.replace(active);
+                self.trigger_process_more();
+                return;
+            }
+        };
+        let range = match active {
+            Active::Complete(ref mut range) | Active::FillGaps(ref mut range) | Active::Retry(ref mut range) => range,
+        };
+        let bucket = range.start / self.lease_range;
+        let bucket_end = (bucket + 1).saturating_mul(self.lease_range);
+        if range.end > bucket_end {
+            // the rest of the range belongs to the next buckets, which get claimed once we reach them
+            self.sync_data.gaps.push(bucket_end..range.end);
+            range.end = bucket_end;
+            self.initial_gap_end = bucket_end;
+        }
+        // no need to claim the bucket again if we still hold its lease
+        if let Some((held_bucket, lease)) = self.lease {
+            if held_bucket == bucket && lease.expires_at > Self::unix_timestamp() + lease_duration.as_secs() / 2 {
+                self.active.replace(active);
+                self.trigger_process_more();
+                return;
+            }
+        }
+        info!("Claiming the sync lease of bucket: {}, for: {:?}", bucket, active);
+        self.claiming.replace(active);
+        let keyspace = self.keyspace.clone();
+        let handle = self.handle.clone();
+        let (owner, instance) = (self.chronicle_id, self.instance);
+        let retries_per_query = self.retries_per_query;
+        let claim_lease = async move {
+            let store = KeyspaceLeases {
+                keyspace: &keyspace,
+                retries_per_query,
+            };
+            let now = Self::unix_timestamp();
+            let expires_at = now + lease_duration.as_secs();
+            let lease = match Self::claim_lease(&store, bucket, owner, instance, now, expires_at).await {
+                Ok(lease) => lease,
+                Err(e) => {
+                    warn!("Unable to claim the sync lease of bucket: {}, error: {}", bucket, e);
+                    None
+                }
+            };
+            let _ = handle.send(SyncerEvent::Claimed(bucket, lease));
+        };
+        tokio::spawn(claim_lease);
+    }
+    /// Handle the outcome of the sync lease claim
+    pub(crate) fn handle_claimed(&mut self, bucket: u32, lease: Option<LeaseRecord>) {
+        if let Some(active) = self.claiming.take() {
+            if let Some(lease) = lease {
+                info!(
+                    "Claimed the sync lease of bucket: {}, until: {}",
+                    bucket, lease.expires_at
+                );
+                self.lease.replace((bucket, lease));
+                self.active.replace(active);
+                self.trigger_process_more();
+            } else {
+                // the bucket is being synced by another chronicle, it will be revisited by the next sync data update
+                info!(
+                    "The sync lease of bucket: {} is held by another chronicle, skipping: {:?}",
+                    bucket, active
+                );
+                match active {
+                    Active::Complete(_) => self.complete(),
+                    Active::FillGaps(_) => self.fill_gaps(),
+                    // the unreachable milestone will be retried by the next retry
+                    Active::Retry(_) => (),
+                }
+            }
+        }
+    }
+    /// Renew the held sync lease, as long as the syncer is active within its bucket
+    pub(crate) fn renew_lease(&mut self) {
+        let lease_duration = match self.lease_duration {
+            Some(lease_duration) => lease_duration,
+            None => return,
+        };
+        if let Some((bucket, lease)) = self.lease {
+            if self.active.is_none() {
+                // the syncer is idle, so we let the lease expire
+                self.lease.take();
+                return;
+            }
+            let keyspace = self.keyspace.clone();
+            let handle = self.handle.clone();
+            let retries_per_query = self.retries_per_query;
+            let renew_lease = async move {
+                let store = KeyspaceLeases {
+                    keyspace: &keyspace,
+                    retries_per_query,
+                };
+                let expires_at = Self::unix_timestamp() + lease_duration.as_secs();
+                let renewed = match Self::swap_lease(&store, bucket, lease, expires_at).await {
+                    Ok(renewed) => renewed,
+                    Err(e) => {
+                        warn!("Unable to renew the sync lease of bucket: {}, error: {}", bucket, e);
+                        None
+                    }
+                };
+                let _ = handle.send(SyncerEvent::Renewed(bucket, renewed));
+            };
+            tokio::spawn(renew_lease);
+        }
+    }
+    /// Handle the outcome of the sync lease renewal
+    pub(crate) fn handle_renewed(&mut self, bucket: u32, lease: Option<LeaseRecord>) {
+        match self.lease {
+            Some((held_bucket, _)) if held_bucket == bucket => {
+                if let Some(lease) = lease {
+                    self.lease.replace((bucket, lease));
+                } else {
+                    error!(
+                        "Lost the sync lease of bucket: {}, skipping the remaining range: {:?}",
+                        bucket, self.active
+                    );
+                    self.lease.take();
+                    if self.active.is_some() {
+                        self.handle_skip();
+                        self.trigger_process_more();
+                    }
+                }
+            }
+            // the lease of a previous bucket
+            _ => (),
+        }
+    }
+    pub(crate) fn schedule_renew_lease(&self) {
+        if let Some(lease_duration) = self.lease_duration {
+            let handle = self.handle.clone();
+            let renew_lease = async move {
+                tokio::time::sleep(lease_duration / 3).await;
+                let _ = handle.send(SyncerEvent::RenewLease);
+            };
+            tokio::spawn(renew_lease);
+        }
+    }
+    /// Claim the sync lease of the bucket until `expires_at`, or take it over if it's expired by `now`
+    async fn claim_lease<S: LeaseStore>(
+        store: &S,
+        bucket: u32,
+        owner: u8,
+        instance: u64,
+        now: u64,
+        expires_at: u64,
+    ) -> anyhow::Result<Option<LeaseRecord>> {
+        let lease = LeaseRecord::new(owner, instance, expires_at);
+        if store.claim(LeaseClaim { bucket, lease }).await? {
+            return Ok(Some(lease));
+        }
+        match store.get(LeaseKey { bucket }).await? {
+            // only this very instance can retake its own lease before it expires
+            Some(current) if (current.owner == owner && current.instance == instance) || current.expires_at <= now => {
+                if current.owner != owner || current.instance != instance {
+                    info!(
+                        "Taking over the expired sync lease of bucket: {}, from chronicle: {}",
+                        bucket, current.owner
+                    );
+                }
+                Self::swap_lease(store, bucket, current, expires_at)
+                    .await
+                    .map(|swapped| swapped.map(|_| lease))
+            }
+            _ => Ok(None),
+        }
+    }
+    /// Extend the current lease of the bucket until `expires_at`, only if it's still the current one
+    async fn swap_lease<S: LeaseStore>(
+        store: &S,
+        bucket: u32,
+        current: LeaseRecord,
+        expires_at: u64,
+    ) -> anyhow::Result<Option<LeaseRecord>> {
+        let new = LeaseRecord::new(current.owner, current.instance, expires_at);
+        let swapped = store.swap(LeaseSwap { bucket, current, new }).await?;
+        Ok(if swapped { Some(new) } else { None })
+    }
+}
+
+/// The lightweight transactions of the 'leases' table
+#[async_trait::async_trait]
+trait LeaseStore {
+    /// Insert the lease if the bucket was never claimed, returns whether it got applied
+    async fn claim(&self, claim: LeaseClaim) -> anyhow::Result<bool>;
+    /// Get the current lease of the bucket
+    async fn get(&self, key: LeaseKey) -> anyhow::Result<Option<LeaseRecord>>;
+    /// Swap the lease if it's still the current one, returns whether it got applied
+    async fn swap(&self, swap: LeaseSwap) -> anyhow::Result<bool>;
+}
+
+struct KeyspaceLeases<'a> {
+    keyspace: &'a ChronicleKeyspace,
+    retries_per_query: usize,
+}
+
+#[async_trait::async_trait]
+impl LeaseStore for KeyspaceLeases<'_> {
+    async fn claim(&self, claim: LeaseClaim) -> anyhow::Result<bool> {
+        let applied = query::<LeaseClaim, Applied>(self.keyspace, claim, self.retries_per_query).await?;
+        Ok(matches!(applied, Some(Applied(true))))
+    }
+    async fn get(&self, key: LeaseKey) -> anyhow::Result<Option<LeaseRecord>> {
+        query::<LeaseKey, LeaseRecord>(self.keyspace, key, self.retries_per_query).await
+    }
+    async fn swap(&self, swap: LeaseSwap) -> anyhow::Result<bool> {
+        let applied = query::<LeaseSwap, Applied>(self.keyspace, swap, self.retries_per_query).await?;
+        Ok(matches!(applied, Some(Applied(true))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// An in memory 'leases' table, which applies the conditions of the lightweight transactions
+    #[derive(Default)]
+    struct MemoryLeases(Mutex<HashMap<u32, LeaseRecord>>);
+
+    #[async_trait::async_trait]
+    impl LeaseStore for MemoryLeases {
+        // INSERT ... IF NOT EXISTS
+        async fn claim(&self, LeaseClaim { bucket, lease }: LeaseClaim) -> anyhow::Result<bool> {
+            let mut leases = self.0.lock().unwrap();
+            if leases.contains_key(&bucket) {
+                return Ok(false);
+            }
+            leases.insert(bucket, lease);
+            Ok(true)
+        }
+        async fn get(&self, LeaseKey { bucket }: LeaseKey) -> anyhow::Result<Option<LeaseRecord>> {
+            Ok(self.0.lock().unwrap().get(&bucket).copied())
+        }
+        // UPDATE ... IF owner = ? AND instance = ? AND expires_at = ?
+        async fn swap(&self, LeaseSwap { bucket, current, new }: LeaseSwap) -> anyhow::Result<bool> {
+            let mut leases = self.0.lock().unwrap();
+            match leases.get_mut(&bucket) {
+                Some(lease) if *lease == current => {
+                    *lease = new;
+                    Ok(true)
+                }
+                _ => Ok(false),
+            }
+        }
+    }
+
+    #[test]
+    fn lease_statements_are_conditional() {
+        let keyspace = ChronicleKeyspace::new("permanode".to_owned());
+        let claim = Select::<LeaseClaim, Applied>::statement(&keyspace);
+        assert!(claim.ends_with("IF NOT EXISTS"));
+        let swap = Select::<LeaseSwap, Applied>::statement(&keyspace);
+        assert!(swap.ends_with("IF owner = ? AND instance = ? AND expires_at = ?"));
+    }
+
+    #[tokio::test]
+    async fn claim_renew_takeover() {
+        let store = MemoryLeases::default();
+        // the first instance claims the bucket
+        let first = Syncer::claim_lease(&store, 1, 0, 1, 0, 100).await.unwrap().unwrap();
+        assert_eq!(first, LeaseRecord::new(0, 1, 100));
+        // another instance, even with the same owner id, can't claim it before it expires
+        assert_eq!(Syncer::claim_lease(&store, 1, 1, 2, 50, 150).await.unwrap(), None);
+        assert_eq!(Syncer::claim_lease(&store, 1, 0, 2, 50, 150).await.unwrap(), None);
+        // while the holder retakes it, and claims the other buckets
+        let first = Syncer::claim_lease(&store, 1, 0, 1, 50, 150).await.unwrap().unwrap();
+        assert_eq!(first, LeaseRecord::new(0, 1, 150));
+        assert!(Syncer::claim_lease(&store, 2, 0, 1, 50, 150).await.unwrap().is_some());
+        // the holder renews its lease
+        let first = Syncer::swap_lease(&store, 1, first, 200).await.unwrap().unwrap();
+        assert_eq!(first.expires_at, 200);
+        assert_eq!(Syncer::claim_lease(&store, 1, 1, 2, 150, 250).await.unwrap(), None);
+        // another instance takes the lease over once it's expired
+        let second = Syncer::claim_lease(&store, 1, 1, 2, 200, 300).await.unwrap().unwrap();
+        assert_eq!(second, LeaseRecord::new(1, 2, 300));
+        assert_eq!(store.get(LeaseKey { bucket: 1 }).await.unwrap(), Some(second));
+        // so the previous holder can't renew it anymore
+        assert_eq!(Syncer::swap_lease(&store, 1, first, 400).await.unwrap(), None);
+        assert_eq!(store.get(LeaseKey { bucket: 1 }).await.unwrap(), Some(second));
+    }
+}
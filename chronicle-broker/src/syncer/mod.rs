@@ -14,21 +14,35 @@ use super::{
 use chronicle_common::Wrapper;
 use chronicle_storage::keyspaces::ChronicleKeyspace;
 use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
     ops::{
         Deref,
         DerefMut,
     },
-    time::Duration,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 use tokio::sync::oneshot::Sender;
 mod event_loop;
 mod init;
+mod lease;
+mod status;
 mod terminating;
+mod unreachable;
 
 // Syncer builder
 builder!(SyncerBuilder {
     sync_data: SyncData,
     update_sync_data_every: Duration,
+    retry_backoff: Duration,
+    chronicle_id: u8,
+    lease_duration: Duration,
+    lease_range: u32,
     sync_range: SyncRange,
     solidifier_handles: HashMap<u8, SolidifierHandle>,
     parallelism: u8,
@@ -46,7 +60,19 @@ pub enum SyncerEvent {
     /// Sync milestone data
     MilestoneData(MilestoneData),
     /// Notify of an unreachable cluster
-    Unreachable(u32),
+    Unreachable(u32, UnreachableReason),
+    /// Retry the unreachable milestones which are due
+    RetryUnreachable,
+    /// Re-queue the given unreachable milestones for an immediate retry, or all of them if None
+    Requeue(Option<Vec<u32>>),
+    /// Update the range of milestones to sync
+    UpdateSyncRange(SyncRange),
+    /// The outcome of the sync lease claim of the given bucket
+    Claimed(u32, Option<LeaseRecord>),
+    /// Renew the held sync lease
+    RenewLease,
+    /// The outcome of the sync lease renewal of the given bucket
+    Renewed(u32, Option<LeaseRecord>),
     /// Shutdown the syncer
     Shutdown,
 }
@@ -118,6 +144,14 @@ pub struct Syncer {
     service: Service,
     sync_data: SyncData,
     update_sync_data_every: Duration,
+    retry_backoff: Duration,
+    retries_per_query: usize,
+    chronicle_id: u8,
+    instance: u64,
+    lease_duration: Option<Duration>,
+    lease_range: u32,
+    lease: Option<(u32, LeaseRecord)>,
+    claiming: Option<Active>,
     keyspace: ChronicleKeyspace,
     sync_range: Option<SyncRange>,
     solidifier_handles: HashMap<u8, SolidifierHandle>,
@@ -135,6 +169,10 @@ pub struct Syncer {
     initial_gap_start: u32,
     initial_gap_end: u32,
     prev_closed_log_filename: u32,
+    in_flight: BTreeSet<u32>,
+    unreachable: BTreeMap<u32, UnreachableMilestone>,
+    synced: u64,
+    started: Option<Instant>,
     oneshot: Option<Sender<u32>>,
     handle: SyncerHandle,
     inbox: SyncerInbox,
@@ -146,10 +184,16 @@ impl<H: ChronicleBrokerScope> ActorBuilder<BrokerHandle<H>> for SyncerBuilder {}
 impl Builder for SyncerBuilder {
     type State = Syncer;
     fn build(self) -> Self::State {
+        self.build_with(&chronicle_common::get_config())
+    }
+}
+
+impl SyncerBuilder {
+    /// Build the syncer with the given config
+    pub(crate) fn build_with(self, config: &chronicle_common::config::Config) -> Syncer {
         let solidifier_handles = self.solidifier_handles.unwrap();
         let solidifier_count = solidifier_handles.len() as u8;
         let sync_data = self.sync_data.unwrap();
-        let config = chronicle_common::get_config();
         let keyspace = ChronicleKeyspace::new(
             config
                 .storage_config
@@ -158,7 +202,7 @@ impl Builder for SyncerBuilder {
                 .and_then(|keyspace| Some(keyspace.name.clone()))
                 .unwrap_or("permanode".to_owned()),
         );
-        Self::State {
+        Syncer {
             service: Service::new(),
             sync_data,
             solidifier_handles,
@@ -168,6 +212,15 @@ impl Builder for SyncerBuilder {
             update_sync_data_every: self
                 .update_sync_data_every
                 .unwrap_or(std::time::Duration::from_secs(60 * 60)),
+            retry_backoff: self.retry_backoff.unwrap_or(std::time::Duration::from_secs(60)),
+            retries_per_query: config.broker_config.retries_per_query,
+            chronicle_id: self.chronicle_id.unwrap_or(0),
+            // tells this instance apart from its previous runs and from any other instance with the same chronicle id
+            instance: rand::random(),
+            lease_duration: self.lease_duration,
+            lease_range: self.lease_range.unwrap_or(10000).max(1),
+            lease: None,
+            claiming: None,
             parallelism: self.parallelism.unwrap_or(solidifier_count),
             active: None,
             first_ask: self.first_ask,
@@ -181,6 +234,10 @@ impl Builder for SyncerBuilder {
             initial_gap_start: 0,
             initial_gap_end: 0,
             prev_closed_log_filename: 0,
+            in_flight: BTreeSet::new(),
+            unreachable: BTreeMap::new(),
+            synced: 0,
+            started: None,
             oneshot: self.oneshot,
             handle: self.handle.unwrap(),
             inbox: self.inbox.unwrap(),
@@ -192,6 +249,7 @@ impl Builder for SyncerBuilder {
 enum Active {
     Complete(std::ops::Range<u32>),
     FillGaps(std::ops::Range<u32>),
+    Retry(std::ops::Range<u32>),
 }
 /// impl name of the Syncer
 impl Name for Syncer {
@@ -0,0 +1,118 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+impl Syncer {
+    /// Publish the live status of the syncer, to be observed by the API and the sockets
+    pub(crate) async fn publish_status(&self) {
+        let status = self.status();
+        SYNCER_STATUS.write().await.replace(status);
+    }
+    /// Build the live status of the syncer
+    pub(crate) fn status(&self) -> SyncerStatus {
+        let (task, complete) = match self.active.as_ref() {
+            Some(Active::Complete(range)) => (Some(SyncerTask::Complete(range.clone())), true),
+            Some(Active::FillGaps(range)) => (Some(SyncerTask::FillGaps(range.clone())), false),
+            Some(Active::Retry(range)) => (Some(SyncerTask::Retry(range.clone())), false),
+            // an idle syncer is either waiting for the first milestones or reached EOF
+            None => (None, matches!(self.first_ask, Some(AskSyncer::Complete))),
+        };
+        let active_remaining = task.as_ref().map_or(0, |task| match task {
+            SyncerTask::Complete(range) | SyncerTask::FillGaps(range) | SyncerTask::Retry(range) => range.len() as u64,
+        });
+        // Complete task syncs both the gaps and the unlogged ranges, while FillGaps only syncs the gaps
+        let mut remaining = active_remaining + self.pending as u64 + self.ranges_len(&self.sync_data.gaps);
+        if complete {
+            remaining += self.ranges_len(&self.sync_data.synced_but_unlogged);
+        }
+        let elapsed = self.started.map_or(0.0, |started| started.elapsed().as_secs_f64());
+        let milestones_per_second = if elapsed > 0.0 {
+            self.synced as f64 / elapsed
+        } else {
+            0.0
+        };
+        let eta_seconds = if remaining == 0 {
+            Some(0)
+        } else if milestones_per_second > 0.0 {
+            Some((remaining as f64 / milestones_per_second).ceil() as u64)
+        } else {
+            None
+        };
+        SyncerStatus {
+            task,
+            in_flight: self.in_flight.iter().copied().collect(),
+            next: self.next,
+            highest: self.highest,
+            gaps: self.sync_data.gaps.clone(),
+            synced_but_unlogged: self.sync_data.synced_but_unlogged.clone(),
+            remaining,
+            synced: self.synced,
+            milestones_per_second,
+            eta_seconds,
+            unreachable: self.unreachable.values().cloned().collect(),
+        }
+    }
+    /// The number of milestones within the ranges, note: the last gap is open ended up to the highest
+    fn ranges_len(&self, ranges: &Vec<std::ops::Range<u32>>) -> u64 {
+        ranges
+            .iter()
+            .map(|range| range.end.min(self.highest).saturating_sub(range.start) as u64)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syncer(active: Option<Active>, sync_data: SyncData) -> Syncer {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut syncer = SyncerBuilder::new()
+            .sync_data(sync_data)
+            .solidifier_handles(HashMap::new())
+            .first_ask(AskSyncer::Complete)
+            .handle(SyncerHandle { tx })
+            .inbox(SyncerInbox { rx })
+            .build_with(&Default::default());
+        syncer.active = active;
+        syncer.highest = 200;
+        syncer.pending = 2;
+        syncer.synced = 21;
+        syncer.started = Some(Instant::now() - Duration::from_secs(10));
+        syncer
+    }
+
+    #[test]
+    fn syncer_status_remaining_and_eta() {
+        let sync_data = SyncData {
+            completed: vec![],
+            synced_but_unlogged: vec![120..130],
+            // the last gap is open ended up to the highest
+            gaps: vec![150..160, 190..u32::MAX],
+        };
+        // 10 active + 2 pending + 20 in the gaps + 10 unlogged
+        let status = syncer(Some(Active::Complete(100..110)), sync_data.clone()).status();
+        assert_eq!(status.task, Some(SyncerTask::Complete(100..110)));
+        assert_eq!(status.remaining, 42);
+        assert_eq!(status.synced, 21);
+        assert!(status.milestones_per_second > 2.09 && status.milestones_per_second <= 2.1);
+        // 42 remaining milestones at ~2.1 milestones per second
+        assert!(matches!(status.eta_seconds, Some(20..=21)));
+        // filling the gaps doesn't log the unlogged ranges
+        let status = syncer(Some(Active::FillGaps(100..110)), sync_data.clone()).status();
+        assert_eq!(status.remaining, 32);
+        // an idle syncer without any fill rate has no ETA
+        let mut idle = syncer(None, sync_data);
+        idle.started = None;
+        let status = idle.status();
+        assert_eq!(status.task, None);
+        assert_eq!(status.remaining, 32);
+        assert_eq!(status.eta_seconds, None);
+        // nothing remains once everything is synced
+        let mut synced = syncer(None, SyncData::default());
+        synced.pending = 0;
+        assert_eq!(synced.status().remaining, 0);
+        assert_eq!(synced.status().eta_seconds, Some(0));
+    }
+}
@@ -11,6 +11,7 @@ impl<H: ChronicleBrokerScope> Terminating<BrokerHandle<H>> for Syncer {
         _supervisor: &mut Option<BrokerHandle<H>>,
     ) -> Result<(), Need> {
         info!("Syncer is terminating");
+        SYNCER_STATUS.write().await.take();
         self.service.update_status(ServiceStatus::Stopping);
         let event = BrokerEvent::Children(BrokerChild::Syncer(self.service.clone(), _status));
         let _ = _supervisor.as_mut().expect("Syncer expected BrokerHandle").send(event);
@@ -0,0 +1,183 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use bee_message::prelude::MilestoneIndex;
+use chronicle_common::Synckey;
+use std::time::{
+    SystemTime,
+    UNIX_EPOCH,
+};
+
+/// The maximum backoff between two consecutive retries of an unreachable milestone
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The retry backoff doubled for every attempt after the first one, up to its upper bound
+fn unreachable_backoff(retry_backoff: Duration, attempts: u32) -> Duration {
+    retry_backoff
+        .checked_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+        .unwrap_or(MAX_RETRY_BACKOFF)
+        .min(MAX_RETRY_BACKOFF)
+}
+
+impl Syncer {
+    /// Load the unreachable milestones recorded by the previous runs
+    pub(crate) async fn load_unreachable(&mut self) {
+        match UnreachableMilestone::try_fetch(&self.keyspace, self.retries_per_query).await {
+            Ok(unreachable_milestones) => {
+                for unreachable in unreachable_milestones {
+                    self.unreachable.insert(unreachable.milestone_index, unreachable);
+                }
+                info!("Loaded {} unreachable milestones", self.unreachable.len());
+            }
+            Err(e) => warn!("Unable to load the unreachable milestones, error: {}", e),
+        }
+    }
+    /// Record a failed attempt of an unreachable milestone, and schedule its retry using exponential backoff
+    pub(crate) fn record_unreachable(&mut self, milestone_index: u32, reason: UnreachableReason) {
+        let now = Self::unix_timestamp();
+        let retry_backoff = self.retry_backoff;
+        let unreachable = self
+            .unreachable
+            .entry(milestone_index)
+            .or_insert_with(|| UnreachableMilestone {
+                milestone_index,
+                reason: String::new(),
+                attempts: 0,
+                last_attempt: now,
+                next_attempt: now,
+            });
+        unreachable.attempts += 1;
+        unreachable.reason = reason.to_string();
+        unreachable.last_attempt = now;
+        let backoff = unreachable_backoff(retry_backoff, unreachable.attempts);
+        unreachable.next_attempt = now + backoff.as_secs();
+        warn!(
+            "Milestone index: {} is unreachable after {} attempts, next retry in {:?}",
+            milestone_index, unreachable.attempts, backoff
+        );
+        let record = UnreachableRecord::from(&*unreachable);
+        self.persist_unreachable(record);
+    }
+    /// Remove the unreachable milestone once its milestone data got synced
+    pub(crate) fn recover_unreachable(&mut self, milestone_index: u32) {
+        if let Some(unreachable) = self.unreachable.remove(&milestone_index) {
+            info!(
+                "Recovered the unreachable milestone index: {} after {} attempts",
+                milestone_index, unreachable.attempts
+            );
+            let key = (Synckey, MilestoneIndex(milestone_index));
+            if let Err(e) = self.delete::<_, UnreachableRecord>(key) {
+                warn!(
+                    "Unable to delete the unreachable milestone index: {}, error: {}",
+                    milestone_index, e
+                );
+            }
+        }
+    }
+    /// Re-queue the given unreachable milestones for an immediate retry, or all of them if None
+    pub(crate) fn requeue_unreachable(&mut self, milestone_indexes: Option<Vec<u32>>) {
+        let now = Self::unix_timestamp();
+        let milestone_indexes =
+            milestone_indexes.unwrap_or_else(|| self.unreachable.keys().copied().collect::<Vec<u32>>());
+        for milestone_index in milestone_indexes {
+            if let Some(unreachable) = self.unreachable.get_mut(&milestone_index) {
+                info!("Re-queued the unreachable milestone index: {}", milestone_index);
+                unreachable.next_attempt = now;
+                let record = UnreachableRecord::from(&*unreachable);
+                self.persist_unreachable(record);
+            } else {
+                warn!(
+                    "Cannot re-queue milestone index: {}, as it's not unreachable",
+                    milestone_index
+                );
+            }
+        }
+    }
+    /// Retry the lowest due unreachable milestone, only once the syncer reached the end of its sync data
+    pub(crate) fn retry_unreachable(&mut self) {
+        if self.active.is_some() || self.claiming.is_some() || !self.eof || self.highest.eq(&0) {
+            return;
+        }
+        let now = Self::unix_timestamp();
+        if let Some(milestone_index) = self
+            .unreachable
+            .values()
+            .find(|unreachable| unreachable.next_attempt <= now)
+            .map(|unreachable| unreachable.milestone_index)
+        {
+            info!("Retrying the unreachable milestone index: {}", milestone_index);
+            // set next to be the start
+            self.next = milestone_index;
+            self.initial_gap_start = self.next;
+            self.initial_gap_end = milestone_index + 1;
+            self.activate(Active::Retry(milestone_index..(milestone_index + 1)));
+        }
+    }
+    pub(crate) fn schedule_retry_unreachable(&self) {
+        let retry_backoff = self.retry_backoff;
+        let handle = self.handle.clone();
+        let retry_unreachable = async move {
+            tokio::time::sleep(retry_backoff).await;
+            let _ = handle.send(SyncerEvent::RetryUnreachable);
+        };
+        tokio::spawn(retry_unreachable);
+    }
+    fn persist_unreachable(&self, record: UnreachableRecord) {
+        let sync_key = Synckey;
+        // insert into the DB, without caring about the response
+        match self
+            .keyspace
+            .insert(&sync_key, &record)
+            .consistency(Consistency::One)
+            .build()
+        {
+            Ok(req) => {
+                req.send_local(InsertWorker::boxed(
+                    self.keyspace.clone(),
+                    sync_key,
+                    record,
+                    self.retries_per_query,
+                ));
+            }
+            Err(e) => warn!(
+                "Unable to persist the unreachable milestone index: {}, error: {}",
+                record.milestone_index.0, e
+            ),
+        }
+    }
+    /// Delete the key in the `Chronicle` keyspace
+    fn delete<K, V>(&self, key: K) -> anyhow::Result<()>
+    where
+        ChronicleKeyspace: Delete<K, V>,
+        K: 'static + Send + Sync + Clone,
+        V: 'static + Send + Sync + Clone,
+    {
+        let delete_req = self.keyspace.delete(&key).consistency(Consistency::One).build()?;
+        let worker = DeleteWorker::boxed(self.keyspace.clone(), key, self.retries_per_query);
+        delete_req.send_local(worker);
+        Ok(())
+    }
+    pub(crate) fn unix_timestamp() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreachable_backoff_doubles_up_to_the_max() {
+        let retry_backoff = Duration::from_secs(60);
+        assert_eq!(unreachable_backoff(retry_backoff, 1), retry_backoff);
+        assert_eq!(unreachable_backoff(retry_backoff, 2), retry_backoff * 2);
+        assert_eq!(unreachable_backoff(retry_backoff, 5), retry_backoff * 16);
+        // 60 * 2^10 seconds exceed the max of a day
+        assert_eq!(unreachable_backoff(retry_backoff, 11), MAX_RETRY_BACKOFF);
+        assert_eq!(unreachable_backoff(retry_backoff, u32::MAX), MAX_RETRY_BACKOFF);
+        assert_eq!(unreachable_backoff(Duration::MAX, 2), MAX_RETRY_BACKOFF);
+    }
+}
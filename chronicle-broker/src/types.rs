@@ -4,6 +4,7 @@
 use bee_message::{
     prelude::{
         Essence,
+        MilestoneIndex,
         MilestonePayload,
         Output,
         Payload,
@@ -17,6 +18,7 @@ use chronicle_storage::access::{
     MessageCount,
     TransactionCount,
     TransferredTokens,
+    UnreachableRecord,
 };
 #[cfg(feature = "scylla-rs")]
 use scylla_rs::cql::Rows;
@@ -28,6 +30,11 @@ use std::{
     collections::HashMap,
     ops::Range,
     path::PathBuf,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+    time::SystemTime,
 };
 use url::Url;
 
@@ -47,6 +54,53 @@ pub enum ChronicleBrokerThrough {
     Topology(BrokerTopology),
     /// Exit the broker app
     ExitProgram,
+    /// Request the live status of the syncer
+    SyncerStatus,
+    /// Re-queue the given unreachable milestones for an immediate retry, or all of them if None
+    RequeueUnreachable(Option<Vec<u32>>),
+    /// Request the health of the api endpoints used by the requesters
+    EndpointsHealth,
+    /// Request the deliveries of the feeds, cross-checked by the collectors
+    FeedsReport,
+    /// A command along with its request id, which the broker acknowledges with a `ControlReply`
+    Request {
+        /// The request id, chosen by the sender
+        id: u64,
+        /// The command
+        command: Box<ChronicleBrokerThrough>,
+    },
+}
+
+impl ChronicleBrokerThrough {
+    /// Wrap the command into a request with a new request id, which the broker acknowledges with a `ControlReply`
+    pub fn into_request(self) -> Self {
+        ChronicleBrokerThrough::Request {
+            id: request_id(),
+            command: Box::new(self),
+        }
+    }
+}
+
+/// The reply to a control request, which either succeeded or failed with the given reason
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ControlReply {
+    /// The id of the request
+    pub id: u64,
+    /// The outcome of the request
+    pub result: Result<(), String>,
+}
+
+/// The number of requests sent by this process, to keep their ids unique
+static REQUEST_COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// A new id for a control request, which is unique within the process and unlikely to collide with the requests
+/// of the other processes
+pub fn request_id() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+    nanos.wrapping_add(REQUEST_COUNT.fetch_add(1, Ordering::Relaxed))
 }
 
 /// Topology event
@@ -60,6 +114,14 @@ pub enum BrokerTopology {
     RemoveMqttMessages(Url),
     /// Remove a MQTT Messages Referenced feed source
     RemoveMqttMessagesReferenced(Url),
+    /// Add new MQTT Latest Milestone feed source
+    AddMqttLatestMilestone(Url),
+    /// Remove a MQTT Latest Milestone feed source
+    RemoveMqttLatestMilestone(Url),
+    /// Add new websocket event stream feed source, for all the topics
+    AddEventStream(Url),
+    /// Remove a websocket event stream feed source
+    RemoveEventStream(Url),
     /// Import a log file using the given url
     Import {
         /// File or dir path which supposed to contain LogFiles
@@ -71,17 +133,76 @@ pub enum BrokerTopology {
         /// The type of import requested
         import_type: ImportType,
     },
+    /// Import LogFile(s) from a remote HTTP(S) source.
+    /// The url either points to a LogFile, or to a base url which must serve a `manifest.json`
+    /// listing the LogFiles names (ie `["1to1000.log", "1000to2000.log"]`)
+    ImportRemote {
+        /// LogFile or base url
+        url: Url,
+        /// Resume the importing process
+        resume: bool,
+        /// Provide optional import range
+        import_range: Option<Range<u32>>,
+        /// The type of import requested
+        import_type: ImportType,
+    },
+    /// Export the synced milestones data of the given range into LogFiles
+    Export {
+        /// The dir path where the exported LogFiles will be written
+        path: PathBuf,
+        /// The range of milestones to export
+        export_range: Range<u32>,
+    },
     /// Add Endpoint
     Requesters(RequesterTopology),
+    /// Update the range of milestones to sync, which the syncer applies with its next sync data update
+    UpdateSyncRange(Range<u32>),
 }
 
 /// Import types
-#[derive(Deserialize, Serialize, Debug, Copy, Clone)]
+#[derive(Deserialize, Serialize, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ImportType {
     /// Import everything
     All,
     /// Import only Analytics data
     Analytics,
+    /// Compare the LogFiles against the stored data without writing anything
+    DryRun,
+}
+
+/// The persisted import progress of a LogFile, which is used to resume importing it mid-file
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ImportCheckpoint {
+    /// The keyspace the LogFile is imported into
+    pub keyspace: String,
+    /// The import type
+    pub import_type: ImportType,
+    /// LogFile start range
+    pub from_ms: u32,
+    /// LogFile end range
+    pub to_ms: u32,
+    /// The last milestone index which got fully imported along with all its preceding ones in the LogFile
+    pub milestone_index: Option<u32>,
+    /// The LogFile bytes offset right after the last fully imported milestone data
+    pub offset: u64,
+    /// Total size of the LogFile
+    pub log_file_size: u64,
+    /// Identify whether the whole LogFile got imported
+    pub done: bool,
+}
+
+impl ImportCheckpoint {
+    /// The directory of the import checkpoints, it's placed in the logs directory if any
+    pub fn dir_path(logs_dir: Option<&String>) -> PathBuf {
+        logs_dir
+            .map(PathBuf::from)
+            .unwrap_or_else(|| std::env::temp_dir().join("chronicle-import"))
+            .join(".import_checkpoints")
+    }
+    /// The file path of the import checkpoint of a LogFile
+    pub fn file_path(dir_path: &PathBuf, keyspace: &str, import_type: ImportType, from_ms: u32, to_ms: u32) -> PathBuf {
+        dir_path.join(format!("{}.{:?}.{}to{}.json", keyspace, import_type, from_ms, to_ms))
+    }
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -263,10 +384,302 @@ pub enum ImporterSession {
         /// Useful debug message
         msg: String,
     },
+    /// Return remote source error
+    UrlError {
+        /// Invalid or unreachable url
+        url: Url,
+        /// Useful debug message
+        msg: String,
+    },
+    /// Report the global progress of the import session
+    Report(ImportReport),
+    /// Report the comparison of a LogFile milestone against the stored one (DryRun mode)
+    Diff {
+        /// LogFile start range
+        from_ms: u32,
+        /// LogFile end range
+        to_ms: u32,
+        /// Milestone index
+        milestone_index: u32,
+        /// The comparison result
+        diff: MilestoneDiff,
+    },
     /// Close session
     Close,
 }
 
+/// The global progress and throughput of an import session, across all of its LogFiles
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    /// The processed milestones
+    pub milestones: u64,
+    /// The processed bytes
+    pub bytes: u64,
+    /// The total bytes of the scheduled LogFiles
+    pub total_bytes: u64,
+    /// The throughput in milestones per second
+    pub milestones_per_second: f64,
+    /// The throughput in bytes per second
+    pub bytes_per_second: f64,
+    /// The estimated remaining seconds, if the throughput is known
+    pub eta_seconds: Option<u64>,
+    /// The number of failed LogFiles
+    pub failures: usize,
+    /// The number of in progress LogFiles
+    pub in_progress_files: usize,
+    /// The number of pending LogFiles
+    pub pending_files: usize,
+}
+
+/// The comparison result of a LogFile milestone against the stored one
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MilestoneDiff {
+    /// The milestone is not synced in the sync table
+    New,
+    /// The milestone is synced and all of its messages are stored identically
+    Identical,
+    /// The milestone is synced, but the stored data differs from the LogFile
+    Divergent {
+        /// Identify whether the stored milestone message differs or is missing
+        milestone: bool,
+        /// The number of messages which are not stored
+        missing_messages: usize,
+        /// The number of stored messages whose message or metadata differs
+        divergent_messages: usize,
+    },
+}
+
+/// Enum used by exporter to keep the sockets up to date with most recent progress.
+#[derive(Deserialize, Serialize, Debug)]
+pub enum ExporterSession {
+    /// Create/update progress bar state
+    ProgressBar {
+        /// Export range start
+        from_ms: u32,
+        /// Export range end
+        to_ms: u32,
+        /// Milestone index
+        milestone_index: u32,
+        /// Identify whether it skipped the milestone_index or exported.
+        skipped: bool,
+    },
+    /// Finish the progress bar with message
+    Finish {
+        /// Export range start
+        from_ms: u32,
+        /// Export range end
+        to_ms: u32,
+        /// Finish the progress bar using this msg.
+        msg: String,
+    },
+    /// Return error
+    PathError {
+        /// Invalid dir path
+        path: PathBuf,
+        /// Useful debug message
+        msg: String,
+    },
+}
+
+/// Enum used to respond to the sockets with the syncer state.
+#[derive(Deserialize, Serialize, Debug)]
+pub enum SyncerSession {
+    /// The live status of the syncer, None if the syncer is not running
+    Status(Option<SyncerStatus>),
+}
+
+/// The active task of the syncer along with its remaining range
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum SyncerTask {
+    /// Completing (syncing and logging) the range
+    Complete(Range<u32>),
+    /// Filling the gaps of the range
+    FillGaps(Range<u32>),
+    /// Retrying an unreachable milestone
+    Retry(Range<u32>),
+}
+
+/// The reason why a milestone is unreachable
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnreachableReason {
+    /// No peers were able to provide the milestone data
+    NoPeers,
+    /// The milestone is not below the expected milestone index of the solidifier
+    AheadOfExpected,
+    /// The milestone data is being solidified from the incoming feed, without its milestone
+    Conflict,
+}
+
+impl std::fmt::Display for UnreachableReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnreachableReason::NoPeers => write!(f, "no peers were able to provide it"),
+            UnreachableReason::AheadOfExpected => write!(f, "ahead of the expected milestone index"),
+            UnreachableReason::Conflict => write!(f, "conflicts with the incoming milestone data"),
+        }
+    }
+}
+
+/// A milestone which got skipped as unreachable, and is scheduled for retry
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnreachableMilestone {
+    /// The milestone index
+    pub milestone_index: u32,
+    /// The reason of the last failed attempt
+    pub reason: String,
+    /// The number of failed attempts
+    pub attempts: u32,
+    /// The unix timestamp in seconds of the last failed attempt
+    pub last_attempt: u64,
+    /// The unix timestamp in seconds of the next retry
+    pub next_attempt: u64,
+}
+
+impl From<UnreachableRecord> for UnreachableMilestone {
+    fn from(record: UnreachableRecord) -> Self {
+        Self {
+            milestone_index: record.milestone_index.0,
+            reason: record.reason,
+            attempts: record.attempts,
+            last_attempt: record.last_attempt,
+            next_attempt: record.next_attempt,
+        }
+    }
+}
+
+impl From<&UnreachableMilestone> for UnreachableRecord {
+    fn from(unreachable: &UnreachableMilestone) -> Self {
+        UnreachableRecord::new(
+            MilestoneIndex(unreachable.milestone_index),
+            unreachable.reason.clone(),
+            unreachable.attempts,
+            unreachable.last_attempt,
+            unreachable.next_attempt,
+        )
+    }
+}
+
+/// The live status of the syncer
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct SyncerStatus {
+    /// The active task, None if the syncer is idle
+    pub task: Option<SyncerTask>,
+    /// The requested milestones which are still being solidified
+    pub in_flight: Vec<u32>,
+    /// The next milestone index expected by the archiver
+    pub next: u32,
+    /// The highest observed milestone index
+    pub highest: u32,
+    /// The remaining gaps, excluding the active range
+    pub gaps: Vec<Range<u32>>,
+    /// The remaining synced but unlogged ranges, excluding the active range
+    pub synced_but_unlogged: Vec<Range<u32>>,
+    /// The number of milestones which are yet to be synced by the current and pending tasks
+    pub remaining: u64,
+    /// The number of milestones synced since the syncer started its first task
+    pub synced: u64,
+    /// The fill rate in milestones per second
+    pub milestones_per_second: f64,
+    /// The estimated remaining seconds, if the fill rate is known
+    pub eta_seconds: Option<u64>,
+    /// The milestones skipped as unreachable, which are scheduled for retry
+    pub unreachable: Vec<UnreachableMilestone>,
+}
+
+/// Enum used to respond to the sockets with the requesters state.
+#[derive(Deserialize, Serialize, Debug)]
+pub enum RequesterSession {
+    /// The health of the api endpoints used by the requesters
+    Endpoints(Vec<EndpointStatus>),
+}
+
+/// The circuit breaker state of an api endpoint
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// The endpoint is selectable
+    Closed,
+    /// The endpoint is excluded from the selection until its cooldown elapses
+    Open,
+    /// The cooldown elapsed, the next request probes the endpoint
+    HalfOpen,
+}
+
+impl std::fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CircuitState::Closed => write!(f, "closed"),
+            CircuitState::Open => write!(f, "open"),
+            CircuitState::HalfOpen => write!(f, "half open"),
+        }
+    }
+}
+
+/// The health of an api endpoint, as observed by the requesters
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct EndpointStatus {
+    /// The api endpoint
+    pub url: Url,
+    /// The circuit breaker state
+    pub circuit: CircuitState,
+    /// Whether the endpoint is quarantined, as it served invalid data
+    pub quarantined: bool,
+    /// Whether the node reports itself as synced, None if its info was never fetched
+    pub healthy: Option<bool>,
+    /// The confirmed milestone index reported by the node info
+    pub confirmed_milestone_index: Option<u32>,
+    /// The latency EWMA in milliseconds, None if no request succeeded yet
+    pub latency_ms: Option<f64>,
+    /// The error rate EWMA, between 0 and 1
+    pub error_rate: f64,
+    /// The number of consecutive failed requests
+    pub consecutive_failures: u32,
+    /// The total number of requests
+    pub requests: u64,
+    /// The total number of failed requests
+    pub failures: u64,
+}
+
+/// Enum used to respond to the sockets with the feeds state.
+#[derive(Deserialize, Serialize, Debug)]
+pub enum FeedSession {
+    /// The deliveries of the feeds
+    Report(Vec<FeedStatus>),
+}
+
+/// The deliveries of a feed, cross-checked against the other feeds of the same topic
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FeedStatus {
+    /// The feed topic
+    pub topic: String,
+    /// The feed url
+    pub url: Url,
+    /// The number of delivered messages
+    pub delivered: u64,
+    /// The number of messages delivered by this feed before the others
+    pub first: u64,
+    /// The number of messages only delivered by this feed
+    pub exclusive: u64,
+    /// The number of messages delivered by the other feeds but not this one
+    pub missed: u64,
+    /// The delay EWMA in milliseconds behind the first delivery, None if nothing got delivered yet
+    pub delay_ms: Option<f64>,
+}
+
+impl FeedStatus {
+    /// Create an empty feed status
+    pub fn new(topic: &str, url: Url) -> Self {
+        Self {
+            topic: topic.to_owned(),
+            url,
+            delivered: 0,
+            first: 0,
+            exclusive: 0,
+            missed: 0,
+            delay_ms: None,
+        }
+    }
+}
+
 #[cfg(feature = "sync")]
 pub use sync::*;
 #[cfg(feature = "sync")]
@@ -286,6 +699,9 @@ mod sync {
         ops::Range,
     };
 
+    /// The live status of the syncer, published by the syncer while it's running
+    pub static SYNCER_STATUS: tokio::sync::RwLock<Option<SyncerStatus>> = tokio::sync::RwLock::const_new(None);
+
     /// Representation of the database sync data
     #[derive(Debug, Clone, Default, Serialize)]
     pub struct SyncData {
@@ -516,6 +932,34 @@ mod sync {
             };
         }
     }
+    impl UnreachableMilestone {
+        /// Try to fetch the unreachable milestones from the unreachable table for the provided keyspace
+        pub async fn try_fetch<S: 'static + Select<SyncRange, Iter<UnreachableRecord>>>(
+            keyspace: &S,
+            retries: usize,
+        ) -> anyhow::Result<Vec<UnreachableMilestone>> {
+            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+            let sync_range = SyncRange::default();
+            keyspace
+                .select(&sync_range)
+                .consistency(Consistency::One)
+                .build()?
+                .send_local(ValueWorker::boxed(
+                    tx,
+                    keyspace.clone(),
+                    sync_range,
+                    retries,
+                    std::marker::PhantomData,
+                ));
+            let records = rx
+                .recv()
+                .await
+                .ok_or_else(|| anyhow::anyhow!("No response from scylla!"))??;
+            Ok(records
+                .map(|records| records.map(UnreachableMilestone::from).collect())
+                .unwrap_or_default())
+        }
+    }
 }
 
 #[cfg(feature = "analytic")]
@@ -7,6 +7,7 @@ use anyhow::{
 };
 use chronicle::{
     ConfigCommand,
+    ControlRequest,
     SocketMsg,
 };
 use chronicle_broker::{
@@ -15,12 +16,16 @@ use chronicle_broker::{
         Merger,
         ValidationLevel,
     },
-    BrokerSocketMsg,
     BrokerTopology,
     ChronicleBrokerThrough,
+    ControlReply,
     *,
 };
 use chronicle_common::config::{
+    export_overrides,
+    set_overrides,
+    Config,
+    ConfigDiff,
     MqttType,
     VersionedConfig,
 };
@@ -30,25 +35,35 @@ use clap::{
     ArgMatches,
 };
 use futures::{
+    Sink,
     SinkExt,
+    Stream,
     StreamExt,
 };
 use indicatif::{
+    HumanBytes,
+    HumanDuration,
     ProgressBar,
     ProgressStyle,
 };
 use regex::Regex;
 use scylla_rs::prelude::ScyllaThrough;
+use serde::Serialize;
 use std::{
+    ops::Range,
     path::{
         Path,
         PathBuf,
     },
     process::Command,
+    time::Duration,
 };
 use tokio_tungstenite::{
     connect_async,
-    tungstenite::Message,
+    tungstenite::{
+        Error as WsError,
+        Message,
+    },
 };
 use url::Url;
 
@@ -62,8 +77,11 @@ async fn process() -> anyhow::Result<()> {
     let app = App::from_yaml(yaml).version(std::env!("CARGO_PKG_VERSION"));
     let matches = app.get_matches();
 
+    set_overrides(config_overrides(&matches))?;
     match matches.subcommand() {
         ("start", Some(matches)) => {
+            // the chronicle process inherits the overrides
+            export_overrides();
             // Assume the chronicle exe is in the same location as this one
             let current_exe = std::env::current_exe()?;
             let parent_dir = current_exe
@@ -106,44 +124,262 @@ async fn process() -> anyhow::Result<()> {
                 bail!("No chronicle exe in the current directory: {}", parent_dir.display());
             }
         }
-        ("stop", Some(_matches)) => {
+        ("stop", Some(matches)) => {
             let config = VersionedConfig::load(None)?.verify().await?;
             let (mut stream, _) = connect_async(Url::parse(&format!("ws://{}/", config.websocket_address))?).await?;
-            let message = Message::text(serde_json::to_string(&BrokerSocketMsg::ChronicleBroker(
-                ChronicleBrokerThrough::ExitProgram,
-            ))?);
-            stream.send(message).await?;
+            let message = SocketMsg::Broker(ChronicleBrokerThrough::ExitProgram);
+            request(&mut stream, message, "Stop", reply_timeout(matches)?).await?;
         }
-        ("rebuild", Some(_matches)) => {
+        ("rebuild", Some(matches)) => {
             let config = VersionedConfig::load(None)?.verify().await?;
             let (mut stream, _) = connect_async(Url::parse(&format!("ws://{}/", config.websocket_address))?).await?;
-            let message = Message::text(serde_json::to_string(&SocketMsg::Scylla(ScyllaThrough::Topology(
-                scylla_rs::prelude::Topology::BuildRing(1),
-            )))?);
-            stream.send(message).await?;
+            let message = SocketMsg::Scylla(ScyllaThrough::Topology(scylla_rs::prelude::Topology::BuildRing(1)));
+            request(&mut stream, message, "Rebuild the ring", reply_timeout(matches)?).await?;
         }
+        ("config", Some(matches)) if matches.subcommand_name().is_some() => match matches.subcommand() {
+            ("migrate", Some(subcommand)) => migrate_config(subcommand)?,
+            ("check", Some(subcommand)) => check_config(subcommand).await?,
+            _ => (),
+        },
         ("config", Some(matches)) => {
             let config = VersionedConfig::load(None)?.verify().await?;
             if matches.is_present("print") {
-                println!("{:#?}", config);
+                if matches.is_present("effective") {
+                    println!("{:#?}", config);
+                } else {
+                    println!("{:#?}", VersionedConfig::load_file(None)?);
+                }
             }
             if matches.is_present("rollback") {
                 let (mut stream, _) =
                     connect_async(Url::parse(&format!("ws://{}/", config.websocket_address))?).await?;
-                let message = Message::text(serde_json::to_string(&SocketMsg::General(ConfigCommand::Rollback))?);
-                stream.send(message).await?;
+                let message = SocketMsg::General(ConfigCommand::Rollback);
+                request(&mut stream, message, "Roll back the config", reply_timeout(matches)?).await?;
+            }
+            if matches.is_present("reload") {
+                let (mut stream, _) =
+                    connect_async(Url::parse(&format!("ws://{}/", config.websocket_address))?).await?;
+                let timeout = reply_timeout(matches)?;
+                let id = send_request(&mut stream, SocketMsg::General(ConfigCommand::Reload), timeout).await?;
+                wait_reply(&mut stream, id, "Reload the config", timeout, |s| {
+                    if let Ok(SocketMsg::General(diff)) = serde_json::from_str::<SocketMsg<ConfigDiff>>(s) {
+                        print_config_diff(&diff);
+                    }
+                })
+                .await?;
             }
         }
         ("nodes", Some(matches)) => nodes(matches).await?,
         ("brokers", Some(matches)) => brokers(matches).await?,
+        ("syncer", Some(matches)) => syncer(matches).await?,
         ("archive", Some(matches)) => archive(matches).await?,
         _ => (),
     }
     Ok(())
 }
 
+/// The --set config overrides, which are propagated to the subcommands, so the deepest ones are used
+fn config_overrides<'a>(matches: &'a ArgMatches<'a>) -> Vec<&'a str> {
+    let mut overrides = Vec::new();
+    let mut matches = Some(matches);
+    while let Some(current) = matches {
+        if let Some(values) = current.values_of("set") {
+            overrides = values.collect();
+        }
+        matches = current.subcommand().1;
+    }
+    overrides
+}
+
+/// The duration to wait for the reply to a request, 60 seconds by default
+fn reply_timeout<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<Duration> {
+    let secs = match matches.value_of("timeout") {
+        Some(secs) => secs
+            .parse()
+            .map_err(|e| anyhow!("Invalid timeout: {}, error: {}", secs, e))?,
+        None => 60,
+    };
+    Ok(Duration::from_secs(secs))
+}
+
+/// Send the control message along with a new request id, which is returned, and the duration the reply is waited for
+async fn send_request<S, T>(stream: &mut S, message: SocketMsg<T>, timeout: Duration) -> anyhow::Result<u64>
+where
+    S: Sink<Message, Error = WsError> + Unpin,
+    T: Serialize,
+{
+    let id = chronicle_broker::request_id();
+    let request = ControlRequest {
+        id,
+        message,
+        timeout_secs: Some(timeout.as_secs()),
+    };
+    stream.send(Message::text(serde_json::to_string(&request)?)).await?;
+    Ok(id)
+}
+
+/// Send the control message, then wait for its reply and print the outcome
+async fn request<S, T>(
+    stream: &mut S,
+    message: SocketMsg<T>,
+    description: &str,
+    timeout: Duration,
+) -> anyhow::Result<()>
+where
+    S: Sink<Message, Error = WsError> + Stream<Item = Result<Message, WsError>> + Unpin,
+    T: Serialize,
+{
+    let id = send_request(stream, message, timeout).await?;
+    wait_reply(stream, id, description, timeout, |_| ()).await
+}
+
+/// Wait for the reply to the request and print its outcome, fails unless the request succeeded.
+/// The other messages are passed to `on_message`
+async fn wait_reply<S, F>(
+    stream: &mut S,
+    id: u64,
+    description: &str,
+    timeout: Duration,
+    mut on_message: F,
+) -> anyhow::Result<()>
+where
+    S: Stream<Item = Result<Message, WsError>> + Unpin,
+    F: FnMut(&str),
+{
+    let reply = tokio::time::timeout(timeout, async {
+        while let Some(msg) = stream.next().await {
+            if let Message::Text(ref s) = msg? {
+                match serde_json::from_str::<ControlReply>(s) {
+                    Ok(reply) if reply.id == id => return Ok(reply.result),
+                    Ok(_) => (),
+                    Err(_) => on_message(s),
+                }
+            }
+        }
+        bail!("The connection got closed without a reply")
+    })
+    .await;
+    match reply {
+        Ok(Ok(Ok(()))) => {
+            println!("{}: done", description);
+            Ok(())
+        }
+        Ok(Ok(Err(e))) => bail!("{}: failed: {}", description, e),
+        Ok(Err(e)) => bail!("{}: no reply: {}", description, e),
+        Err(_) => bail!("{}: no reply within {} seconds", description, timeout.as_secs()),
+    }
+}
+
+/// Receive the next message of the stream, fails if none is received within the timeout
+async fn next_message<S>(stream: &mut S, timeout: Duration) -> anyhow::Result<Option<Result<Message, WsError>>>
+where
+    S: Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    tokio::time::timeout(timeout, stream.next()).await.map_err(|_| {
+        anyhow!(
+            "No message received from Chronicle within {} seconds",
+            timeout.as_secs()
+        )
+    })
+}
+
+fn migrate_config<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
+    let dry_run = matches.is_present("dry-run");
+    let migration = VersionedConfig::migrate(None, dry_run)?;
+    if migration.is_current() {
+        println!("The config file {} is up to date", migration.path.display());
+        return Ok(());
+    }
+    print_line_diff(&migration.previous, &migration.migrated);
+    if let Some(backup_path) = migration.backup_path.as_ref() {
+        println!(
+            "Migrated the config file {} from version {}, backed it up at {}",
+            migration.path.display(),
+            migration.from_version,
+            backup_path.display()
+        );
+    } else if dry_run {
+        println!(
+            "The config file {} of version {} would be migrated (dry run)",
+            migration.path.display(),
+            migration.from_version
+        );
+    }
+    Ok(())
+}
+
+async fn check_config<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
+    // the check must not migrate nor rewrite the config file
+    let config = VersionedConfig::load_unmigrated(None)?.config;
+    let problems = config.validate(matches.is_present("offline")).await;
+    if problems.is_empty() {
+        println!("The config is valid");
+        return Ok(());
+    }
+    for problem in problems.iter() {
+        println!("{}", problem);
+    }
+    bail!("Found {} problem(s) in the config", problems.len())
+}
+
+/// Print the lines removed from `previous` (-) and added to `next` (+), along with the unchanged ones
+fn print_line_diff(previous: &str, next: &str) {
+    let (previous, next) = (previous.lines().collect::<Vec<_>>(), next.lines().collect::<Vec<_>>());
+    // the longest common subsequences of the suffixes
+    let mut lcs = vec![vec![0usize; next.len() + 1]; previous.len() + 1];
+    for i in (0..previous.len()).rev() {
+        for j in (0..next.len()).rev() {
+            lcs[i][j] = if previous[i] == next[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < previous.len() || j < next.len() {
+        if i < previous.len() && j < next.len() && previous[i] == next[j] {
+            println!("  {}", previous[i]);
+            i += 1;
+            j += 1;
+        } else if j < next.len() && (i == previous.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            println!("+ {}", next[j]);
+            j += 1;
+        } else {
+            println!("- {}", previous[i]);
+            i += 1;
+        }
+    }
+}
+
+fn print_config_diff(diff: &ConfigDiff) {
+    if diff.is_empty() {
+        println!("The config is unchanged");
+        return;
+    }
+    if !diff.hot.is_empty() {
+        println!("Changes applied live:");
+        for change in diff.hot.iter() {
+            println!("\t{:?}", change);
+        }
+    }
+    if !diff.restart.is_empty() {
+        println!("Changes which need a restart:");
+        for field in diff.restart.iter() {
+            println!("\t{}", field);
+        }
+    }
+}
+
 async fn nodes<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
-    let mut config = VersionedConfig::load(None)?.verify().await?;
+    // without connection the config file gets edited, so it's loaded without the overrides
+    let mut config = if matches.is_present("skip-connection") {
+        VersionedConfig::load_file(None)?
+    } else {
+        VersionedConfig::load(None)?
+    }
+    .verify()
+    .await?;
     let add_address = matches
         .value_of("add")
         .map(|address| address.parse().expect("Invalid address provided!"));
@@ -153,17 +389,16 @@ async fn nodes<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
     if !matches.is_present("skip-connection") {
         let (mut stream, _) = connect_async(Url::parse(&format!("ws://{}/", config.websocket_address))?).await?;
 
+        let timeout = reply_timeout(matches)?;
         if let Some(address) = add_address {
             let message = SocketMsg::Scylla(ScyllaThrough::Topology(scylla_rs::prelude::Topology::AddNode(address)));
-            let message = Message::text(serde_json::to_string(&message)?);
-            stream.send(message).await?;
+            request(&mut stream, message, &format!("Add node {}", address), timeout).await?;
         }
         if let Some(address) = rem_address {
             let message = SocketMsg::Scylla(ScyllaThrough::Topology(scylla_rs::prelude::Topology::RemoveNode(
                 address,
             )));
-            let message = Message::text(serde_json::to_string(&message)?);
-            stream.send(message).await?;
+            request(&mut stream, message, &format!("Remove node {}", address), timeout).await?;
         }
         if matches.is_present("list") {
             todo!("Print list of nodes");
@@ -188,35 +423,51 @@ async fn nodes<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
 }
 
 async fn brokers<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
-    let mut config = VersionedConfig::load(None)?.verify().await?;
+    // without connection the config file gets edited, so it's loaded without the overrides
+    let mut config = if matches.is_present("skip-connection") {
+        VersionedConfig::load_file(None)?
+    } else {
+        VersionedConfig::load(None)?
+    }
+    .verify()
+    .await?;
     match matches.subcommand() {
         ("add", Some(subcommand)) => {
             let mqtt_addresses = subcommand
                 .values_of("mqtt-address")
-                .ok_or_else(|| anyhow!("No mqtt addresses received!"))?
+                .into_iter()
+                .flatten()
                 .map(|mqtt_address| Ok(Url::parse(mqtt_address)?))
                 .filter_map(|r: anyhow::Result<Url>| r.ok());
+            let event_stream_addresses = subcommand
+                .values_of("event-stream-address")
+                .into_iter()
+                .flatten()
+                .map(|event_stream_address| Ok(Url::parse(event_stream_address)?))
+                .filter_map(|r: anyhow::Result<Url>| r.ok());
+            if !subcommand.is_present("mqtt-address") && !subcommand.is_present("event-stream-address") {
+                bail!("No mqtt or event stream addresses received!");
+            }
             let _endpoint_addresses = subcommand.values_of("endpoint-address");
             // TODO add endpoints
 
             if !matches.is_present("skip-connection") {
-                let mut messages = Vec::new();
+                let mut topologies = Vec::new();
                 for mqtt_address in mqtt_addresses.clone() {
-                    messages.push(Message::text(serde_json::to_string(
-                        &BrokerSocketMsg::ChronicleBroker(ChronicleBrokerThrough::Topology(
-                            BrokerTopology::AddMqttMessages(mqtt_address.clone()),
-                        )),
-                    )?));
-                    messages.push(Message::text(serde_json::to_string(
-                        &BrokerSocketMsg::ChronicleBroker(ChronicleBrokerThrough::Topology(
-                            BrokerTopology::AddMqttMessagesReferenced(mqtt_address),
-                        )),
-                    )?));
+                    topologies.push(BrokerTopology::AddMqttMessages(mqtt_address.clone()));
+                    topologies.push(BrokerTopology::AddMqttMessagesReferenced(mqtt_address.clone()));
+                    topologies.push(BrokerTopology::AddMqttLatestMilestone(mqtt_address));
+                }
+                for event_stream_address in event_stream_addresses {
+                    topologies.push(BrokerTopology::AddEventStream(event_stream_address));
                 }
                 let (mut stream, _) =
                     connect_async(Url::parse(&format!("ws://{}/", config.websocket_address))?).await?;
-                for message in messages.drain(..) {
-                    stream.send(message).await?;
+                let timeout = reply_timeout(subcommand)?;
+                for topology in topologies.drain(..) {
+                    let description = format!("{:?}", topology);
+                    let message = SocketMsg::Broker(ChronicleBrokerThrough::Topology(topology));
+                    request(&mut stream, message, &description, timeout).await?;
                 }
             } else {
                 config
@@ -228,33 +479,63 @@ async fn brokers<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
                     .broker_config
                     .mqtt_brokers
                     .get_mut(&MqttType::MessagesReferenced)
-                    .map(|m| m.extend(mqtt_addresses));
+                    .map(|m| m.extend(mqtt_addresses.clone()));
+                config
+                    .broker_config
+                    .mqtt_brokers
+                    .entry(MqttType::LatestMilestone)
+                    .or_default()
+                    .extend(mqtt_addresses);
+                let event_streams = config.broker_config.event_streams.get_or_insert_with(Default::default);
+                for mqtt_type in [
+                    MqttType::Messages,
+                    MqttType::MessagesReferenced,
+                    MqttType::LatestMilestone,
+                ] {
+                    event_streams
+                        .entry(mqtt_type)
+                        .or_default()
+                        .extend(event_stream_addresses.clone());
+                }
                 config.save(None).expect("Failed to save config!");
             }
         }
         ("remove", Some(subcommand)) => {
             let mqtt_addresses = subcommand
                 .values_of("mqtt-address")
-                .ok_or_else(|| anyhow!("No mqtt addresses received!"))?
+                .into_iter()
+                .flatten()
                 .map(|mqtt_address| Ok(Url::parse(mqtt_address)?))
                 .filter_map(|r: anyhow::Result<Url>| r.ok());
+            let event_stream_addresses = subcommand
+                .values_of("event-stream-address")
+                .into_iter()
+                .flatten()
+                .map(|event_stream_address| Ok(Url::parse(event_stream_address)?))
+                .filter_map(|r: anyhow::Result<Url>| r.ok());
+            if !subcommand.is_present("mqtt-address") && !subcommand.is_present("event-stream-address") {
+                bail!("No mqtt or event stream addresses received!");
+            }
             let _endpoint_addresses = subcommand.values_of("endpoint-address");
             // TODO add endpoints
 
             if !matches.is_present("skip-connection") {
-                let mut messages = Vec::new();
+                let mut topologies = Vec::new();
                 for mqtt_address in mqtt_addresses.clone() {
-                    messages.push(Message::text(serde_json::to_string(&SocketMsg::Broker(
-                        ChronicleBrokerThrough::Topology(BrokerTopology::RemoveMqttMessages(mqtt_address.clone())),
-                    ))?));
-                    messages.push(Message::text(serde_json::to_string(&SocketMsg::Broker(
-                        ChronicleBrokerThrough::Topology(BrokerTopology::RemoveMqttMessagesReferenced(mqtt_address)),
-                    ))?));
+                    topologies.push(BrokerTopology::RemoveMqttMessages(mqtt_address.clone()));
+                    topologies.push(BrokerTopology::RemoveMqttMessagesReferenced(mqtt_address.clone()));
+                    topologies.push(BrokerTopology::RemoveMqttLatestMilestone(mqtt_address));
+                }
+                for event_stream_address in event_stream_addresses {
+                    topologies.push(BrokerTopology::RemoveEventStream(event_stream_address));
                 }
                 let (mut stream, _) =
                     connect_async(Url::parse(&format!("ws://{}/", config.websocket_address))?).await?;
-                for message in messages.drain(..) {
-                    stream.send(message).await?;
+                let timeout = reply_timeout(subcommand)?;
+                for topology in topologies.drain(..) {
+                    let description = format!("{:?}", topology);
+                    let message = SocketMsg::Broker(ChronicleBrokerThrough::Topology(topology));
+                    request(&mut stream, message, &description, timeout).await?;
                 }
             } else {
                 config.broker_config.mqtt_brokers.get_mut(&MqttType::Messages).map(|m| {
@@ -266,35 +547,247 @@ async fn brokers<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
                     .broker_config
                     .mqtt_brokers
                     .get_mut(&MqttType::MessagesReferenced)
+                    .map(|m| {
+                        mqtt_addresses.clone().for_each(|u| {
+                            m.remove(&u);
+                        })
+                    });
+                config
+                    .broker_config
+                    .mqtt_brokers
+                    .get_mut(&MqttType::LatestMilestone)
                     .map(|m| {
                         mqtt_addresses.for_each(|u| {
                             m.remove(&u);
                         })
                     });
+                if let Some(event_streams) = config.broker_config.event_streams.as_mut() {
+                    for urls in event_streams.values_mut() {
+                        event_stream_addresses.clone().for_each(|u| {
+                            urls.remove(&u);
+                        });
+                    }
+                }
                 config.save(None).expect("Failed to save config!");
             }
         }
         _ => (),
     }
     if matches.is_present("list") {
-        if !matches.is_present("skip-connection") {
-            todo!("List brokers");
-        } else {
-            println!("Configured MQTT Addresses:");
-            config.broker_config.mqtt_brokers.iter().for_each(|(ty, s)| {
+        println!("Configured MQTT Addresses:");
+        config.broker_config.mqtt_brokers.iter().for_each(|(ty, s)| {
+            println!("\t{:?}", ty);
+            for url in s.iter() {
+                println!("\t\t{}", url);
+            }
+        });
+        if let Some(event_streams) = config.broker_config.event_streams.as_ref() {
+            println!("Configured Event Stream Addresses:");
+            event_streams.iter().for_each(|(ty, s)| {
                 println!("\t{:?}", ty);
                 for url in s.iter() {
                     println!("\t\t{}", url);
                 }
             });
         }
+        if !matches.is_present("skip-connection") {
+            let (mut stream, _) = connect_async(Url::parse(&format!("ws://{}/", config.websocket_address))?).await?;
+            let timeout = reply_timeout(matches)?;
+            send_request(
+                &mut stream,
+                SocketMsg::Broker(ChronicleBrokerThrough::EndpointsHealth),
+                timeout,
+            )
+            .await?;
+            send_request(
+                &mut stream,
+                SocketMsg::Broker(ChronicleBrokerThrough::FeedsReport),
+                timeout,
+            )
+            .await?;
+            let (mut endpoints_printed, mut feeds_printed) = (false, false);
+            while let Some(msg) = next_message(&mut stream, timeout).await? {
+                if let Message::Text(ref s) = msg? {
+                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(s) {
+                        if let Some(service_json) = json.get("ChronicleBroker").cloned() {
+                            if let Ok(RequesterSession::Endpoints(endpoints)) =
+                                serde_json::from_value::<RequesterSession>(service_json.clone())
+                            {
+                                print_endpoints_health(&endpoints);
+                                endpoints_printed = true;
+                            } else if let Ok(FeedSession::Report(feeds)) =
+                                serde_json::from_value::<FeedSession>(service_json)
+                            {
+                                print_feeds_report(&feeds);
+                                feeds_printed = true;
+                            }
+                            if endpoints_printed && feeds_printed {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        } else {
+            println!("Configured API Endpoints:");
+            for url in config.broker_config.api_endpoints.iter() {
+                println!("\t{}", url);
+            }
+        }
     }
     Ok(())
 }
 
+fn print_endpoints_health(endpoints: &Vec<EndpointStatus>) {
+    println!("API Endpoints:");
+    for endpoint in endpoints.iter() {
+        println!("\t{}", endpoint.url);
+        println!(
+            "\t\tcircuit: {}, quarantined: {}, synced: {}, confirmed milestone: {}",
+            endpoint.circuit,
+            endpoint.quarantined,
+            endpoint
+                .healthy
+                .map_or("unknown".to_owned(), |healthy| healthy.to_string()),
+            endpoint
+                .confirmed_milestone_index
+                .map_or("unknown".to_owned(), |index| index.to_string())
+        );
+        println!(
+            "\t\tlatency: {}, error rate: {:.1}%, consecutive failures: {}, requests: {}, failures: {}",
+            endpoint
+                .latency_ms
+                .map_or("unknown".to_owned(), |latency_ms| format!("{:.0}ms", latency_ms)),
+            endpoint.error_rate * 100.0,
+            endpoint.consecutive_failures,
+            endpoint.requests,
+            endpoint.failures
+        );
+    }
+}
+
+fn print_feeds_report(feeds: &Vec<FeedStatus>) {
+    println!("MQTT Feeds:");
+    for feed in feeds.iter() {
+        println!("\t{} ({})", feed.url, feed.topic);
+        println!(
+            "\t\tdelivered: {}, first: {}, only by this feed: {}, missed: {}, delay: {}",
+            feed.delivered,
+            feed.first,
+            feed.exclusive,
+            feed.missed,
+            feed.delay_ms
+                .map_or("unknown".to_owned(), |delay_ms| format!("{:.0}ms", delay_ms))
+        );
+    }
+}
+
+async fn syncer<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
+    let config = VersionedConfig::load(None)?.verify().await?;
+    let (mut stream, _) = connect_async(Url::parse(&format!("ws://{}/", config.websocket_address))?).await?;
+    if let Some(milestone_indexes) = matches.values_of("requeue") {
+        let milestone_indexes = milestone_indexes
+            .map(|milestone_index| milestone_index.parse::<u32>())
+            .collect::<Result<Vec<u32>, _>>()?;
+        // requeue all of them if no milestone index is provided
+        let milestone_indexes = if milestone_indexes.is_empty() {
+            None
+        } else {
+            Some(milestone_indexes)
+        };
+        let message = SocketMsg::Broker(ChronicleBrokerThrough::RequeueUnreachable(milestone_indexes));
+        request(&mut stream, message, "Requeue", reply_timeout(matches)?).await?;
+    } else if matches.is_present("requeue") {
+        let message = SocketMsg::Broker(ChronicleBrokerThrough::RequeueUnreachable(None));
+        request(&mut stream, message, "Requeue", reply_timeout(matches)?).await?;
+    }
+    if matches.is_present("status") || matches.is_present("unreachable") {
+        let timeout = reply_timeout(matches)?;
+        send_request(
+            &mut stream,
+            SocketMsg::Broker(ChronicleBrokerThrough::SyncerStatus),
+            timeout,
+        )
+        .await?;
+        while let Some(msg) = next_message(&mut stream, timeout).await? {
+            if let Message::Text(ref s) = msg? {
+                if let Ok(json) = serde_json::from_str::<serde_json::Value>(s) {
+                    if let Some(service_json) = json.get("ChronicleBroker").cloned() {
+                        if let Ok(SyncerSession::Status(status)) = serde_json::from_value::<SyncerSession>(service_json)
+                        {
+                            match status {
+                                Some(status) => {
+                                    if matches.is_present("status") {
+                                        print_syncer_status(&status);
+                                    }
+                                    if matches.is_present("unreachable") {
+                                        print_unreachable_milestones(&status.unreachable);
+                                    }
+                                }
+                                None => println!("The syncer is not running"),
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_unreachable_milestones(unreachable_milestones: &Vec<UnreachableMilestone>) {
+    if unreachable_milestones.is_empty() {
+        println!("No unreachable milestones");
+        return;
+    }
+    println!("Unreachable milestones:");
+    for unreachable in unreachable_milestones {
+        println!(
+            "\t#{}: {}, attempts: {}, last attempt: {}, next attempt: {}",
+            unreachable.milestone_index,
+            unreachable.reason,
+            unreachable.attempts,
+            unreachable.last_attempt,
+            unreachable.next_attempt
+        );
+    }
+}
+
+fn print_syncer_status(status: &SyncerStatus) {
+    match status.task.as_ref() {
+        Some(SyncerTask::Complete(range)) => println!("Task: Complete, remaining range: {:?}", range),
+        Some(SyncerTask::FillGaps(range)) => println!("Task: FillGaps, remaining range: {:?}", range),
+        Some(SyncerTask::Retry(range)) => println!("Task: Retry, remaining range: {:?}", range),
+        None => println!("Task: idle"),
+    }
+    println!("Highest milestone: {}, next milestone: {}", status.highest, status.next);
+    println!("In-flight milestones: {:?}", status.in_flight);
+    println!("Remaining gaps: {:?}", status.gaps);
+    println!("Remaining synced but unlogged: {:?}", status.synced_but_unlogged);
+    println!(
+        "Synced: {} milestones, {:.1} ms/s, remaining: {} milestones, ETA: {}",
+        status.synced,
+        status.milestones_per_second,
+        status.remaining,
+        status.eta_seconds.map_or("unknown".to_owned(), |eta_seconds| {
+            HumanDuration(std::time::Duration::from_secs(eta_seconds)).to_string()
+        })
+    );
+    println!(
+        "Unreachable milestones: {:?}",
+        status
+            .unreachable
+            .iter()
+            .map(|unreachable| unreachable.milestone_index)
+            .collect::<Vec<u32>>()
+    );
+}
+
 async fn archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
     let config = VersionedConfig::load(None)?.verify().await?;
     match matches.subcommand() {
+        ("import", Some(subcommand)) if subcommand.is_present("status") => import_status(&config).await?,
         ("import", Some(subcommand)) => {
             let dir = subcommand.value_of("directory").unwrap_or("");
             let mut path = PathBuf::from(dir);
@@ -307,18 +800,8 @@ async fn archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
             let (is_url, is_file) = Url::parse(dir)
                 .map(|url| (true, Path::new(url.path()).extension().is_some()))
                 .unwrap_or_else(|_| (false, path.extension().is_some()));
-            let range = subcommand.value_of("range");
-            let range = match range {
-                Some(s) => {
-                    let matches = Regex::new(r"(\d+)\D+(\d+)")?
-                        .captures(s)
-                        .ok_or_else(|| anyhow!("Malformatted range!"));
-                    matches.and_then(|c| {
-                        let start = c.get(1).unwrap().as_str().parse::<u32>()?;
-                        let end = c.get(2).unwrap().as_str().parse::<u32>()?;
-                        Ok(start..end)
-                    })?
-                }
+            let range = match subcommand.value_of("range") {
+                Some(s) => parse_range(s)?,
                 _ => 1..(i32::MAX as u32),
             };
             println!(
@@ -328,40 +811,61 @@ async fn archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
                 is_file,
                 range
             );
-            if is_url {
-                panic!("URL imports are not currently supported!");
-            }
+            let dry_run = subcommand.is_present("dry-run");
             let import_type = if subcommand.is_present("analytics") {
                 ImportType::Analytics
+            } else if dry_run {
+                ImportType::DryRun
             } else {
                 ImportType::All
             };
+            // the (new, identical, divergent) milestones count of the DryRun mode
+            let mut diff_count = (0usize, 0usize, 0usize);
             let sty = ProgressStyle::default_bar()
                 .template(
-                    "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} {msg} ({eta})",
+                    "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} {prefix} {msg} ({eta})",
                 )
                 .progress_chars("##-");
             let mut active_progress_bars: std::collections::HashMap<(u32, u32), ()> = std::collections::HashMap::new();
+            let mut last_report: Option<ImportReport> = None;
             let pb = ProgressBar::new(0);
             pb.set_style(sty.clone());
+            let import_topology = if is_url {
+                BrokerTopology::ImportRemote {
+                    url: Url::parse(dir)?,
+                    resume,
+                    import_range: Some(range),
+                    import_type,
+                }
+            } else {
+                BrokerTopology::Import {
+                    path,
+                    resume,
+                    import_range: Some(range),
+                    import_type,
+                }
+            };
             let (mut stream, _) = connect_async(Url::parse(&format!("ws://{}/", config.websocket_address))?).await?;
-            stream
-                .send(Message::text(serde_json::to_string(&SocketMsg::Broker(
-                    ChronicleBrokerThrough::Topology(BrokerTopology::Import {
-                        path,
-                        resume,
-                        import_range: Some(range),
-                        import_type,
-                    }),
-                ))?))
-                .await?;
-            while let Some(msg) = stream.next().await {
+            let timeout = reply_timeout(subcommand)?;
+            let id = send_request(
+                &mut stream,
+                SocketMsg::Broker(ChronicleBrokerThrough::Topology(import_topology)),
+                timeout,
+            )
+            .await?;
+            while let Some(msg) = next_message(&mut stream, timeout).await? {
                 match msg {
                     Ok(msg) => {
                         match msg {
                             Message::Text(ref s) => {
                                 if let Ok(json) = serde_json::from_str::<serde_json::Value>(s) {
-                                    if let Some(service_json) = json.get("ChronicleBroker").cloned() {
+                                    if let Ok(reply) = serde_json::from_value::<ControlReply>(json.clone()) {
+                                        if reply.id == id {
+                                            if let Err(e) = reply.result {
+                                                bail!("Import failed: {}", e);
+                                            }
+                                        }
+                                    } else if let Some(service_json) = json.get("ChronicleBroker").cloned() {
                                         if let Ok(session) =
                                             serde_json::from_value::<ImporterSession>(service_json.clone())
                                         {
@@ -379,6 +883,8 @@ async fn archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
                                                         let skipped_or_imported;
                                                         if skipped {
                                                             skipped_or_imported = "skipped"
+                                                        } else if dry_run {
+                                                            skipped_or_imported = "compared"
                                                         } else {
                                                             skipped_or_imported = "imported"
                                                         }
@@ -393,6 +899,8 @@ async fn archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
                                                         let skipped_or_imported;
                                                         if skipped {
                                                             skipped_or_imported = "skipped"
+                                                        } else if dry_run {
+                                                            skipped_or_imported = "compared"
                                                         } else {
                                                             skipped_or_imported = "imported"
                                                         }
@@ -416,8 +924,44 @@ async fn archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
                                                 ImporterSession::PathError { path, msg } => {
                                                     pb.println(format!("ErrorPath: {:?}, msg: {:?}", path, msg))
                                                 }
+                                                ImporterSession::UrlError { url, msg } => {
+                                                    pb.println(format!("ErrorUrl: {}, msg: {:?}", url, msg))
+                                                }
+                                                ImporterSession::Diff {
+                                                    from_ms,
+                                                    to_ms,
+                                                    milestone_index,
+                                                    diff,
+                                                } => match diff {
+                                                    MilestoneDiff::New => diff_count.0 += 1,
+                                                    MilestoneDiff::Identical => diff_count.1 += 1,
+                                                    MilestoneDiff::Divergent {
+                                                        milestone,
+                                                        missing_messages,
+                                                        divergent_messages,
+                                                    } => {
+                                                        diff_count.2 += 1;
+                                                        pb.println(format!(
+                                                            "LogFile: {}to{}.log divergent #{}: milestone differs: {}, missing messages: {}, divergent messages: {}",
+                                                            from_ms, to_ms, milestone_index, milestone, missing_messages, divergent_messages
+                                                        ));
+                                                    }
+                                                },
+                                                ImporterSession::Report(report) => {
+                                                    pb.set_prefix(import_report_line(&report));
+                                                    last_report.replace(report);
+                                                }
                                                 ImporterSession::Close => {
                                                     pb.finish_with_message("done");
+                                                    if let Some(report) = last_report.as_ref() {
+                                                        println!("Import report: {}", import_report_line(report));
+                                                    }
+                                                    if dry_run {
+                                                        println!(
+                                                            "New milestones: {}, identical milestones: {}, divergent milestones: {}",
+                                                            diff_count.0, diff_count.1, diff_count.2
+                                                        );
+                                                    }
                                                     break;
                                                 }
                                             }
@@ -445,6 +989,7 @@ async fn archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
                 }
             }
         }
+        ("export", Some(subcommand)) => export_archive(subcommand, config).await?,
         ("cleanup", Some(matches)) => cleanup_archive(matches).await?,
         ("validate", Some(_matches)) => validate_archive().await?,
         _ => (),
@@ -452,6 +997,159 @@ async fn archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn import_status(config: &Config) -> anyhow::Result<()> {
+    let dir_path = ImportCheckpoint::dir_path(config.broker_config.logs_dir.as_ref());
+    let mut checkpoints = Vec::new();
+    if let Ok(mut dir_entry) = tokio::fs::read_dir(&dir_path).await {
+        while let Some(entry) = dir_entry.next_entry().await? {
+            let path = entry.path();
+            if path.extension().map_or(false, |ext| ext == "json") {
+                match serde_json::from_slice::<ImportCheckpoint>(&tokio::fs::read(&path).await?) {
+                    Ok(checkpoint) => checkpoints.push(checkpoint),
+                    Err(e) => println!("Invalid checkpoint: {:?}, error: {}", path, e),
+                }
+            }
+        }
+    }
+    if checkpoints.is_empty() {
+        println!("No import checkpoints in: {:?}", dir_path);
+        return Ok(());
+    }
+    checkpoints.sort_by(|a, b| (&a.keyspace, a.from_ms).cmp(&(&b.keyspace, b.from_ms)));
+    println!("Import checkpoints in: {:?}", dir_path);
+    for checkpoint in checkpoints {
+        let percentage = if checkpoint.log_file_size > 0 {
+            checkpoint.offset as f64 * 100.0 / checkpoint.log_file_size as f64
+        } else {
+            100.0
+        };
+        println!(
+            "\t{} {:?} {}to{}.log: {}, milestone index: {}, offset: {}/{} ({:.1}%)",
+            checkpoint.keyspace,
+            checkpoint.import_type,
+            checkpoint.from_ms,
+            checkpoint.to_ms,
+            if checkpoint.done { "done" } else { "in progress" },
+            checkpoint
+                .milestone_index
+                .map_or("none".to_owned(), |milestone_index| milestone_index.to_string()),
+            checkpoint.offset,
+            checkpoint.log_file_size,
+            percentage
+        );
+    }
+    Ok(())
+}
+
+fn import_report_line(report: &ImportReport) -> String {
+    format!(
+        "{} milestones, {:.1} ms/s, {}/s, ETA: {}, failures: {}, files: {} in progress, {} pending",
+        report.milestones,
+        report.milestones_per_second,
+        HumanBytes(report.bytes_per_second as u64),
+        report.eta_seconds.map_or("unknown".to_owned(), |eta_seconds| {
+            HumanDuration(std::time::Duration::from_secs(eta_seconds)).to_string()
+        }),
+        report.failures,
+        report.in_progress_files,
+        report.pending_files
+    )
+}
+
+fn parse_range(s: &str) -> anyhow::Result<Range<u32>> {
+    let matches = Regex::new(r"(\d+)\D+(\d+)")?
+        .captures(s)
+        .ok_or_else(|| anyhow!("Malformatted range!"));
+    matches.and_then(|c| {
+        let start = c.get(1).unwrap().as_str().parse::<u32>()?;
+        let end = c.get(2).unwrap().as_str().parse::<u32>()?;
+        Ok(start..end)
+    })
+}
+
+async fn export_archive<'a>(matches: &ArgMatches<'a>, config: Config) -> anyhow::Result<()> {
+    let mut path = PathBuf::from(matches.value_of("directory").unwrap_or("export"));
+    if path.is_relative() {
+        if let Some(logs_dir) = config.broker_config.logs_dir.as_ref() {
+            path = Path::new(&logs_dir).join(path);
+        }
+    }
+    let export_range = parse_range(matches.value_of("range").ok_or_else(|| anyhow!("No range provided!"))?)?;
+    anyhow::ensure!(!export_range.is_empty(), "Empty export range!");
+    println!("Path: {}, range: {:?}", path.to_string_lossy(), export_range);
+    let sty = ProgressStyle::default_bar()
+        .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg} ({eta})")
+        .progress_chars("##-");
+    let pb = ProgressBar::new((export_range.end - export_range.start) as u64);
+    pb.set_style(sty);
+    let (mut stream, _) = connect_async(Url::parse(&format!("ws://{}/", config.websocket_address))?).await?;
+    let timeout = reply_timeout(matches)?;
+    let id = send_request(
+        &mut stream,
+        SocketMsg::Broker(ChronicleBrokerThrough::Topology(BrokerTopology::Export {
+            path,
+            export_range: export_range.clone(),
+        })),
+        timeout,
+    )
+    .await?;
+    while let Some(msg) = next_message(&mut stream, timeout).await? {
+        match msg {
+            Ok(Message::Text(ref s)) => {
+                if let Ok(reply) = serde_json::from_str::<ControlReply>(s) {
+                    if let (true, Err(e)) = (reply.id == id, reply.result) {
+                        pb.abandon();
+                        bail!("Export failed: {}", e);
+                    }
+                } else if let Some(session) = serde_json::from_str::<serde_json::Value>(s)
+                    .ok()
+                    .and_then(|json| json.get("ChronicleBroker").cloned())
+                    .and_then(|json| serde_json::from_value::<ExporterSession>(json).ok())
+                {
+                    match session {
+                        ExporterSession::ProgressBar {
+                            from_ms,
+                            to_ms,
+                            milestone_index,
+                            skipped,
+                        } => {
+                            if (from_ms..to_ms) != export_range {
+                                continue;
+                            }
+                            let skipped_or_exported = if skipped { "skipped" } else { "exported" };
+                            pb.set_message(format!("{} #{}", skipped_or_exported, milestone_index));
+                            pb.set_position((milestone_index + 1 - from_ms) as u64);
+                        }
+                        ExporterSession::Finish { from_ms, to_ms, msg } => {
+                            if (from_ms..to_ms) != export_range {
+                                continue;
+                            }
+                            pb.finish_with_message(msg);
+                            break;
+                        }
+                        ExporterSession::PathError { path, msg } => {
+                            pb.println(format!("ErrorPath: {:?}, msg: {:?}", path, msg));
+                            break;
+                        }
+                    }
+                }
+            }
+            Ok(Message::Close(c)) => {
+                if let Some(c) = c {
+                    println!("Closed connection: {}", c);
+                }
+                break;
+            }
+            Ok(_) => (),
+            Err(e) => {
+                println!("Error received from Chronicle: {}", e);
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
 async fn cleanup_archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
     let backup_logs = !matches.is_present("no-backup");
     let val_level = matches
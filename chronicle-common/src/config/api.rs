@@ -2,14 +2,178 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
+use std::collections::HashSet;
+use url::Url;
+
 /// Configuration for the Chronicle API
-#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq, Clone)]
-pub struct ApiConfig {}
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// The address the API listens on
+    #[serde(deserialize_with = "super::deserialize_socket_addr")]
+    pub listen_address: SocketAddr,
+    /// The TLS certificate and key, the API is served over plain http without them
+    pub tls: Option<TlsConfig>,
+    /// The origins allowed by the CORS policy, `*` allows any origin
+    pub allowed_origins: Vec<String>,
+    /// The max page size of the paged routes, larger requested page sizes get capped
+    pub max_page_size: usize,
+    /// The timeout in seconds of the storage queries made to serve a request
+    pub request_timeout_secs: u64,
+    /// The route groups served by the API
+    pub route_groups: HashSet<ApiRouteGroup>,
+    /// The authentication of the API requests, the API is open to anyone without it
+    pub auth: Option<AuthConfig>,
+    /// The rate limit of every client (API key, JWT subject or ip address), unlimited without it
+    pub rate_limit: Option<RateLimitConfig>,
+    /// The max number of partitions a paged request can scan, its page gets cut short beyond it
+    pub max_query_partitions: usize,
+    /// The max number of rows a paged request can scan, its page gets cut short beyond it
+    pub max_query_rows: usize,
+    /// The number of immutable responses kept in the response cache, zero disables it
+    pub cache_capacity: usize,
+    /// The duration in seconds a response is cached, which is also its Cache-Control max-age
+    pub cache_ttl_secs: u64,
+}
+
+/// The token bucket rate limit of the API clients. Every request costs a token, and the paged requests
+/// cost an extra token for every additional partition they scanned.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct RateLimitConfig {
+    /// The tokens refilled every second
+    pub requests_per_sec: u32,
+    /// The capacity of the bucket, which allows bursts of requests
+    pub burst: u32,
+}
+
+/// TLS configuration of the API
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct TlsConfig {
+    /// The path to the PEM encoded certificate chain
+    pub cert_path: String,
+    /// The path to the PEM encoded private key
+    pub key_path: String,
+}
+
+/// The groups of API routes, which can be enabled separately
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum ApiRouteGroup {
+    /// The keyspace info, metrics and service routes
+    Info,
+    /// The import, syncer, sync and unreachable routes
+    Sync,
+    /// The messages routes
+    Messages,
+    /// The outputs and addresses routes
+    Outputs,
+    /// The transactions routes
+    Transactions,
+    /// The milestones routes
+    Milestones,
+    /// The analytics routes
+    Analytics,
+    /// The admin routes, which alter the broker and scylla topologies and roll back the config
+    Admin,
+}
+
+/// The authentication of the API requests, either with an API key (`X-API-Key` header)
+/// or with a bearer token (`Authorization: Bearer <token>`), which is an API key or a JWT
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct AuthConfig {
+    /// The static API keys
+    pub api_keys: Vec<ApiKey>,
+    /// The HS256 secret the JWTs are signed with, JWTs are rejected without it
+    pub jwt_secret: Option<String>,
+    /// The scope of the requests without credentials, which get rejected without it
+    pub anonymous_scope: Option<ApiScope>,
+}
+
+/// A static API key
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct ApiKey {
+    /// The name of the API key holder
+    pub name: String,
+    /// The API key
+    pub key: String,
+    /// The scope of the API key
+    pub scope: ApiScope,
+}
+
+/// The keyspaces and permissions granted to a client. A JWT holds its scope in the
+/// `keyspaces` and `permissions` claims.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
+pub struct ApiScope {
+    /// The accessible keyspaces, `*` grants all of them
+    pub keyspaces: HashSet<String>,
+    /// The granted permissions
+    pub permissions: HashSet<ApiPermission>,
+}
+
+impl ApiScope {
+    /// Whether the scope grants the permission, on the keyspace if any
+    pub fn allows(&self, permission: ApiPermission, keyspace: Option<&str>) -> bool {
+        self.permissions.contains(&permission)
+            && keyspace.map_or(true, |keyspace| {
+                self.keyspaces.contains("*") || self.keyspaces.contains(keyspace)
+            })
+    }
+}
+
+/// The permissions required by the API routes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum ApiPermission {
+    /// Read the tangle data of the keyspaces (messages, outputs, transactions, milestones)
+    Data,
+    /// Read the analytics of the keyspaces
+    Analytics,
+    /// Read the metrics and the service, and monitor the sync and imports
+    Admin,
+}
+
+impl ApiRouteGroup {
+    /// All the route groups
+    pub const ALL: [ApiRouteGroup; 8] = [
+        ApiRouteGroup::Info,
+        ApiRouteGroup::Sync,
+        ApiRouteGroup::Messages,
+        ApiRouteGroup::Outputs,
+        ApiRouteGroup::Transactions,
+        ApiRouteGroup::Milestones,
+        ApiRouteGroup::Analytics,
+        ApiRouteGroup::Admin,
+    ];
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            listen_address: ([127, 0, 0, 1], 8000).into(),
+            tls: None,
+            allowed_origins: vec!["*".to_owned()],
+            max_page_size: 1000,
+            request_timeout_secs: 30,
+            // the admin routes have to be enabled explicitly
+            route_groups: ApiRouteGroup::ALL
+                .iter()
+                .copied()
+                .filter(|route_group| *route_group != ApiRouteGroup::Admin)
+                .collect(),
+            auth: None,
+            rate_limit: None,
+            max_query_partitions: 64,
+            max_query_rows: 50_000,
+            cache_capacity: 10_000,
+            cache_ttl_secs: 60 * 60,
+        }
+    }
+}
 
 impl ApiConfig {
-    /// Verify that the api config is valid
-    pub async fn verify(&mut self) -> anyhow::Result<()> {
-        // TODO
-        Ok(())
+    /// Whether the allowed origin is "*", or an origin the way browsers send it, ie without any trailing slash
+    pub(super) fn valid_origin(origin: &str) -> bool {
+        origin == "*"
+            || Url::parse(origin)
+                .map(|url| matches!(url.scheme(), "http" | "https") && url.origin().ascii_serialization() == origin)
+                .unwrap_or_default()
     }
 }
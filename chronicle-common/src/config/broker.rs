@@ -49,6 +49,53 @@ pub struct BrokerConfig {
     pub logs_dir: Option<String>,
     /// The maximum log file size
     pub max_log_size: Option<u64>,
+    /// The target write latency in milliseconds of the importers batches and of the scylla nodes,
+    /// above which the importers reduce their concurrency
+    pub import_target_latency_ms: Option<u64>,
+    /// The initial backoff in seconds before retrying an unreachable milestone,
+    /// doubled after every failed attempt
+    pub unreachable_retry_backoff_secs: Option<u64>,
+    /// The id of this chronicle instance, recorded in the sync table as synced_by/logged_by,
+    /// and used to own the sync leases when several instances share the same keyspace
+    pub chronicle_id: Option<u8>,
+    /// The duration in seconds of the sync leases, which the syncer claims in order to sync disjoint
+    /// milestone ranges with the other instances, None to disable the leases.
+    /// Enabling them requires a chronicle_id which is unique to each instance. The unlogged ranges are archived
+    /// by the syncer under the same leases, while the live milestones are archived by every instance
+    pub sync_lease_secs: Option<u64>,
+    /// The number of milestones covered by a single sync lease
+    pub sync_lease_range: Option<u32>,
+    /// The number of consecutive failures which open the circuit breaker of an api endpoint
+    pub endpoint_failure_threshold: Option<u32>,
+    /// The duration in seconds an api endpoint circuit stays open, before a half open probe is allowed
+    pub endpoint_open_secs: Option<u64>,
+    /// The interval in seconds between two consecutive info probes of the api endpoints
+    pub endpoint_probe_interval_secs: Option<u64>,
+    /// The api endpoints path (relative to the api endpoint) which serves all the messages referenced by a milestone,
+    /// where `{index}` is replaced by the milestone index (ie "milestones/{index}/cone"), None to fetch the milestone
+    /// cones message by message
+    pub milestone_cone_path: Option<String>,
+    /// The minimum PoW score of the network, the messages served by the api endpoints below it are rejected,
+    /// None to skip the PoW check
+    pub min_pow_score: Option<u64>,
+    /// The duration in seconds an api endpoint which served invalid data is excluded from the selection
+    pub endpoint_quarantine_secs: Option<u64>,
+    /// The duration in seconds without any message after which a MQTT feed is considered stale and gets reconnected
+    pub mqtt_stale_feed_secs: Option<u64>,
+    /// The initial backoff in seconds before reconnecting a MQTT feed, doubled after every consecutive reconnect
+    pub mqtt_reconnect_backoff_secs: Option<u64>,
+    /// The duration in seconds the collectors wait for the redundant feeds to deliver a message,
+    /// before reporting the feeds which missed it
+    pub feed_divergence_window_secs: Option<u64>,
+    /// The websocket event streams (ws:// or wss://) the broker will use as feed sources separated by type,
+    /// along with the MQTT brokers. A file:// url replays the feeds of a recording instead,
+    /// optionally accelerated with a `speed` query parameter
+    pub event_streams: Option<HashMap<MqttType, HashSet<Url>>>,
+    /// The file the broker records the feeds and the api endpoints traffic into, for later replays
+    pub record_path: Option<String>,
+    /// The recording served by a mock api endpoint on the loopback interface, which joins the api endpoints
+    /// so the broker can run offline along with the replayed event streams
+    pub mock_endpoint: Option<String>,
 }
 
 /// Enumerated MQTT feed source type
@@ -58,6 +105,17 @@ pub enum MqttType {
     Messages,
     /// Receives Referenced notifications
     MessagesReferenced,
+    /// Receives the latest milestones, used to monitor the feed
+    LatestMilestone,
+}
+
+impl MqttType {
+    /// All the MQTT feed source types
+    pub const ALL: [MqttType; 3] = [
+        MqttType::Messages,
+        MqttType::MessagesReferenced,
+        MqttType::LatestMilestone,
+    ];
 }
 
 impl Default for BrokerConfig {
@@ -80,6 +138,10 @@ impl Default for BrokerConfig {
                 MqttType::MessagesReferenced => hashset![
                     url::Url::parse("tcp://api.hornet-0.testnet.chrysalis2.com:1883").unwrap(),
                     url::Url::parse("tcp://api.hornet-1.testnet.chrysalis2.com:1883").unwrap(),
+                ],
+                MqttType::LatestMilestone => hashset![
+                    url::Url::parse("tcp://api.hornet-0.testnet.chrysalis2.com:1883").unwrap(),
+                    url::Url::parse("tcp://api.hornet-1.testnet.chrysalis2.com:1883").unwrap(),
                 ]
             },
             api_endpoints: hashset![
@@ -90,12 +152,30 @@ impl Default for BrokerConfig {
             sync_range: Some(Default::default()),
             logs_dir: Some("chronicle/logs/".to_owned()),
             max_log_size: Some(4 * 1024 * 1024 * 1024),
+            import_target_latency_ms: Some(200),
+            unreachable_retry_backoff_secs: Some(60),
+            chronicle_id: Some(0),
+            sync_lease_secs: None,
+            sync_lease_range: Some(10000),
+            endpoint_failure_threshold: Some(5),
+            endpoint_open_secs: Some(30),
+            endpoint_probe_interval_secs: Some(30),
+            milestone_cone_path: None,
+            min_pow_score: Some(4000),
+            endpoint_quarantine_secs: Some(60 * 60),
+            mqtt_stale_feed_secs: Some(60),
+            mqtt_reconnect_backoff_secs: Some(5),
+            feed_divergence_window_secs: Some(10),
+            event_streams: None,
+            record_path: None,
+            mock_endpoint: None,
         }
     }
 }
 
 impl BrokerConfig {
-    /// Verify that the broker's config is valid
+    /// Verify that the MQTT brokers and api endpoints of the broker's config are usable,
+    /// the rest of it is checked by `Config::validate`
     pub async fn verify(&mut self) -> anyhow::Result<()> {
         for mqtt_broker in self.mqtt_brokers.values().flatten() {
             let random_id: u64 = rand::random();
@@ -116,12 +196,7 @@ impl BrokerConfig {
         for endpoint in self.api_endpoints.iter() {
             Self::verify_endpoint(&client, endpoint).await?
         }
-        let sync_range = self.sync_range.get_or_insert_with(|| SyncRange::default());
-        if sync_range.from == 0 || sync_range.to == 0 {
-            bail!("Error verifying sync from/to, zero provided!\nPlease provide non-zero milestone index");
-        } else if sync_range.from >= sync_range.to {
-            bail!("Error verifying sync from/to, greater or equal provided!\nPlease provide lower \"Sync range from\" milestone index");
-        }
+        self.sync_range.get_or_insert_with(|| SyncRange::default());
         Ok(())
     }
     /// Adjust IOTA api endpoint url and ensure it's correct or return None otherwise
@@ -0,0 +1,175 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use std::collections::{
+    BTreeSet,
+    HashSet,
+};
+use url::Url;
+
+/// A change between the running config and a reloaded one, which can be applied live
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ConfigChange {
+    /// Add the MQTT broker feed source of the topic
+    AddMqttBroker(MqttType, Url),
+    /// Remove the MQTT broker feed source of the topic
+    RemoveMqttBroker(MqttType, Url),
+    /// Add the websocket event stream feed source
+    AddEventStream(Url),
+    /// Remove the websocket event stream feed source
+    RemoveEventStream(Url),
+    /// Add the api endpoint of the requesters
+    AddApiEndpoint(Url),
+    /// Remove the api endpoint of the requesters
+    RemoveApiEndpoint(Url),
+    /// Update the range of milestones to sync
+    SyncRange(SyncRange),
+    /// Replace the alert requests, which are read whenever an alert is sent
+    AlertRequests,
+}
+
+/// The differences between the running config and a reloaded one
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ConfigDiff {
+    /// The changes which can be applied live
+    pub hot: Vec<ConfigChange>,
+    /// The changed fields (ie `api_config.listen_address`) which only take effect after a restart
+    pub restart: Vec<String>,
+}
+
+impl ConfigDiff {
+    /// Whether the configs are identical
+    pub fn is_empty(&self) -> bool {
+        self.hot.is_empty() && self.restart.is_empty()
+    }
+}
+
+impl Config {
+    /// Undo the change in this reloaded config, restoring the values of the previous config
+    pub fn revert(&mut self, change: &ConfigChange, previous: &Config) {
+        let (new, old) = (&mut self.broker_config, &previous.broker_config);
+        match change {
+            ConfigChange::AddMqttBroker(mqtt_type, url) => {
+                if let Some(brokers) = new.mqtt_brokers.get_mut(mqtt_type) {
+                    brokers.remove(url);
+                }
+            }
+            ConfigChange::RemoveMqttBroker(mqtt_type, url) => {
+                new.mqtt_brokers.entry(*mqtt_type).or_default().insert(url.clone());
+            }
+            ConfigChange::AddEventStream(url) | ConfigChange::RemoveEventStream(url) => {
+                // the event streams feed all the topics, so the url gets back its previous topics
+                let streams = new.event_streams.get_or_insert_with(HashMap::new);
+                for mqtt_type in MqttType::ALL {
+                    let previously = old
+                        .event_streams
+                        .as_ref()
+                        .and_then(|streams| streams.get(&mqtt_type))
+                        .map_or(false, |urls| urls.contains(url));
+                    if previously {
+                        streams.entry(mqtt_type).or_default().insert(url.clone());
+                    } else if let Some(urls) = streams.get_mut(&mqtt_type) {
+                        urls.remove(url);
+                    }
+                }
+            }
+            ConfigChange::AddApiEndpoint(url) => {
+                new.api_endpoints.remove(url);
+            }
+            ConfigChange::RemoveApiEndpoint(url) => {
+                new.api_endpoints.insert(url.clone());
+            }
+            ConfigChange::SyncRange(_) => new.sync_range = old.sync_range,
+            ConfigChange::AlertRequests => self.alert_config = previous.alert_config.clone(),
+        }
+    }
+    /// Diff this running config against the reloaded one
+    pub fn diff(&self, reloaded: &Config) -> ConfigDiff {
+        let (old, new) = (&self.broker_config, &reloaded.broker_config);
+        let mut hot = Vec::new();
+        let empty = HashSet::new();
+        for mqtt_type in MqttType::ALL {
+            let old_brokers = old.mqtt_brokers.get(&mqtt_type).unwrap_or(&empty);
+            let new_brokers = new.mqtt_brokers.get(&mqtt_type).unwrap_or(&empty);
+            hot.extend(
+                sorted_difference(new_brokers, old_brokers).map(|url| ConfigChange::AddMqttBroker(mqtt_type, url)),
+            );
+            hot.extend(
+                sorted_difference(old_brokers, new_brokers).map(|url| ConfigChange::RemoveMqttBroker(mqtt_type, url)),
+            );
+        }
+        // the event streams feed all the topics
+        let old_streams = event_stream_urls(old);
+        let new_streams = event_stream_urls(new);
+        hot.extend(sorted_difference(&new_streams, &old_streams).map(ConfigChange::AddEventStream));
+        hot.extend(sorted_difference(&old_streams, &new_streams).map(ConfigChange::RemoveEventStream));
+        hot.extend(sorted_difference(&new.api_endpoints, &old.api_endpoints).map(ConfigChange::AddApiEndpoint));
+        hot.extend(sorted_difference(&old.api_endpoints, &new.api_endpoints).map(ConfigChange::RemoveApiEndpoint));
+        if old.sync_range != new.sync_range {
+            hot.push(ConfigChange::SyncRange(new.sync_range.unwrap_or_default()));
+        }
+        if self.alert_config != reloaded.alert_config {
+            hot.push(ConfigChange::AlertRequests);
+        }
+        // anything else needs a restart
+        let mut patched = reloaded.broker_config.clone();
+        patched.mqtt_brokers = old.mqtt_brokers.clone();
+        patched.event_streams = old.event_streams.clone();
+        patched.api_endpoints = old.api_endpoints.clone();
+        patched.sync_range = old.sync_range;
+        let mut restart = Vec::new();
+        changed_fields(
+            "websocket_address",
+            &self.websocket_address,
+            &reloaded.websocket_address,
+            &mut restart,
+        );
+        changed_fields(
+            "storage_config",
+            &self.storage_config,
+            &reloaded.storage_config,
+            &mut restart,
+        );
+        changed_fields("api_config", &self.api_config, &reloaded.api_config, &mut restart);
+        changed_fields("broker_config", old, &patched, &mut restart);
+        changed_fields(
+            "historical_config_path",
+            &self.historical_config_path,
+            &reloaded.historical_config_path,
+            &mut restart,
+        );
+        ConfigDiff { hot, restart }
+    }
+}
+
+/// The urls of `a` which are not in `b`, in order
+fn sorted_difference(a: &HashSet<Url>, b: &HashSet<Url>) -> impl Iterator<Item = Url> {
+    a.difference(b).cloned().collect::<BTreeSet<_>>().into_iter()
+}
+
+fn event_stream_urls(broker_config: &BrokerConfig) -> HashSet<Url> {
+    broker_config
+        .event_streams
+        .iter()
+        .flat_map(|streams| streams.values().flatten().cloned())
+        .collect()
+}
+
+/// Push the name of the section if it changed, or the names of its changed fields if it's a struct
+fn changed_fields<T: Serialize + PartialEq>(section: &str, old: &T, new: &T, fields: &mut Vec<String>) {
+    if old == new {
+        return;
+    }
+    match (serde_json::to_value(old), serde_json::to_value(new)) {
+        (Ok(serde_json::Value::Object(old)), Ok(serde_json::Value::Object(new))) => {
+            let keys = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+            fields.extend(
+                keys.into_iter()
+                    .filter(|key| old.get(*key) != new.get(*key))
+                    .map(|key| format!("{}.{}", section, key)),
+            );
+        }
+        _ => fields.push(section.to_owned()),
+    }
+}
@@ -0,0 +1,324 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use serde::de::DeserializeOwned;
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+};
+use url::Url;
+
+/// A config file wrapped with the version of its format
+#[derive(Serialize, Deserialize)]
+struct Versioned<T> {
+    version: u32,
+    config: T,
+}
+
+/// The config format of version 1.
+/// Its sections are frozen snapshots, so the later changes to the current sections can't break the migrations.
+#[derive(Serialize, Deserialize)]
+struct ConfigV1 {
+    #[serde(deserialize_with = "deserialize_socket_addr")]
+    websocket_address: SocketAddr,
+    storage_config: StorageConfigV1,
+    api_config: ApiConfigV1,
+    broker_config: BrokerConfigV1,
+}
+
+/// The config format of version 2, which introduced the historical config path
+#[derive(Serialize, Deserialize)]
+struct ConfigV2 {
+    #[serde(deserialize_with = "deserialize_socket_addr")]
+    websocket_address: SocketAddr,
+    storage_config: StorageConfigV1,
+    api_config: ApiConfigV1,
+    broker_config: BrokerConfigV1,
+    historical_config_path: String,
+}
+
+/// The storage config of the versions 1 and 2
+#[derive(Serialize, Deserialize)]
+struct StorageConfigV1 {
+    keyspaces: Vec<KeyspaceConfig>,
+    #[serde(deserialize_with = "deserialize_socket_addr")]
+    listen_address: SocketAddr,
+    thread_count: ThreadCount,
+    reporter_count: u8,
+    local_datacenter: String,
+    #[serde(deserialize_with = "deserialize_socket_addr_collected")]
+    nodes: HashSet<SocketAddr>,
+    #[serde(default)]
+    partition_config: PartitionConfig,
+}
+
+/// The api config of the versions 1 and 2, which had no settings yet
+#[derive(Serialize, Deserialize)]
+struct ApiConfigV1 {}
+
+/// The broker config of the versions 1 and 2
+#[derive(Serialize, Deserialize)]
+struct BrokerConfigV1 {
+    #[serde(deserialize_with = "deserialize_socket_addr")]
+    websocket_address: SocketAddr,
+    mqtt_brokers: HashMap<MqttType, HashSet<Url>>,
+    mqtt_stream_capacity: usize,
+    api_endpoints: HashSet<Url>,
+    retries_per_endpoint: usize,
+    retries_per_query: usize,
+    collector_count: u8,
+    requester_count: u8,
+    request_timeout_secs: u64,
+    parallelism: u8,
+    sync_range: Option<SyncRange>,
+    complete_gaps_interval_secs: u64,
+    logs_dir: Option<String>,
+    max_log_size: Option<u64>,
+}
+
+impl From<StorageConfigV1> for StorageConfig {
+    fn from(config: StorageConfigV1) -> Self {
+        Self {
+            keyspaces: config.keyspaces,
+            listen_address: config.listen_address,
+            thread_count: config.thread_count,
+            reporter_count: config.reporter_count,
+            local_datacenter: config.local_datacenter,
+            nodes: config.nodes,
+            partition_config: config.partition_config,
+        }
+    }
+}
+
+/// The api settings got introduced with their defaults
+impl From<ApiConfigV1> for ApiConfig {
+    fn from(_: ApiConfigV1) -> Self {
+        Self::default()
+    }
+}
+
+/// The broker settings introduced since take their defaults
+impl From<BrokerConfigV1> for BrokerConfig {
+    fn from(config: BrokerConfigV1) -> Self {
+        Self {
+            websocket_address: config.websocket_address,
+            mqtt_brokers: config.mqtt_brokers,
+            mqtt_stream_capacity: config.mqtt_stream_capacity,
+            api_endpoints: config.api_endpoints,
+            retries_per_endpoint: config.retries_per_endpoint,
+            retries_per_query: config.retries_per_query,
+            collector_count: config.collector_count,
+            requester_count: config.requester_count,
+            request_timeout_secs: config.request_timeout_secs,
+            parallelism: config.parallelism,
+            sync_range: config.sync_range,
+            complete_gaps_interval_secs: config.complete_gaps_interval_secs,
+            logs_dir: config.logs_dir,
+            max_log_size: config.max_log_size,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ConfigV1> for ConfigV2 {
+    fn from(config: ConfigV1) -> Self {
+        Self {
+            websocket_address: config.websocket_address,
+            storage_config: config.storage_config,
+            api_config: config.api_config,
+            broker_config: config.broker_config,
+            historical_config_path: HISTORICAL_CONFIG_PATH.to_owned(),
+        }
+    }
+}
+
+/// Version 3 introduced the alert config
+impl From<ConfigV2> for Config {
+    fn from(config: ConfigV2) -> Self {
+        Self {
+            websocket_address: config.websocket_address,
+            storage_config: config.storage_config.into(),
+            api_config: config.api_config.into(),
+            broker_config: config.broker_config.into(),
+            historical_config_path: config.historical_config_path,
+            alert_config: Default::default(),
+        }
+    }
+}
+
+/// Parse the config of the given format version, and migrate it through the chain of the next versions.
+/// Returns the parsed config, formatted like the migrated one, along with the migrated config.
+/// **Must be extended with each change to the config format.**
+fn migrate(contents: &str, from_version: u32) -> anyhow::Result<(String, Config)> {
+    match from_version {
+        1 => parse::<ConfigV1>(contents).map(|(previous, config)| (previous, ConfigV2::from(config).into())),
+        2 => parse::<ConfigV2>(contents).map(|(previous, config)| (previous, config.into())),
+        CURRENT_VERSION => parse::<Config>(contents),
+        _ => bail!(
+            "Unable to migrate the config file of version {} to the version {}",
+            from_version,
+            CURRENT_VERSION
+        ),
+    }
+}
+
+fn parse<T: Serialize + DeserializeOwned>(contents: &str) -> anyhow::Result<(String, T)> {
+    let versioned = ron::de::from_str::<Versioned<T>>(contents)?;
+    let formatted = ron::ser::to_string_pretty(&versioned, ron::ser::PrettyConfig::default())?;
+    Ok((formatted, versioned.config))
+}
+
+/// The migration of a config file to the current version
+#[derive(Debug, Clone)]
+pub struct ConfigMigration {
+    /// The config file path
+    pub path: PathBuf,
+    /// The path of the backup of the config file, if it got migrated in place
+    pub backup_path: Option<PathBuf>,
+    /// The version of the config file
+    pub from_version: u32,
+    /// The config file as it was, formatted like the migrated one
+    pub previous: String,
+    /// The migrated config file
+    pub migrated: String,
+}
+
+impl ConfigMigration {
+    /// Migrate the config file contents of the given version to the current version
+    pub(super) fn new(path: PathBuf, contents: &str, from_version: u32) -> anyhow::Result<Self> {
+        let (previous, config) = migrate(contents, from_version)?;
+        let migrated = ron::ser::to_string_pretty(&VersionedConfig::new(config), ron::ser::PrettyConfig::default())?;
+        Ok(Self {
+            path,
+            backup_path: None,
+            from_version,
+            previous,
+            migrated,
+        })
+    }
+
+    /// Whether the config file is already of the current version
+    pub fn is_current(&self) -> bool {
+        self.from_version == CURRENT_VERSION
+    }
+
+    /// Back the config file up next to it as `<file>.v<version>.bak`, then overwrite it with the migrated config
+    pub fn apply(&mut self) -> anyhow::Result<()> {
+        if self.is_current() {
+            return Ok(());
+        }
+        let mut backup_path = self.path.clone().into_os_string();
+        backup_path.push(format!(".v{}.bak", self.from_version));
+        let backup_path = PathBuf::from(backup_path);
+        std::fs::copy(&self.path, &backup_path)?;
+        std::fs::write(&self.path, &self.migrated)?;
+        self.backup_path.replace(backup_path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A config file of version 1, as it was written back then
+    const CONFIG_V1: &str = r#"(
+    version: 1,
+    config: (
+        websocket_address: "127.0.0.1:8081",
+        storage_config: (
+            keyspaces: [
+                (
+                    name: "permanode",
+                    data_centers: {
+                        "USA": (
+                            replication_factor: 2,
+                        ),
+                    },
+                ),
+            ],
+            listen_address: "127.0.0.1:8080",
+            thread_count: CoreMultiple(1),
+            reporter_count: 1,
+            local_datacenter: "USA",
+            nodes: ["127.0.0.1:9042"],
+        ),
+        api_config: (),
+        broker_config: (
+            websocket_address: "127.0.0.1:9000",
+            mqtt_brokers: {
+                Messages: ["tcp://api.hornet-0.testnet.chrysalis2.com:1883"],
+                MessagesReferenced: ["tcp://api.hornet-0.testnet.chrysalis2.com:1883"],
+            },
+            mqtt_stream_capacity: 10000,
+            api_endpoints: ["https://api.hornet-0.testnet.chrysalis2.com/api/v1/"],
+            retries_per_endpoint: 5,
+            retries_per_query: 100,
+            collector_count: 10,
+            requester_count: 10,
+            request_timeout_secs: 5,
+            parallelism: 25,
+            sync_range: Some((
+                from: 1,
+                to: 2147483647,
+            )),
+            complete_gaps_interval_secs: 3600,
+            logs_dir: Some("chronicle/logs/"),
+            max_log_size: Some(4294967296),
+        ),
+    ),
+)"#;
+
+    #[test]
+    pub fn config_migration() {
+        let migration = ConfigMigration::new(PathBuf::from(CONFIG_PATH), CONFIG_V1, 1).unwrap();
+        assert_eq!(migration.from_version, 1);
+        assert!(!migration.is_current());
+        let migrated = ron::de::from_str::<VersionedConfig>(&migration.migrated).unwrap();
+        let config = migrated.config;
+        assert_eq!(config.websocket_address, ([127, 0, 0, 1], 8081).into());
+        assert_eq!(config.storage_config.keyspaces[0].name, "permanode");
+        assert_eq!(config.storage_config.nodes.len(), 1);
+        assert_eq!(config.broker_config.websocket_address, ([127, 0, 0, 1], 9000).into());
+        assert_eq!(config.broker_config.parallelism, 25);
+        assert_eq!(config.broker_config.mqtt_brokers.len(), 2);
+        // the sections introduced since the version 1 take their defaults
+        let default = Config::default();
+        assert_eq!(config.api_config, default.api_config);
+        assert_eq!(config.alert_config, default.alert_config);
+        assert_eq!(
+            config.broker_config.import_target_latency_ms,
+            default.broker_config.import_target_latency_ms
+        );
+        assert_eq!(config.historical_config_path, HISTORICAL_CONFIG_PATH);
+        assert!(ConfigMigration::new(PathBuf::from(CONFIG_PATH), CONFIG_V1, CURRENT_VERSION + 1).is_err());
+        // a version 1 file doesn't parse as the current version
+        assert!(ConfigMigration::new(PathBuf::from(CONFIG_PATH), CONFIG_V1, CURRENT_VERSION).is_err());
+    }
+
+    #[test]
+    pub fn apply_config_migration() {
+        let dir = std::env::temp_dir().join(format!("chronicle-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.ron");
+        std::fs::write(&path, CONFIG_V1).unwrap();
+
+        let mut migration = ConfigMigration::new(path.clone(), CONFIG_V1, 1).unwrap();
+        migration.apply().unwrap();
+        // the original file is backed up as is, and rewritten in place with the migrated config
+        let backup_path = dir.join("config.ron.v1.bak");
+        assert_eq!(migration.backup_path.as_ref(), Some(&backup_path));
+        assert_eq!(std::fs::read_to_string(&backup_path).unwrap(), CONFIG_V1);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, migration.migrated);
+        assert!(ron::de::from_str::<VersionedConfig>(&contents).is_ok());
+
+        // the migrated file is current, so applying its migration leaves it untouched
+        let mut migration = ConfigMigration::new(path.clone(), &contents, CURRENT_VERSION).unwrap();
+        migration.apply().unwrap();
+        assert!(migration.backup_path.is_none());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), contents);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -10,10 +10,12 @@ use anyhow::{
 };
 pub use api::*;
 pub use broker::*;
+pub use diff::*;
 use maplit::{
     hashmap,
     hashset,
 };
+pub use migration::ConfigMigration;
 use ron::value::Value;
 use serde::Deserializer;
 use std::{
@@ -28,16 +30,22 @@ use std::{
     path::Path,
 };
 pub use storage::*;
+pub use validation::ConfigProblem;
 
 mod alert;
 mod api;
 mod broker;
+mod diff;
+mod migration;
 mod storage;
+mod validation;
 
 /// The default config file path
 pub const CONFIG_PATH: &str = "./config.ron";
 /// The default historical config path
 pub const HISTORICAL_CONFIG_PATH: &str = "./historical_config";
+/// The prefix of the environment variables which override the config fields, ie `CHRONICLE__STORAGE_CONFIG__NODES`
+pub const ENV_PREFIX: &str = "CHRONICLE__";
 /// The current config version.
 /// **Must be updated with each change to the config format.**
 const CURRENT_VERSION: u32 = 3;
@@ -68,12 +76,47 @@ impl VersionedConfig {
         }
     }
 
-    /// Load versioned config from a RON file. Will check the following paths in this order:
+    /// Load versioned config from a RON file, along with the overrides of the environment variables and of
+    /// `set_overrides`. Will check the following paths in this order:
     /// 1. Provided path
     /// 2. Environment variable CONFIG_PATH
     /// 3. Default config path: ./config.ron
+    ///
+    /// If no file is found but there are overrides, they are applied to the default config.
     pub fn load<P: Into<Option<String>>>(path: P) -> anyhow::Result<Self> {
-        VersionedValue::load(path)
+        VersionedValue::load(path, true)
+    }
+
+    /// Load versioned config from a RON file, without the overrides. Will check the following paths in this order:
+    /// 1. Provided path
+    /// 2. Environment variable CONFIG_PATH
+    /// 3. Default config path: ./config.ron
+    ///
+    /// If no file is found but there are overrides, the default config is used.
+    pub fn load_file<P: Into<Option<String>>>(path: P) -> anyhow::Result<Self> {
+        VersionedValue::load(path, false)
+    }
+
+    /// Load versioned config from a RON file along with the overrides, without migrating nor saving any file,
+    /// so an outdated or missing config file is an error. Will check the following paths in this order:
+    /// 1. Provided path
+    /// 2. Environment variable CONFIG_PATH
+    /// 3. Default config path: ./config.ron
+    pub fn load_unmigrated<P: Into<Option<String>>>(path: P) -> anyhow::Result<Self> {
+        let (path, val) = VersionedValue::read(path.into())?.ok_or_else(|| anyhow!("Config file was not found!"))?;
+        let version = ron::de::from_str::<VersionedValue>(&val)
+            .map_err(|e| anyhow!(e))?
+            .version;
+        ensure!(
+            version == CURRENT_VERSION,
+            "Config file {} version mismatch! Expected: {}, Actual: {}, it is migrated when loaded by chronicle",
+            path,
+            CURRENT_VERSION,
+            version
+        );
+        ron::de::from_str::<VersionedConfig>(&val)
+            .map_err(|e| anyhow!(e))?
+            .with_overrides()
     }
 
     /// Load versioned config from a RON file without checking the version. Will check the
@@ -111,6 +154,23 @@ impl VersionedConfig {
         );
     }
 
+    /// Migrate the config file to the current version, in place (after backing it up) unless `dry_run` is set.
+    /// Will check the following paths in this order:
+    /// 1. Provided path
+    /// 2. Environment variable CONFIG_PATH
+    /// 3. Default config path: ./config.ron
+    pub fn migrate<P: Into<Option<String>>>(path: P, dry_run: bool) -> anyhow::Result<ConfigMigration> {
+        let (path, val) = VersionedValue::read(path.into())?.ok_or_else(|| anyhow!("Config file was not found!"))?;
+        let version = ron::de::from_str::<VersionedValue>(&val)
+            .map_err(|e| anyhow!(e))?
+            .version;
+        let mut migration = ConfigMigration::new(path.into(), &val, version)?;
+        if !dry_run {
+            migration.apply()?;
+        }
+        Ok(migration)
+    }
+
     /// Save versioned config to a RON file. Will use the first of the following possible locations:
     /// 1. Provided path
     /// 2. Environment variable CONFIG_PATH
@@ -145,10 +205,60 @@ impl VersionedConfig {
 }
 
 impl VersionedValue {
-    fn load<P: Into<Option<String>>>(path: P) -> anyhow::Result<VersionedConfig> {
+    fn load<P: Into<Option<String>>>(path: P, with_overrides: bool) -> anyhow::Result<VersionedConfig> {
         let opt_path = path.into();
+        if let Some((path, mut val)) = Self::read(opt_path.clone())? {
+            let version = ron::de::from_str::<VersionedValue>(&val)
+                .map_err(|e| anyhow!(e))?
+                .version;
+            if version < CURRENT_VERSION {
+                let mut migration = ConfigMigration::new(path.into(), &val, version)?;
+                migration.apply()?;
+                log::warn!(
+                    "Migrated the config file {} from version {} to {}, backed it up at {:?}",
+                    migration.path.display(),
+                    version,
+                    CURRENT_VERSION,
+                    migration.backup_path
+                );
+                val = migration.migrated;
+            }
+            let config = ron::de::from_str::<VersionedValue>(&val)
+                .map_err(|e| anyhow!(e))
+                .and_then(|v| {
+                    v.verify_version()
+                        .and_then(|_| ron::de::from_str::<VersionedConfig>(&val).map_err(|e| anyhow!(e)))
+                })?;
+            if with_overrides {
+                return config.with_overrides();
+            }
+            return Ok(config);
+        }
+        if !overrides().is_empty() {
+            log::warn!("Config file was not found! Using the default config along with the overrides");
+            let config = VersionedConfig::default();
+            return if with_overrides {
+                config.with_overrides()
+            } else {
+                Ok(config)
+            };
+        }
+        let path = opt_path
+            .or_else(|| std::env::var("CONFIG_PATH").ok())
+            .unwrap_or(CONFIG_PATH.to_string());
+        let config: VersionedConfig = Config::default().try_into()?;
+        config.save(path.clone())?;
+        bail!(
+                "Config file was not found! Saving a default config file at {}. Please edit it and restart the application!",
+                std::fs::canonicalize(&path).map(|p| p.to_string_lossy().into_owned()).unwrap_or(path)
+            );
+    }
+
+    /// Read the first config file found among the provided path, the CONFIG_PATH environment variable and the
+    /// default config path, along with its path
+    fn read(opt_path: Option<String>) -> anyhow::Result<Option<(String, String)>> {
         let paths = vec![
-            opt_path.clone(),
+            opt_path,
             std::env::var("CONFIG_PATH").ok(),
             Some(CONFIG_PATH.to_string()),
         ]
@@ -158,12 +268,7 @@ impl VersionedValue {
                 Ok(mut f) => {
                     let mut val = String::new();
                     f.read_to_string(&mut val)?;
-                    return ron::de::from_str::<VersionedValue>(&val)
-                        .map_err(|e| anyhow!(e))
-                        .and_then(|v| {
-                            v.verify_version()
-                                .and_then(|_| ron::de::from_str::<VersionedConfig>(&val).map_err(|e| anyhow!(e)))
-                        });
+                    return Ok(Some((path, val)));
                 }
                 Err(e) => match e.kind() {
                     std::io::ErrorKind::NotFound => {
@@ -173,15 +278,7 @@ impl VersionedValue {
                 },
             }
         }
-        let path = opt_path
-            .or_else(|| std::env::var("CONFIG_PATH").ok())
-            .unwrap_or(CONFIG_PATH.to_string());
-        let config: VersionedConfig = Config::default().try_into()?;
-        config.save(path.clone())?;
-        bail!(
-                "Config file was not found! Saving a default config file at {}. Please edit it and restart the application!",
-                std::fs::canonicalize(&path).map(|p| p.to_string_lossy().into_owned()).unwrap_or(path)
-            );
+        Ok(None)
     }
 
     fn verify_version(self) -> anyhow::Result<()> {
@@ -195,6 +292,14 @@ impl VersionedValue {
     }
 }
 
+impl VersionedConfig {
+    /// Apply the overrides of the environment variables and of `set_overrides` to the config
+    pub fn with_overrides(mut self) -> anyhow::Result<Self> {
+        self.config = self.config.with_overrides()?;
+        Ok(self)
+    }
+}
+
 impl From<Config> for VersionedConfig {
     fn from(config: Config) -> Self {
         Self::new(config)
@@ -276,6 +381,15 @@ impl Config {
         VersionedConfig::load(path).map(|c| c.config)
     }
 
+    /// Load unversioned config from a RON file, without the overrides.
+    /// Will check the following paths in this order:
+    /// 1. Provided path
+    /// 2. Environment variable CONFIG_PATH
+    /// 3. Default config path: ./config.ron
+    pub fn load_file<P: Into<Option<String>>>(path: P) -> anyhow::Result<Config> {
+        VersionedConfig::load_file(path).map(|c| c.config)
+    }
+
     /// Save config to a RON file with the current version. Will use the first of the following possible locations:
     /// 1. Provided path
     /// 2. Environment variable CONFIG_PATH
@@ -284,16 +398,121 @@ impl Config {
         VersionedConfig::new(self.clone()).save(path)
     }
 
-    /// Verify this config
+    /// Apply the overrides of the environment variables, then the ones of `set_overrides`, to this config
+    pub fn with_overrides(self) -> anyhow::Result<Self> {
+        let overrides = overrides();
+        if overrides.is_empty() {
+            return Ok(self);
+        }
+        let mut value = serde_json::to_value(&self)?;
+        for (path, raw) in overrides.iter() {
+            override_field(&mut value, path, raw).map_err(|e| anyhow!("Invalid config override {}: {}", path, e))?;
+        }
+        serde_json::from_value(value).map_err(|e| anyhow!("Invalid config overrides: {}", e))
+    }
+
+    /// Verify this config, bailing with all the problems found by the offline validation
     pub async fn verify(mut self) -> anyhow::Result<Self> {
-        self.storage_config.verify().await?;
-        self.api_config.verify().await?;
+        let problems = self.validate(true).await;
+        if !problems.is_empty() {
+            bail!(
+                "Error verifying the config, found {} problem(s):\n{}",
+                problems.len(),
+                problems.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+            );
+        }
         self.broker_config.verify().await?;
         self.alert_config.verify().await?;
         Ok(self)
     }
 }
 
+lazy_static::lazy_static! {
+    static ref SET_OVERRIDES: std::sync::RwLock<Vec<(String, String)>> = Default::default();
+}
+
+/// Override the config fields with `path=value` pairs, ie `storage_config.nodes=["172.17.0.2:9042"]`.
+/// They take precedence over the config file and the environment variables.
+pub fn set_overrides<I, S>(overrides: I) -> anyhow::Result<()>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let overrides = overrides
+        .into_iter()
+        .map(|s| {
+            s.as_ref()
+                .split_once('=')
+                .map(|(path, value)| (path.trim().to_owned(), value.to_owned()))
+                .ok_or_else(|| anyhow!("Invalid config override: {}, expected path=value", s.as_ref()))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    *SET_OVERRIDES.write().unwrap() = overrides;
+    Ok(())
+}
+
+/// Export the overrides of `set_overrides` as environment variables, so the child processes inherit them
+pub fn export_overrides() {
+    for (path, value) in SET_OVERRIDES.read().unwrap().iter() {
+        std::env::set_var(
+            format!("{}{}", ENV_PREFIX, path.replace('.', "__").to_uppercase()),
+            value,
+        );
+    }
+}
+
+/// The overrides of the environment variables, sorted by path, followed by the ones of `set_overrides`
+fn overrides() -> Vec<(String, String)> {
+    let mut overrides = std::env::vars()
+        .filter_map(|(key, value)| {
+            key.strip_prefix(ENV_PREFIX)
+                .map(|path| (path.split("__").collect::<Vec<_>>().join(".").to_lowercase(), value))
+        })
+        .collect::<Vec<_>>();
+    overrides.sort();
+    overrides.extend(SET_OVERRIDES.read().unwrap().iter().cloned());
+    overrides
+}
+
+/// Override the field at the dot separated path (the map keys are case insensitive and the list items are
+/// indexed). The value is parsed as json, or else taken as a string, or a comma separated list for a list field.
+fn override_field(value: &mut serde_json::Value, path: &str, raw: &str) -> anyhow::Result<()> {
+    let mut field = value;
+    for segment in path.split('.') {
+        field = match field {
+            serde_json::Value::Object(map) => {
+                let key = map
+                    .keys()
+                    .find(|key| *key == segment)
+                    .or_else(|| map.keys().find(|key| key.eq_ignore_ascii_case(segment)))
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Unknown field: {}", segment))?;
+                map.get_mut(&key).unwrap()
+            }
+            serde_json::Value::Array(list) => {
+                let index = segment
+                    .parse::<usize>()
+                    .map_err(|_| anyhow!("Expected a list index, found: {}", segment))?;
+                list.get_mut(index)
+                    .ok_or_else(|| anyhow!("List index out of bounds: {}", index))?
+            }
+            _ => bail!(
+                "Unable to override the field: {}, of a value which isn't a struct, map or list",
+                segment
+            ),
+        };
+    }
+    *field = match serde_json::from_str(raw) {
+        Ok(value) => value,
+        Err(_) if field.is_array() => raw
+            .split(',')
+            .map(|item| serde_json::Value::String(item.trim().to_owned()))
+            .collect(),
+        Err(_) => serde_json::Value::String(raw.to_owned()),
+    };
+    Ok(())
+}
+
 impl Persist for Config {
     fn persist(&self) -> anyhow::Result<()> {
         self.save(None).map(|_| {
@@ -329,11 +548,32 @@ mod test {
                 listen_address: "localhost:8080".to_socket_addrs().unwrap().next().unwrap(),
                 thread_count: ThreadCount::CoreMultiple(1),
                 reporter_count: 2,
-                local_datacenter: "datacenter1".to_owned(),
+                local_datacenter: "USA".to_owned(),
                 nodes: hashset!["localhost:9042".to_socket_addrs().unwrap().next().unwrap()],
                 partition_config: PartitionConfig::default(),
             },
-            api_config: ApiConfig {},
+            api_config: ApiConfig {
+                listen_address: "localhost:8000".to_socket_addrs().unwrap().next().unwrap(),
+                tls: None,
+                allowed_origins: vec!["*".to_owned()],
+                max_page_size: 1000,
+                request_timeout_secs: 30,
+                route_groups: hashset![
+                    ApiRouteGroup::Info,
+                    ApiRouteGroup::Sync,
+                    ApiRouteGroup::Messages,
+                    ApiRouteGroup::Outputs,
+                    ApiRouteGroup::Transactions,
+                    ApiRouteGroup::Milestones,
+                    ApiRouteGroup::Analytics,
+                ],
+                auth: None,
+                rate_limit: None,
+                max_query_partitions: 64,
+                max_query_rows: 50_000,
+                cache_capacity: 10_000,
+                cache_ttl_secs: 3600,
+            },
             broker_config: BrokerConfig {
                 collector_count: 10,
                 requester_count: 10,
@@ -352,6 +592,10 @@ mod test {
                     MqttType::MessagesReferenced => hashset![
                         url::Url::parse("tcp://api.hornet-0.testnet.chrysalis2.com:1883").unwrap(),
                         url::Url::parse("tcp://api.hornet-1.testnet.chrysalis2.com:1883").unwrap(),
+                    ],
+                    MqttType::LatestMilestone => hashset![
+                        url::Url::parse("tcp://api.hornet-0.testnet.chrysalis2.com:1883").unwrap(),
+                        url::Url::parse("tcp://api.hornet-1.testnet.chrysalis2.com:1883").unwrap(),
                     ]
                 },
                 api_endpoints: hashset![
@@ -362,6 +606,23 @@ mod test {
                 sync_range: Some(SyncRange::default()),
                 logs_dir: Some("chronicle/logs/".to_owned()),
                 max_log_size: Some(4294967296),
+                import_target_latency_ms: Some(200),
+                unreachable_retry_backoff_secs: Some(60),
+                chronicle_id: Some(0),
+                sync_lease_secs: None,
+                sync_lease_range: Some(10000),
+                endpoint_failure_threshold: Some(5),
+                endpoint_open_secs: Some(30),
+                endpoint_probe_interval_secs: Some(30),
+                milestone_cone_path: None,
+                min_pow_score: Some(4000),
+                endpoint_quarantine_secs: Some(3600),
+                mqtt_stale_feed_secs: Some(60),
+                mqtt_reconnect_backoff_secs: Some(5),
+                feed_divergence_window_secs: Some(10),
+                event_streams: None,
+                record_path: None,
+                mock_endpoint: None,
             },
             historical_config_path: HISTORICAL_CONFIG_PATH.to_owned(),
             alert_config: Default::default(),
@@ -379,4 +640,104 @@ mod test {
 
         assert_eq!(config, deserialized_config);
     }
+
+    #[test]
+    pub fn config_diff() {
+        let running = Config::default();
+        assert!(running.diff(&running).is_empty());
+        let added = url::Url::parse("tcp://localhost:1883").unwrap();
+        let removed = running.broker_config.api_endpoints.iter().next().cloned().unwrap();
+        let mut reloaded = running.clone();
+        reloaded
+            .broker_config
+            .mqtt_brokers
+            .entry(MqttType::Messages)
+            .or_default()
+            .insert(added.clone());
+        reloaded.broker_config.api_endpoints.remove(&removed);
+        reloaded.broker_config.sync_range = Some(SyncRange { from: 1, to: 1000 });
+        reloaded.broker_config.collector_count += 1;
+        reloaded.api_config.listen_address = "localhost:8001".to_socket_addrs().unwrap().next().unwrap();
+        let diff = running.diff(&reloaded);
+        assert_eq!(
+            diff.hot,
+            vec![
+                ConfigChange::AddMqttBroker(MqttType::Messages, added),
+                ConfigChange::RemoveApiEndpoint(removed),
+                ConfigChange::SyncRange(SyncRange { from: 1, to: 1000 }),
+            ]
+        );
+        assert_eq!(
+            diff.restart,
+            vec![
+                "api_config.listen_address".to_owned(),
+                "broker_config.collector_count".to_owned()
+            ]
+        );
+        // reverting the live changes leaves the ones which need a restart
+        for change in diff.hot.iter() {
+            reloaded.revert(change, &running);
+        }
+        assert!(running.diff(&reloaded).hot.is_empty());
+        assert_eq!(running.diff(&reloaded).restart, diff.restart);
+    }
+
+    #[test]
+    pub fn config_overrides() {
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        override_field(&mut value, "storage_config.nodes", "172.17.0.2:9042, 172.17.0.3:9042").unwrap();
+        override_field(&mut value, "storage_config.keyspaces.0.name", "chronicle").unwrap();
+        override_field(
+            &mut value,
+            "broker_config.mqtt_brokers.messages",
+            r#"["tcp://localhost:1883"]"#,
+        )
+        .unwrap();
+        override_field(&mut value, "broker_config.collector_count", "4").unwrap();
+        assert!(override_field(&mut value, "broker_config.unknown_field", "1").is_err());
+        assert!(override_field(&mut value, "storage_config.keyspaces.1.name", "chronicle").is_err());
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            config.storage_config.nodes,
+            hashset!["172.17.0.2:9042".parse().unwrap(), "172.17.0.3:9042".parse().unwrap()]
+        );
+        assert_eq!(config.storage_config.keyspaces[0].name, "chronicle");
+        assert_eq!(
+            config.broker_config.mqtt_brokers[&MqttType::Messages],
+            hashset![url::Url::parse("tcp://localhost:1883").unwrap()]
+        );
+        assert_eq!(config.broker_config.collector_count, 4);
+    }
+
+    #[test]
+    pub fn config_validation() {
+        let mut config = Config::default();
+        config.storage_config.local_datacenter = "USA".to_owned();
+        assert!(futures::executor::block_on(config.validate(true)).is_empty());
+        config.storage_config.local_datacenter = "Europe".to_owned();
+        config.storage_config.partition_config.partition_count = 0;
+        config.api_config.listen_address = config.websocket_address;
+        config.broker_config.sync_range = Some(SyncRange { from: 1000, to: 1 });
+        config.broker_config.logs_dir = Some("Cargo.toml/logs/".to_owned());
+        let paths = futures::executor::block_on(config.validate(true))
+            .into_iter()
+            .map(|problem| problem.path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "api_config.listen_address",
+                "storage_config.local_datacenter",
+                "storage_config.partition_config.partition_count",
+                "broker_config.sync_range",
+                "broker_config.logs_dir",
+            ]
+        );
+        // the verification reports every problem, not only the first one
+        let error = futures::executor::block_on(config.verify()).unwrap_err().to_string();
+        assert!(error.contains("found 5 problem(s)"));
+        for path in paths {
+            assert!(error.contains(&path));
+        }
+    }
 }
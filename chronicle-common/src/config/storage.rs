@@ -59,33 +59,13 @@ impl Default for StorageConfig {
             listen_address: ([127, 0, 0, 1], 8080).into(),
             thread_count: Default::default(),
             reporter_count: 1,
-            local_datacenter: "datacenter1".to_string(),
+            local_datacenter: "USA".to_string(),
             nodes: hashset![([127, 0, 0, 1], 9042).into()],
             partition_config: Default::default(),
         }
     }
 }
 
-impl StorageConfig {
-    /// Verify that the storage config is valid
-    pub async fn verify(&mut self) -> anyhow::Result<()> {
-        if self
-            .keyspaces
-            .iter()
-            .any(|k| k.data_centers.iter().any(|dc| dc.1.replication_factor == 0))
-        {
-            bail!("replication_factor must be greater than zero, ensure your config is correct");
-        }
-        if self.reporter_count.eq(&0) {
-            bail!("reporter_count must be greater than zero, ensure your config is correct");
-        }
-        if self.local_datacenter.eq(&"") {
-            bail!("local_datacenter must be non-empty string, ensure your config is correct");
-        }
-        Ok(())
-    }
-}
-
 /// Configuration for a scylla keyspace
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct KeyspaceConfig {
@@ -0,0 +1,487 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use reqwest::Client;
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+        HashSet,
+    },
+    fmt::Display,
+    fs::OpenOptions,
+    time::Duration,
+};
+use tokio::net::TcpStream;
+use url::Url;
+
+/// The timeout of each network probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A problem found by the config validation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// The path of the field, ie `storage_config.partition_config.partition_count`
+    pub path: String,
+    /// What is wrong with the field
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// The problems found so far by the validation
+#[derive(Default)]
+struct Problems(Vec<ConfigProblem>);
+
+impl Problems {
+    fn push(&mut self, path: impl Display, message: impl Display) {
+        self.0.push(ConfigProblem {
+            path: path.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    /// Push the problem unless the condition holds
+    fn ensure(&mut self, condition: bool, path: impl Display, message: impl Display) {
+        if !condition {
+            self.push(path, message);
+        }
+    }
+}
+
+impl Config {
+    /// Validate this config, returning all the problems found along with the paths of their fields.
+    /// Unlike `verify`, it checks the consistency between the sections and whether the directories are writable,
+    /// then probes the scylla nodes, MQTT brokers, event streams and api endpoints unless `offline`.
+    pub async fn validate(&self, offline: bool) -> Vec<ConfigProblem> {
+        let mut problems = Problems::default();
+        // the listeners can't share an address
+        let listeners = [
+            ("websocket_address", self.websocket_address),
+            ("storage_config.listen_address", self.storage_config.listen_address),
+            ("api_config.listen_address", self.api_config.listen_address),
+            ("broker_config.websocket_address", self.broker_config.websocket_address),
+        ];
+        for (i, (path, address)) in listeners.iter().enumerate() {
+            if let Some((other, _)) = listeners[..i].iter().find(|(_, other)| other == address) {
+                problems.push(path, format!("{} is already the address of {}", address, other));
+            }
+        }
+        self.storage_config.validate(&mut problems);
+        self.api_config.validate(&self.storage_config, &mut problems);
+        self.broker_config.validate(&mut problems);
+        for (i, request) in self.alert_config.requests.iter().enumerate() {
+            problems.ensure(
+                matches!(request.url.scheme(), "http" | "https"),
+                format!("alert_config.requests.{}.url", i),
+                format!(
+                    "unsupported scheme of {}, expected an http:// or https:// url",
+                    request.url
+                ),
+            );
+        }
+        writable_dir(&mut problems, "historical_config_path", &self.historical_config_path);
+        if !offline {
+            self.probe(&mut problems).await;
+        }
+        problems.0
+    }
+
+    /// Probe the reachability of the scylla nodes, MQTT brokers, event streams and api endpoints
+    async fn probe(&self, problems: &mut Problems) {
+        for node in self.storage_config.nodes.iter().collect::<BTreeSet<_>>() {
+            if let Err(e) = connect(&node.to_string()).await {
+                problems.push(
+                    "storage_config.nodes",
+                    format!("scylla node {} is unreachable: {}", node, e),
+                );
+            }
+        }
+        let broker_config = &self.broker_config;
+        let empty = HashSet::new();
+        for mqtt_type in MqttType::ALL {
+            for mqtt_broker in sorted(broker_config.mqtt_brokers.get(&mqtt_type).unwrap_or(&empty)) {
+                if let Err(e) = connect_url(mqtt_broker).await {
+                    problems.push(
+                        format!("broker_config.mqtt_brokers.{:?}", mqtt_type),
+                        format!("MQTT broker {} is unreachable: {}", mqtt_broker, e),
+                    );
+                }
+            }
+            let event_streams = broker_config.event_streams.as_ref().and_then(|e| e.get(&mqtt_type));
+            for event_stream in sorted(event_streams.unwrap_or(&empty)) {
+                // the file event streams are checked offline
+                if event_stream.scheme() == "file" {
+                    continue;
+                }
+                if let Err(e) = connect_url(event_stream).await {
+                    problems.push(
+                        format!("broker_config.event_streams.{:?}", mqtt_type),
+                        format!("event stream {} is unreachable: {}", event_stream, e),
+                    );
+                }
+            }
+        }
+        let client = Client::builder().timeout(PROBE_TIMEOUT).build().unwrap_or_default();
+        for endpoint in sorted(&broker_config.api_endpoints) {
+            let endpoint = BrokerConfig::adjust_api_endpoint(endpoint.clone()).unwrap_or_else(|| endpoint.clone());
+            if let Err(e) = BrokerConfig::verify_endpoint(&client, &endpoint).await {
+                problems.push("broker_config.api_endpoints", e);
+            }
+        }
+    }
+}
+
+impl StorageConfig {
+    fn validate(&self, problems: &mut Problems) {
+        problems.ensure(
+            !self.keyspaces.is_empty(),
+            "storage_config.keyspaces",
+            "no keyspace provided",
+        );
+        let mut names = HashSet::new();
+        for (i, keyspace) in self.keyspaces.iter().enumerate() {
+            let path = format!("storage_config.keyspaces.{}", i);
+            // scylla keyspace names are identifiers of at most 48 characters
+            let valid_name = keyspace.name.len() <= 48
+                && keyspace.name.starts_with(|c: char| c.is_ascii_alphabetic())
+                && keyspace.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            problems.ensure(
+                valid_name,
+                format!("{}.name", path),
+                format!(
+                    "invalid keyspace name {:?}, expected at most 48 letters, digits or underscores starting with a letter",
+                    keyspace.name
+                ),
+            );
+            problems.ensure(
+                names.insert(&keyspace.name),
+                format!("{}.name", path),
+                format!("duplicated keyspace name {}", keyspace.name),
+            );
+            problems.ensure(
+                !keyspace.data_centers.is_empty(),
+                format!("{}.data_centers", path),
+                "no datacenter provided",
+            );
+            for (name, data_center) in keyspace.data_centers.iter().collect::<BTreeMap<_, _>>() {
+                problems.ensure(
+                    data_center.replication_factor > 0,
+                    format!("{}.data_centers.{}.replication_factor", path, name),
+                    "must be greater than zero",
+                );
+            }
+        }
+        if self.local_datacenter.is_empty() {
+            problems.push("storage_config.local_datacenter", "must be a non-empty string");
+        } else {
+            problems.ensure(
+                self.keyspaces
+                    .iter()
+                    .any(|keyspace| keyspace.data_centers.contains_key(&self.local_datacenter)),
+                "storage_config.local_datacenter",
+                format!("{} is not a datacenter of any keyspace", self.local_datacenter),
+            );
+        }
+        problems.ensure(
+            !matches!(self.thread_count, ThreadCount::Count(0) | ThreadCount::CoreMultiple(0)),
+            "storage_config.thread_count",
+            "must be greater than zero",
+        );
+        problems.ensure(
+            self.reporter_count > 0,
+            "storage_config.reporter_count",
+            "must be greater than zero",
+        );
+        problems.ensure(!self.nodes.is_empty(), "storage_config.nodes", "no node provided");
+        problems.ensure(
+            self.partition_config.partition_count > 0,
+            "storage_config.partition_config.partition_count",
+            "must be greater than zero",
+        );
+        problems.ensure(
+            self.partition_config.milestone_chunk_size > 0,
+            "storage_config.partition_config.milestone_chunk_size",
+            "must be greater than zero",
+        );
+    }
+}
+
+impl ApiConfig {
+    fn validate(&self, storage_config: &StorageConfig, problems: &mut Problems) {
+        if let Some(tls) = self.tls.as_ref() {
+            for (field, path) in [("cert_path", &tls.cert_path), ("key_path", &tls.key_path)] {
+                problems.ensure(
+                    Path::new(path).is_file(),
+                    format!("api_config.tls.{}", field),
+                    format!("missing file {}", path),
+                );
+            }
+        }
+        problems.ensure(
+            !self.allowed_origins.is_empty(),
+            "api_config.allowed_origins",
+            "no origin provided, expected at least one origin or \"*\"",
+        );
+        for (i, origin) in self.allowed_origins.iter().enumerate() {
+            problems.ensure(
+                Self::valid_origin(origin),
+                format!("api_config.allowed_origins.{}", i),
+                format!(
+                    "invalid origin {}, expected an origin like https://example.com or \"*\"",
+                    origin
+                ),
+            );
+        }
+        problems.ensure(
+            self.max_page_size > 0,
+            "api_config.max_page_size",
+            "must be greater than zero",
+        );
+        problems.ensure(
+            self.request_timeout_secs > 0,
+            "api_config.request_timeout_secs",
+            "must be greater than zero",
+        );
+        problems.ensure(
+            !self.route_groups.is_empty(),
+            "api_config.route_groups",
+            "no route group enabled",
+        );
+        if self.route_groups.contains(&ApiRouteGroup::Admin) {
+            let anonymous_admin = self.auth.as_ref().map_or(true, |auth| {
+                matches!(auth.anonymous_scope.as_ref(), Some(scope) if scope.permissions.contains(&ApiPermission::Admin))
+            });
+            problems.ensure(
+                !anonymous_admin,
+                "api_config.route_groups",
+                "the admin routes require an auth config without an anonymous admin scope",
+            );
+        }
+        if let Some(auth) = self.auth.as_ref() {
+            // the scopes can only grant the configured keyspaces
+            let keyspaces = storage_config
+                .keyspaces
+                .iter()
+                .map(|keyspace| keyspace.name.as_str())
+                .chain(std::iter::once("*"))
+                .collect::<HashSet<_>>();
+            let mut scopes = Vec::new();
+            let (mut names, mut keys) = (HashSet::new(), HashSet::new());
+            for (i, api_key) in auth.api_keys.iter().enumerate() {
+                let path = format!("api_config.auth.api_keys.{}", i);
+                problems.ensure(
+                    api_key.key.len() >= 16,
+                    format!("{}.key", path),
+                    "too short, expected at least 16 characters",
+                );
+                problems.ensure(
+                    names.insert(&api_key.name),
+                    format!("{}.name", path),
+                    format!("duplicated api key name {}", api_key.name),
+                );
+                problems.ensure(keys.insert(&api_key.key), format!("{}.key", path), "duplicated api key");
+                scopes.push((format!("{}.scope", path), &api_key.scope));
+            }
+            if let Some(scope) = auth.anonymous_scope.as_ref() {
+                scopes.push(("api_config.auth.anonymous_scope".to_owned(), scope));
+            }
+            for (path, scope) in scopes {
+                for keyspace in scope.keyspaces.iter().collect::<BTreeSet<_>>() {
+                    problems.ensure(
+                        keyspaces.contains(keyspace.as_str()),
+                        format!("{}.keyspaces", path),
+                        format!("{} is not a configured keyspace", keyspace),
+                    );
+                }
+            }
+            problems.ensure(
+                auth.jwt_secret.as_ref().map_or(true, |secret| secret.len() >= 32),
+                "api_config.auth.jwt_secret",
+                "too short, expected at least 32 characters",
+            );
+        }
+        if let Some(rate_limit) = self.rate_limit.as_ref() {
+            problems.ensure(
+                rate_limit.requests_per_sec > 0,
+                "api_config.rate_limit.requests_per_sec",
+                "must be greater than zero",
+            );
+            problems.ensure(
+                rate_limit.burst > 0,
+                "api_config.rate_limit.burst",
+                "must be greater than zero",
+            );
+        }
+        problems.ensure(
+            self.cache_capacity == 0 || self.cache_ttl_secs > 0,
+            "api_config.cache_ttl_secs",
+            "must be greater than zero, unless the cache is disabled with a zero cache_capacity",
+        );
+        problems.ensure(
+            self.max_query_partitions > 0,
+            "api_config.max_query_partitions",
+            "must be greater than zero",
+        );
+        problems.ensure(
+            self.max_query_rows > 0,
+            "api_config.max_query_rows",
+            "must be greater than zero",
+        );
+    }
+}
+
+impl BrokerConfig {
+    fn validate(&self, problems: &mut Problems) {
+        let empty = HashSet::new();
+        for mqtt_type in MqttType::ALL {
+            for mqtt_broker in sorted(self.mqtt_brokers.get(&mqtt_type).unwrap_or(&empty)) {
+                problems.ensure(
+                    matches!(mqtt_broker.scheme(), "tcp" | "ssl" | "ws" | "wss"),
+                    format!("broker_config.mqtt_brokers.{:?}", mqtt_type),
+                    format!(
+                        "unsupported scheme of {}, expected a tcp://, ssl://, ws:// or wss:// url",
+                        mqtt_broker
+                    ),
+                );
+            }
+            let event_streams = self.event_streams.as_ref().and_then(|e| e.get(&mqtt_type));
+            for event_stream in sorted(event_streams.unwrap_or(&empty)) {
+                let path = format!("broker_config.event_streams.{:?}", mqtt_type);
+                match event_stream.scheme() {
+                    "ws" | "wss" => (),
+                    "file" => problems.ensure(
+                        event_stream.to_file_path().map_or(false, |path| path.is_file()),
+                        path,
+                        format!("missing file of {}", event_stream),
+                    ),
+                    _ => problems.push(
+                        path,
+                        format!(
+                            "unsupported scheme of {}, expected a ws://, wss:// or file:// url",
+                            event_stream
+                        ),
+                    ),
+                }
+            }
+        }
+        for endpoint in sorted(&self.api_endpoints) {
+            problems.ensure(
+                matches!(endpoint.scheme(), "http" | "https"),
+                "broker_config.api_endpoints",
+                format!(
+                    "unsupported scheme of {}, expected an http:// or https:// url",
+                    endpoint
+                ),
+            );
+        }
+        for (field, count) in [
+            ("collector_count", self.collector_count),
+            ("requester_count", self.requester_count),
+            ("parallelism", self.parallelism),
+        ] {
+            problems.ensure(
+                count > 0,
+                format!("broker_config.{}", field),
+                "must be greater than zero",
+            );
+        }
+        problems.ensure(
+            self.request_timeout_secs > 0,
+            "broker_config.request_timeout_secs",
+            "must be greater than zero",
+        );
+        if let Some(sync_range) = self.sync_range.as_ref() {
+            if sync_range.from == 0 || sync_range.to == 0 {
+                problems.push(
+                    "broker_config.sync_range",
+                    "the milestone indexes must be greater than zero",
+                );
+            } else {
+                problems.ensure(
+                    sync_range.from < sync_range.to,
+                    "broker_config.sync_range",
+                    format!("from ({}) must be lower than to ({})", sync_range.from, sync_range.to),
+                );
+            }
+        }
+        problems.ensure(
+            self.sync_lease_secs.is_none() || self.chronicle_id.is_some(),
+            "broker_config.chronicle_id",
+            "the sync leases require a chronicle id unique to this instance",
+        );
+        problems.ensure(
+            self.sync_lease_range != Some(0),
+            "broker_config.sync_lease_range",
+            "must be greater than zero",
+        );
+        problems.ensure(
+            self.endpoint_failure_threshold != Some(0),
+            "broker_config.endpoint_failure_threshold",
+            "must be greater than zero",
+        );
+        if let Some(logs_dir) = self.logs_dir.as_ref() {
+            writable_dir(problems, "broker_config.logs_dir", logs_dir);
+        }
+        if let Some(recording) = self.mock_endpoint.as_ref() {
+            problems.ensure(
+                Path::new(recording).is_file(),
+                "broker_config.mock_endpoint",
+                format!("the recording {} doesn't exist", recording),
+            );
+        }
+    }
+}
+
+fn sorted(urls: &HashSet<Url>) -> BTreeSet<&Url> {
+    urls.iter().collect()
+}
+
+/// Push a problem unless the directory is writable, or can be created
+fn writable_dir(problems: &mut Problems, path: &str, dir: &str) {
+    // the directory would be created within its closest existing ancestor
+    let existing = Path::new(dir)
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .unwrap_or_else(|| Path::new("."));
+    if !existing.is_dir() {
+        problems.push(path, format!("{} is not a directory", existing.display()));
+        return;
+    }
+    let probe = existing.join(format!(".chronicle-check-{}", rand::random::<u32>()));
+    let written = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .and_then(|_| std::fs::remove_file(&probe));
+    if let Err(e) = written {
+        problems.push(path, format!("{} is not writable: {}", existing.display(), e));
+    }
+}
+
+/// Open a TCP connection to the address within the probe timeout
+async fn connect(address: &str) -> anyhow::Result<()> {
+    tokio::time::timeout(PROBE_TIMEOUT, TcpStream::connect(address))
+        .await
+        .map_err(|_| anyhow!("timed out"))??;
+    Ok(())
+}
+
+/// Open a TCP connection to the host of the url, using the default port of its scheme if it has none
+async fn connect_url(url: &Url) -> anyhow::Result<()> {
+    let host = url.host_str().ok_or_else(|| anyhow!("missing host"))?;
+    let port = url
+        .port_or_known_default()
+        .or(match url.scheme() {
+            "tcp" => Some(1883),
+            "ssl" => Some(8883),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("missing port"))?;
+    connect(&format!("{}:{}", host, port)).await
+}
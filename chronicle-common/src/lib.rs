@@ -227,9 +227,13 @@ impl History<HistoricalConfig> {
             .into()
             .map(|max_records| Self::new(max_records))
             .unwrap_or_default();
-        match Config::load(None) {
+        // the history holds the config files, the overrides only apply to the running config so they're never saved
+        match Config::load_file(None) {
             Ok(latest) => {
                 debug!("Latest Config found! {:?}", latest);
+                if let Err(e) = latest.clone().with_overrides() {
+                    panic!("{}", e)
+                }
                 let historical_config_path = latest.historical_config_path.clone();
                 history.update(latest);
                 glob(&format!(r"{}/\d+_config.ron", historical_config_path))
@@ -238,7 +242,7 @@ impl History<HistoricalConfig> {
                     .filter_map(|path| {
                         debug!("historical path: {:?}", path);
                         path.map(|p| {
-                            Config::load(p.to_str().map(|s| s.to_owned())).ok().and_then(|c| {
+                            Config::load_file(p.to_str().map(|s| s.to_owned())).ok().and_then(|c| {
                                 p.file_name()
                                     .and_then(|s| s.to_string_lossy().split("_").next().map(|s| s.to_owned()))
                                     .and_then(|s| s.parse::<u64>().ok())
@@ -328,15 +332,30 @@ impl std::ops::Drop for PersistHandle {
     }
 }
 
-/// Get the latest config asynchronously
+/// Get the latest config asynchronously, along with the overrides of the environment variables and of
+/// `config::set_overrides`
 pub async fn get_config_async() -> Config {
-    CONFIG.read().await.latest()
+    let config = CONFIG.read().await.latest();
+    config.clone().with_overrides().unwrap_or_else(|e| {
+        error!("Unable to apply the config overrides: {}", e);
+        config
+    })
 }
 /// Get the latest config synchronously
 pub fn get_config() -> Config {
     futures::executor::block_on(get_config_async())
 }
 
+/// Get the latest config file asynchronously, without the overrides. The config changes to persist must be based on
+/// it, so the overrides don't get saved
+pub async fn get_config_file_async() -> Config {
+    CONFIG.read().await.latest()
+}
+/// Get the latest config file synchronously, without the overrides
+pub fn get_config_file() -> Config {
+    futures::executor::block_on(get_config_file_async())
+}
+
 /// Get a mutable reference to the config history asynchronously
 pub async fn get_history_mut_async() -> PersistHandle {
     CONFIG.write().await.into()
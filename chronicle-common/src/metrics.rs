@@ -5,10 +5,13 @@ use super::*;
 pub use prometheus;
 use prometheus::{
     Gauge,
+    GaugeVec,
     HistogramOpts,
     HistogramVec,
     IntCounter,
     IntCounterVec,
+    IntGauge,
+    IntGaugeVec,
     Opts,
     Registry,
 };
@@ -33,8 +36,125 @@ lazy_static! {
         HistogramVec::new(HistogramOpts::new("response_time", "Response Times"), &["endpoint"])
             .expect("failed to create metric");
 
+    /// Throttled API requests, by client kind and reason (rate limit or query budget)
+    pub static ref API_THROTTLED_REQUESTS: IntCounterVec = IntCounterVec::new(
+        Opts::new("api_throttled_requests", "Throttled API Requests"),
+        &["client", "reason"]
+    )
+    .expect("failed to create metric");
+
+    /// API response cache lookups, by route and result (hit or miss)
+    pub static ref API_CACHE_REQUESTS: IntCounterVec = IntCounterVec::new(
+        Opts::new("api_cache_requests", "API Response Cache Requests"),
+        &["route", "result"]
+    )
+    .expect("failed to create metric");
+
     /// Confirmation time collector
     pub static ref CONFIRMATION_TIME_COLLECTOR: Gauge =
         Gauge::new("confirmation_time", "Confirmation Times")
             .expect("failed to create metric");
+
+    /// Processed milestones of the current import session
+    pub static ref IMPORT_MILESTONES: IntGauge =
+        IntGauge::new("import_milestones", "Import Processed Milestones").expect("failed to create metric");
+
+    /// Processed bytes of the current import session
+    pub static ref IMPORT_BYTES: IntGauge =
+        IntGauge::new("import_bytes", "Import Processed Bytes").expect("failed to create metric");
+
+    /// Total bytes of the scheduled LogFiles of the current import session
+    pub static ref IMPORT_TOTAL_BYTES: IntGauge =
+        IntGauge::new("import_total_bytes", "Import Total Bytes").expect("failed to create metric");
+
+    /// Import throughput in milestones per second
+    pub static ref IMPORT_MILESTONES_PER_SECOND: Gauge =
+        Gauge::new("import_milestones_per_second", "Import Milestones Per Second").expect("failed to create metric");
+
+    /// Import throughput in bytes per second
+    pub static ref IMPORT_BYTES_PER_SECOND: Gauge =
+        Gauge::new("import_bytes_per_second", "Import Bytes Per Second").expect("failed to create metric");
+
+    /// Estimated remaining seconds of the current import session
+    pub static ref IMPORT_ETA_SECONDS: IntGauge =
+        IntGauge::new("import_eta_seconds", "Import ETA Seconds").expect("failed to create metric");
+
+    /// Failed LogFiles of the current import session
+    pub static ref IMPORT_FAILURES: IntGauge =
+        IntGauge::new("import_failures", "Import Failures").expect("failed to create metric");
+
+    /// In progress LogFiles of the current import session
+    pub static ref IMPORT_IN_PROGRESS_FILES: IntGauge =
+        IntGauge::new("import_in_progress_files", "Import In Progress Files").expect("failed to create metric");
+
+    /// Pending LogFiles of the current import session
+    pub static ref IMPORT_PENDING_FILES: IntGauge =
+        IntGauge::new("import_pending_files", "Import Pending Files").expect("failed to create metric");
+
+    /// Latency EWMA in milliseconds of the api endpoints used by the requesters
+    pub static ref ENDPOINT_LATENCY_MS: GaugeVec =
+        GaugeVec::new(Opts::new("endpoint_latency_ms", "Endpoint Latency EWMA Milliseconds"), &["endpoint"])
+            .expect("failed to create metric");
+
+    /// Error rate EWMA of the api endpoints used by the requesters
+    pub static ref ENDPOINT_ERROR_RATE: GaugeVec =
+        GaugeVec::new(Opts::new("endpoint_error_rate", "Endpoint Error Rate EWMA"), &["endpoint"])
+            .expect("failed to create metric");
+
+    /// Circuit breaker state of the api endpoints (0 closed, 1 half open, 2 open)
+    pub static ref ENDPOINT_CIRCUIT_STATE: IntGaugeVec =
+        IntGaugeVec::new(Opts::new("endpoint_circuit_state", "Endpoint Circuit Breaker State"), &["endpoint"])
+            .expect("failed to create metric");
+
+    /// Sync status of the api endpoints as reported by their info (1 healthy, 0 unhealthy)
+    pub static ref ENDPOINT_HEALTHY: IntGaugeVec =
+        IntGaugeVec::new(Opts::new("endpoint_healthy", "Endpoint Healthy"), &["endpoint"])
+            .expect("failed to create metric");
+
+    /// Requests sent to the api endpoints, by outcome
+    pub static ref ENDPOINT_REQUESTS: IntCounterVec = IntCounterVec::new(
+        Opts::new("endpoint_requests", "Endpoint Requests"),
+        &["endpoint", "outcome"]
+    )
+    .expect("failed to create metric");
+
+    /// Messages received from the MQTT feeds
+    pub static ref MQTT_MESSAGES: IntCounterVec =
+        IntCounterVec::new(Opts::new("mqtt_messages", "MQTT Messages"), &["topic", "url"])
+            .expect("failed to create metric");
+
+    /// Messages per second received from the MQTT feeds
+    pub static ref MQTT_MESSAGE_RATE: GaugeVec =
+        GaugeVec::new(Opts::new("mqtt_message_rate", "MQTT Messages Per Second"), &["topic", "url"])
+            .expect("failed to create metric");
+
+    /// Unix timestamp of the last message received from the MQTT feeds
+    pub static ref MQTT_LAST_MESSAGE_TIMESTAMP: IntGaugeVec =
+        IntGaugeVec::new(Opts::new("mqtt_last_message_timestamp", "MQTT Last Message Timestamp"), &["topic", "url"])
+            .expect("failed to create metric");
+
+    /// Reconnects of the MQTT feeds
+    pub static ref MQTT_RECONNECTS: IntCounterVec =
+        IntCounterVec::new(Opts::new("mqtt_reconnects", "MQTT Reconnects"), &["topic", "url"])
+            .expect("failed to create metric");
+
+    /// Latest milestone index received from the MQTT feeds
+    pub static ref MQTT_LATEST_MILESTONE_INDEX: IntGaugeVec =
+        IntGaugeVec::new(Opts::new("mqtt_latest_milestone_index", "MQTT Latest Milestone Index"), &["url"])
+            .expect("failed to create metric");
+
+    /// Delay EWMA in milliseconds of the feeds behind the first delivery of the same message
+    pub static ref FEED_DELAY_MS: GaugeVec =
+        GaugeVec::new(Opts::new("feed_delay_ms", "Feed Delay EWMA Milliseconds"), &["topic", "url"])
+            .expect("failed to create metric");
+
+    /// Messages only delivered by a single feed, while other feeds of the same topic are active
+    pub static ref FEED_EXCLUSIVE_MESSAGES: IntCounterVec =
+        IntCounterVec::new(Opts::new("feed_exclusive_messages", "Feed Exclusive Messages"), &["topic", "url"])
+            .expect("failed to create metric");
+
+    /// Messages delivered by the other feeds of the same topic, but missed by the feed
+    pub static ref FEED_MISSED_MESSAGES: IntCounterVec =
+        IntCounterVec::new(Opts::new("feed_missed_messages", "Feed Missed Messages"), &["topic", "url"])
+            .expect("failed to create metric");
 }
@@ -168,3 +168,18 @@ impl Delete<ParentPK, ParentRecord> for ChronicleKeyspace {
             .value(&message_id.to_string())
     }
 }
+
+/// Delete Unreachable record from unreachable table
+impl Delete<(Synckey, MilestoneIndex), UnreachableRecord> for ChronicleKeyspace {
+    type QueryOrPrepared = PreparedStatement;
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!(
+            "DELETE FROM {}.unreachable WHERE key = ? AND milestone_index = ?",
+            self.name()
+        )
+        .into()
+    }
+    fn bind_values<T: Values>(builder: T, (_, milestone_index): &(Synckey, MilestoneIndex)) -> T::Return {
+        builder.value(&"permanode").value(&milestone_index.0)
+    }
+}
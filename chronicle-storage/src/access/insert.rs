@@ -291,6 +291,36 @@ impl Insert<Synckey, SyncRecord> for ChronicleKeyspace {
     }
 }
 
+impl Insert<Synckey, UnreachableRecord> for ChronicleKeyspace {
+    type QueryOrPrepared = PreparedStatement;
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!(
+            "INSERT INTO {}.unreachable (key, milestone_index, reason, attempts, last_attempt, next_attempt) VALUES (?, ?, ?, ?, ?, ?)",
+            self.name()
+        )
+        .into()
+    }
+    fn bind_values<T: Values>(
+        builder: T,
+        _: &Synckey,
+        UnreachableRecord {
+            milestone_index,
+            reason,
+            attempts,
+            last_attempt,
+            next_attempt,
+        }: &UnreachableRecord,
+    ) -> T::Return {
+        builder
+            .value(&"permanode")
+            .value(&milestone_index.0)
+            .value(reason)
+            .value(attempts)
+            .value(last_attempt)
+            .value(next_attempt)
+    }
+}
+
 impl Insert<Synckey, AnalyticRecord> for ChronicleKeyspace {
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
@@ -228,6 +228,86 @@ impl SyncRecord {
         }
     }
 }
+/// An 'unreachable' table row, which records a milestone that no peer was able to provide
+#[allow(missing_docs)]
+#[derive(Clone, Debug)]
+pub struct UnreachableRecord {
+    pub milestone_index: MilestoneIndex,
+    pub reason: String,
+    pub attempts: u32,
+    pub last_attempt: u64,
+    pub next_attempt: u64,
+}
+
+impl UnreachableRecord {
+    /// Creates a new unreachable row
+    pub fn new(
+        milestone_index: MilestoneIndex,
+        reason: String,
+        attempts: u32,
+        last_attempt: u64,
+        next_attempt: u64,
+    ) -> Self {
+        Self {
+            milestone_index,
+            reason,
+            attempts,
+            last_attempt,
+            next_attempt,
+        }
+    }
+}
+/// The primary key of a 'leases' table row, which covers the milestone range of the given bucket
+#[derive(Clone, Copy, Debug)]
+pub struct LeaseKey {
+    /// The milestone range bucket
+    pub bucket: u32,
+}
+
+/// A 'leases' table row, the instance token tells apart the chronicle instances which share the same owner id
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LeaseRecord {
+    pub owner: u8,
+    pub instance: u64,
+    pub expires_at: u64,
+}
+
+impl LeaseRecord {
+    /// Creates a new leases row
+    pub fn new(owner: u8, instance: u64, expires_at: u64) -> Self {
+        Self {
+            owner,
+            instance,
+            expires_at,
+        }
+    }
+}
+
+/// Claim a lease for a bucket, only if it was never claimed before
+#[derive(Clone, Copy, Debug)]
+pub struct LeaseClaim {
+    /// The milestone range bucket
+    pub bucket: u32,
+    /// The claimed lease
+    pub lease: LeaseRecord,
+}
+
+/// Swap the lease of a bucket, only if it's still the current one, used to renew and take over leases
+#[derive(Clone, Copy, Debug)]
+pub struct LeaseSwap {
+    /// The milestone range bucket
+    pub bucket: u32,
+    /// The expected current lease
+    pub current: LeaseRecord,
+    /// The new lease
+    pub new: LeaseRecord,
+}
+
+/// The outcome of a lightweight transaction
+#[derive(Clone, Copy, Debug)]
+pub struct Applied(pub bool);
+
 /// An `addresses` table row
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Debug)]
@@ -493,6 +493,117 @@ impl RowsDecoder<SyncRange, Iter<SyncRecord>> for ChronicleKeyspace {
     }
 }
 
+impl Select<SyncRange, Iter<UnreachableRecord>> for ChronicleKeyspace {
+    type QueryOrPrepared = QueryStatement;
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!(
+            "SELECT milestone_index, reason, attempts, last_attempt, next_attempt FROM {}.unreachable WHERE key = ? AND milestone_index >= ? AND milestone_index < ?",
+            self.name()
+        )
+        .into()
+    }
+    fn bind_values<T: Values>(builder: T, sync_range: &SyncRange) -> T::Return {
+        builder
+            .value(&"permanode")
+            .value(&sync_range.from)
+            .value(&sync_range.to)
+    }
+}
+
+impl RowsDecoder<SyncRange, Iter<UnreachableRecord>> for ChronicleKeyspace {
+    type Row = UnreachableRecord;
+    fn try_decode(decoder: Decoder) -> anyhow::Result<Option<Iter<UnreachableRecord>>> {
+        ensure!(decoder.is_rows()?, "Decoded response is not rows!");
+        let rows_iter = Self::Row::rows_iter(decoder)?;
+        if rows_iter.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(rows_iter))
+        }
+    }
+}
+
+impl Select<LeaseKey, LeaseRecord> for ChronicleKeyspace {
+    type QueryOrPrepared = PreparedStatement;
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!(
+            "SELECT owner, instance, expires_at FROM {}.leases WHERE key = ? AND bucket = ?",
+            self.name()
+        )
+        .into()
+    }
+    fn bind_values<T: Values>(builder: T, LeaseKey { bucket }: &LeaseKey) -> T::Return {
+        builder.value(&"permanode").value(bucket)
+    }
+}
+
+impl RowsDecoder<LeaseKey, LeaseRecord> for ChronicleKeyspace {
+    type Row = LeaseRecord;
+    fn try_decode(decoder: Decoder) -> anyhow::Result<Option<LeaseRecord>> {
+        ensure!(decoder.is_rows()?, "Decoded response is not rows!");
+        Ok(Self::Row::rows_iter(decoder)?.next())
+    }
+}
+
+// Lightweight transactions respond with an [applied] row, therefore they're sent through the Select interface
+
+impl Select<LeaseClaim, Applied> for ChronicleKeyspace {
+    type QueryOrPrepared = QueryStatement;
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!(
+            "INSERT INTO {}.leases (key, bucket, owner, instance, expires_at) VALUES (?, ?, ?, ?, ?) IF NOT EXISTS",
+            self.name()
+        )
+        .into()
+    }
+    fn bind_values<T: Values>(builder: T, LeaseClaim { bucket, lease }: &LeaseClaim) -> T::Return {
+        builder
+            .value(&"permanode")
+            .value(bucket)
+            .value(&lease.owner)
+            .value(&lease.instance)
+            .value(&lease.expires_at)
+    }
+}
+
+impl RowsDecoder<LeaseClaim, Applied> for ChronicleKeyspace {
+    type Row = Applied;
+    fn try_decode(decoder: Decoder) -> anyhow::Result<Option<Applied>> {
+        ensure!(decoder.is_rows()?, "Decoded response is not rows!");
+        Ok(Self::Row::rows_iter(decoder)?.next())
+    }
+}
+
+impl Select<LeaseSwap, Applied> for ChronicleKeyspace {
+    type QueryOrPrepared = QueryStatement;
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!(
+            "UPDATE {}.leases SET owner = ?, instance = ?, expires_at = ? WHERE key = ? AND bucket = ? IF owner = ? AND instance = ? AND expires_at = ?",
+            self.name()
+        )
+        .into()
+    }
+    fn bind_values<T: Values>(builder: T, LeaseSwap { bucket, current, new }: &LeaseSwap) -> T::Return {
+        builder
+            .value(&new.owner)
+            .value(&new.instance)
+            .value(&new.expires_at)
+            .value(&"permanode")
+            .value(bucket)
+            .value(&current.owner)
+            .value(&current.instance)
+            .value(&current.expires_at)
+    }
+}
+
+impl RowsDecoder<LeaseSwap, Applied> for ChronicleKeyspace {
+    type Row = Applied;
+    fn try_decode(decoder: Decoder) -> anyhow::Result<Option<Applied>> {
+        ensure!(decoder.is_rows()?, "Decoded response is not rows!");
+        Ok(Self::Row::rows_iter(decoder)?.next())
+    }
+}
+
 impl Select<SyncRange, Iter<AnalyticRecord>> for ChronicleKeyspace {
     type QueryOrPrepared = QueryStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
@@ -677,6 +788,39 @@ impl Row for SyncRecord {
     }
 }
 
+impl Row for UnreachableRecord {
+    fn try_decode_row<T: ColumnValue>(rows: &mut T) -> anyhow::Result<Self> {
+        let milestone_index = MilestoneIndex(rows.column_value::<u32>()?);
+        let reason = rows.column_value::<String>()?;
+        let attempts = rows.column_value::<u32>()?;
+        let last_attempt = rows.column_value::<u64>()?;
+        let next_attempt = rows.column_value::<u64>()?;
+        Ok(UnreachableRecord::new(
+            milestone_index,
+            reason,
+            attempts,
+            last_attempt,
+            next_attempt,
+        ))
+    }
+}
+
+impl Row for LeaseRecord {
+    fn try_decode_row<T: ColumnValue>(rows: &mut T) -> anyhow::Result<Self> {
+        let owner = rows.column_value::<u8>()?;
+        let instance = rows.column_value::<u64>()?;
+        let expires_at = rows.column_value::<u64>()?;
+        Ok(LeaseRecord::new(owner, instance, expires_at))
+    }
+}
+
+impl Row for Applied {
+    fn try_decode_row<T: ColumnValue>(rows: &mut T) -> anyhow::Result<Self> {
+        // the [applied] column, which might be followed by the current values if not applied
+        Ok(Applied(rows.column_value::<bool>()?))
+    }
+}
+
 impl Row for AnalyticRecord {
     fn try_decode_row<T: ColumnValue>(rows: &mut T) -> anyhow::Result<Self> {
         let milestone_index = MilestoneIndex(rows.column_value::<u32>()?);
@@ -63,6 +63,30 @@ impl ComputeToken<Synckey> for ChronicleKeyspace {
     }
 }
 
+impl ComputeToken<(Synckey, MilestoneIndex)> for ChronicleKeyspace {
+    fn token(_: &(Synckey, MilestoneIndex)) -> i64 {
+        "permanode".get_token()
+    }
+}
+
+impl ComputeToken<LeaseKey> for ChronicleKeyspace {
+    fn token(_: &LeaseKey) -> i64 {
+        "permanode".get_token()
+    }
+}
+
+impl ComputeToken<LeaseClaim> for ChronicleKeyspace {
+    fn token(_: &LeaseClaim) -> i64 {
+        "permanode".get_token()
+    }
+}
+
+impl ComputeToken<LeaseSwap> for ChronicleKeyspace {
+    fn token(_: &LeaseSwap) -> i64 {
+        "permanode".get_token()
+    }
+}
+
 impl ComputeToken<(TransactionId, Index)> for ChronicleKeyspace {
     fn token(key: &(TransactionId, Index)) -> i64 {
         key.0.to_string().chain_token(&key.1).finish()
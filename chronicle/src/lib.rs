@@ -18,7 +18,7 @@ impl<T: Serialize> SocketMsg<T> {
         match self {
             SocketMsg::General(_) => Err("No outgoing message for general commands".to_owned()),
             SocketMsg::API(_) => {
-                todo!()
+                Err("No outgoing message for api commands, use the admin routes of the api".to_owned())
             }
             SocketMsg::Broker(v) => {
                 serde_json::to_string(&chronicle_broker::BrokerSocketMsg::ChronicleBroker(v)).map_err(|e| e.to_string())
@@ -33,4 +33,18 @@ impl<T: Serialize> SocketMsg<T> {
 #[derive(Deserialize, Serialize, Clone)]
 pub enum ConfigCommand {
     Rollback,
+    /// Reload the config file, applying its live changes (reported as a `ConfigDiff`)
+    Reload,
+}
+
+/// A control message along with its request id, which gets acknowledged with a `ControlReply`
+#[derive(Deserialize, Serialize, Clone)]
+pub struct ControlRequest<T> {
+    /// The request id, chosen by the sender
+    pub id: u64,
+    /// The control message
+    pub message: SocketMsg<T>,
+    /// The number of seconds the sender waits for the reply, the relays wait as long for the broker or scylla
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
 }
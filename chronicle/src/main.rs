@@ -10,6 +10,7 @@ use chronicle_common::{
     config::*,
     get_config,
     get_config_async,
+    get_config_file,
     get_history_mut,
     metrics::*,
 };
@@ -69,6 +70,7 @@ fn main() {
     dotenv::dotenv().ok();
     env_logger::init();
     register_metrics();
+    set_overrides(config_overrides()).expect("Invalid config overrides");
     let config = get_config();
     let thread_count;
     match config.storage_config.thread_count {
@@ -88,12 +90,27 @@ fn main() {
         .build()
         .expect("Expected to build tokio runtime");
     let verified_config = runtime.block_on(config.clone().verify()).unwrap();
-    if verified_config != config {
+    // the verified adjustments are only saved without overrides, as the config file must not hold them
+    if verified_config != config && config == get_config_file() {
         get_history_mut().update(verified_config.into());
     }
     runtime.block_on(chronicle(apps));
 }
 
+/// The `--set path=value` config overrides of the command line
+fn config_overrides() -> Vec<String> {
+    let mut args = std::env::args().skip(1);
+    let mut overrides = Vec::new();
+    while let Some(arg) = args.next() {
+        if arg == "--set" {
+            overrides.extend(args.next());
+        } else if let Some(config_override) = arg.strip_prefix("--set=") {
+            overrides.push(config_override.to_owned());
+        }
+    }
+    overrides
+}
+
 async fn chronicle(apps: Apps) {
     apps.Scylla()
         .await
@@ -132,9 +149,105 @@ fn register_metrics() {
         .register(Box::new(RESPONSE_TIME_COLLECTOR.clone()))
         .expect("Could not register collector");
 
+    REGISTRY
+        .register(Box::new(API_THROTTLED_REQUESTS.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(API_CACHE_REQUESTS.clone()))
+        .expect("Could not register collector");
+
     REGISTRY
         .register(Box::new(CONFIRMATION_TIME_COLLECTOR.clone()))
         .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(IMPORT_MILESTONES.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(IMPORT_BYTES.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(IMPORT_TOTAL_BYTES.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(IMPORT_MILESTONES_PER_SECOND.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(IMPORT_BYTES_PER_SECOND.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(IMPORT_ETA_SECONDS.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(IMPORT_FAILURES.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(IMPORT_IN_PROGRESS_FILES.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(IMPORT_PENDING_FILES.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(ENDPOINT_LATENCY_MS.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(ENDPOINT_ERROR_RATE.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(ENDPOINT_CIRCUIT_STATE.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(ENDPOINT_HEALTHY.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(ENDPOINT_REQUESTS.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(MQTT_MESSAGES.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(MQTT_MESSAGE_RATE.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(MQTT_LAST_MESSAGE_TIMESTAMP.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(MQTT_RECONNECTS.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(MQTT_LATEST_MILESTONE_INDEX.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(FEED_DELAY_MS.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(FEED_EXCLUSIVE_MESSAGES.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(FEED_MISSED_MESSAGES.clone()))
+        .expect("Could not register collector");
 }
 
 async fn init_database() -> anyhow::Result<()> {
@@ -244,6 +357,25 @@ async fn init_database() -> anyhow::Result<()> {
                 logged_by tinyint,
                 PRIMARY KEY (key, milestone_index)
             ) WITH CLUSTERING ORDER BY (milestone_index DESC);
+
+            CREATE TABLE IF NOT EXISTS {0}.unreachable  (
+                key text,
+                milestone_index int,
+                reason text,
+                attempts int,
+                last_attempt bigint,
+                next_attempt bigint,
+                PRIMARY KEY (key, milestone_index)
+            ) WITH CLUSTERING ORDER BY (milestone_index DESC);
+
+            CREATE TABLE IF NOT EXISTS {0}.leases  (
+                key text,
+                bucket int,
+                owner tinyint,
+                instance bigint,
+                expires_at bigint,
+                PRIMARY KEY (key, bucket)
+            );
             
             CREATE TABLE IF NOT EXISTS {0}.analytics (
                 key text,
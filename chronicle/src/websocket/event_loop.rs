@@ -3,16 +3,37 @@
 use super::*;
 use chronicle::{
     ConfigCommand,
+    ControlRequest,
     SocketMsg,
 };
+use chronicle_broker::{
+    control::relay_to_broker,
+    BrokerSocketMsg,
+    BrokerTopology,
+    ChronicleBrokerThrough,
+    ControlReply,
+    RequesterTopology,
+};
+use chronicle_common::config::{
+    Config,
+    ConfigChange,
+    MqttType,
+};
 use futures::{
     stream::SplitSink,
     SinkExt,
     StreamExt,
 };
-use log::error;
+use log::{
+    error,
+    info,
+};
 use scylla_rs::prelude::Topology;
 use serde_json::Value;
+use std::{
+    net::SocketAddr,
+    time::Duration,
+};
 use tokio_tungstenite::connect_async;
 use url::Url;
 use warp::{
@@ -73,8 +94,18 @@ async fn manage_connection(ws: WebSocket) {
     }
 }
 
+/// The duration to wait for the broker or scylla to acknowledge a request, unless the request carries its own
+const DEFAULT_REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+
 async fn handle_message(msg: Message, tx: &mut SplitSink<WebSocket, Message>) -> anyhow::Result<()> {
     if let Ok(txt) = msg.to_str() {
+        if let Ok(request) = serde_json::from_str::<ControlRequest<Value>>(txt) {
+            let timeout = request
+                .timeout_secs
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_REPLY_TIMEOUT);
+            return handle_request(request.id, request.message, timeout, tx).await;
+        }
         if let Ok(target) = serde_json::from_str::<SocketMsg<Value>>(txt) {
             if let SocketMsg::General(v) = target.clone() {
                 if let Ok(command) = serde_json::from_value::<ConfigCommand>(v) {
@@ -83,6 +114,12 @@ async fn handle_message(msg: Message, tx: &mut SplitSink<WebSocket, Message>) ->
                             get_history_mut_async().await.rollback();
                             return Ok(());
                         }
+                        ConfigCommand::Reload => {
+                            if let Err(e) = reload_config(DEFAULT_REPLY_TIMEOUT, tx).await {
+                                error!("Unable to reload the config: {}", e);
+                            }
+                            return Ok(());
+                        }
                     }
                 }
             }
@@ -93,8 +130,7 @@ async fn handle_message(msg: Message, tx: &mut SplitSink<WebSocket, Message>) ->
                     if let Ok((mut stream, _)) = connect_async(Url::parse(&format!(
                         "ws://{}/",
                         match target {
-                            SocketMsg::General(_) => todo!(),
-                            SocketMsg::API(_) => todo!(),
+                            SocketMsg::General(_) | SocketMsg::API(_) => unreachable!("No outgoing message"),
                             SocketMsg::Broker(_) => config.broker_config.websocket_address,
                             SocketMsg::Scylla(_) => config.storage_config.listen_address,
                         }
@@ -105,27 +141,7 @@ async fn handle_message(msg: Message, tx: &mut SplitSink<WebSocket, Message>) ->
                         // Pass along the message we received
                         stream.send(message).await?;
                         if let SocketMsg::Scylla(v) = target {
-                            if let Ok(through) = serde_json::value::from_value::<ScyllaThrough>(v) {
-                                match through {
-                                    ScyllaThrough::Topology(topo) => {
-                                        let mut config = get_config_async().await;
-                                        match topo {
-                                            Topology::AddNode(address) => {
-                                                if config.storage_config.nodes.insert(address) {
-                                                    get_history_mut_async().await.update(config.into());
-                                                }
-                                            }
-                                            Topology::RemoveNode(address) => {
-                                                if config.storage_config.nodes.remove(&address) {
-                                                    get_history_mut_async().await.update(config.into());
-                                                }
-                                            }
-                                            Topology::BuildRing(_) => (),
-                                        }
-                                    }
-                                    ScyllaThrough::Shutdown => (),
-                                }
-                            }
+                            update_nodes(v).await;
                         }
                         // Get the responses
                         while let Some(res) = stream.next().await {
@@ -162,3 +178,241 @@ async fn handle_message(msg: Message, tx: &mut SplitSink<WebSocket, Message>) ->
     }
     Ok(())
 }
+
+/// Update the configured nodes with the scylla topology change
+async fn update_nodes(through: Value) {
+    if let Ok(ScyllaThrough::Topology(topo)) = serde_json::value::from_value::<ScyllaThrough>(through) {
+        let mut config = get_config_file_async().await;
+        match topo {
+            Topology::AddNode(address) => {
+                if config.storage_config.nodes.insert(address) {
+                    get_history_mut_async().await.update(config.into());
+                }
+            }
+            Topology::RemoveNode(address) => {
+                if config.storage_config.nodes.remove(&address) {
+                    get_history_mut_async().await.update(config.into());
+                }
+            }
+            Topology::BuildRing(_) => (),
+        }
+    }
+}
+
+/// Acknowledge the control request to the peer
+async fn send_reply(tx: &mut SplitSink<WebSocket, Message>, id: u64, result: Result<(), String>) -> anyhow::Result<()> {
+    let reply = ControlReply { id, result };
+    tx.send(warp::ws::Message::text(serde_json::to_string(&reply)?)).await?;
+    Ok(())
+}
+
+/// Handle the control request, and acknowledge it once the broker or scylla did, within the timeout
+async fn handle_request(
+    id: u64,
+    message: SocketMsg<Value>,
+    timeout: Duration,
+    tx: &mut SplitSink<WebSocket, Message>,
+) -> anyhow::Result<()> {
+    let config = get_config_async().await;
+    match message {
+        SocketMsg::General(v) => {
+            let result = match serde_json::from_value::<ConfigCommand>(v) {
+                Ok(ConfigCommand::Rollback) => get_history_mut_async()
+                    .await
+                    .rollback()
+                    .map(|_| ())
+                    .ok_or_else(|| "There is no config to roll back".to_owned()),
+                Ok(ConfigCommand::Reload) => reload_config(timeout, tx).await,
+                Err(e) => Err(e.to_string()),
+            };
+            send_reply(tx, id, result).await
+        }
+        SocketMsg::API(_) => send_reply(tx, id, message.to_outgoing().map(|_| ())).await,
+        SocketMsg::Broker(command) => {
+            // the imports and exports report their progress once acknowledged
+            let follow = matches!(
+                command.pointer("/Topology").and_then(|topology| topology.as_object()),
+                Some(topology) if ["Import", "ImportRemote", "Export"].iter().any(|k| topology.contains_key(*k))
+            );
+            let outgoing = serde_json::json!({ "ChronicleBroker": { "Request": { "id": id, "command": command } } });
+            relay_request(
+                config.broker_config.websocket_address,
+                outgoing.to_string(),
+                tx,
+                id,
+                timeout,
+                follow,
+                |reply| match serde_json::from_value::<BrokerSocketMsg<ControlReply>>(reply.clone()) {
+                    Ok(BrokerSocketMsg::ChronicleBroker(reply)) if reply.id == id => Some(reply.result),
+                    _ => None,
+                },
+            )
+            .await
+            .map(|_| ())
+        }
+        SocketMsg::Scylla(ref through) => {
+            let outgoing = match message.to_outgoing() {
+                Ok(outgoing) => outgoing,
+                Err(e) => return send_reply(tx, id, Err(e)).await,
+            };
+            let through = through.clone();
+            // scylla doesn't support request ids, so its next topology reply is the one
+            let applied = relay_request(
+                config.storage_config.listen_address,
+                outgoing,
+                tx,
+                id,
+                timeout,
+                false,
+                |reply| {
+                    let reply = reply.get("Scylla")?;
+                    if reply.get("Ok").is_some() {
+                        Some(Ok(()))
+                    } else {
+                        reply
+                            .get("Err")
+                            .map(|topology| Err(format!("Scylla rejected the topology change: {}", topology)))
+                    }
+                },
+            )
+            .await?;
+            if applied {
+                update_nodes(through).await;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Send the message to the websocket of the app, wait for the reply matched by `reply` and acknowledge the
+/// request with it. The other messages sent by the app are passed along to the peer, and keep being passed along
+/// after a successful reply if `follow` is set, until either side closes. Returns whether the request succeeded.
+async fn relay_request<F>(
+    address: SocketAddr,
+    outgoing: String,
+    tx: &mut SplitSink<WebSocket, Message>,
+    id: u64,
+    timeout: Duration,
+    follow: bool,
+    reply: F,
+) -> anyhow::Result<bool>
+where
+    F: Fn(&Value) -> Option<Result<(), String>>,
+{
+    let url = Url::parse(&format!("ws://{}/", address))?;
+    let mut stream = match connect_async(url).await {
+        Ok((stream, _)) => stream,
+        Err(e) => {
+            send_reply(tx, id, Err(format!("Unable to connect to {}: {}", address, e))).await?;
+            return Ok(false);
+        }
+    };
+    if let Err(e) = stream
+        .send(tokio_tungstenite::tungstenite::Message::text(outgoing))
+        .await
+    {
+        send_reply(tx, id, Err(e.to_string())).await?;
+        return Ok(false);
+    }
+    let result = tokio::time::timeout(timeout, async {
+        while let Some(Ok(msg)) = stream.next().await {
+            if let tokio_tungstenite::tungstenite::Message::Text(s) = msg {
+                match serde_json::from_str::<Value>(&s).ok().as_ref().and_then(&reply) {
+                    Some(result) => return Ok(result),
+                    None => tx.send(warp::ws::Message::text(s)).await?,
+                }
+            }
+        }
+        Ok::<_, warp::Error>(Err(format!("The connection to {} got closed without a reply", address)))
+    })
+    .await
+    .unwrap_or_else(|_| Ok(Err(format!("No reply from {} within {:?}", address, timeout))))?;
+    let succeeded = result.is_ok();
+    send_reply(tx, id, result).await?;
+    if follow && succeeded {
+        while let Some(Ok(msg)) = stream.next().await {
+            if let tokio_tungstenite::tungstenite::Message::Text(s) = msg {
+                tx.send(warp::ws::Message::text(s)).await?;
+            }
+        }
+    }
+    stream.close(None).await.ok();
+    Ok(succeeded)
+}
+
+/// Reload the config file and apply its live changes through the broker topology. The diff against the running
+/// config is reported to the peer, along with the changed fields which need a restart.
+async fn reload_config(timeout: Duration, tx: &mut SplitSink<WebSocket, Message>) -> Result<(), String> {
+    let reloaded = Config::load_file(None).map_err(|e| e.to_string())?;
+    let effective = reloaded
+        .clone()
+        .with_overrides()
+        .map_err(|e| e.to_string())?
+        .verify()
+        .await
+        .map_err(|e| e.to_string())?;
+    let running = get_config_async().await;
+    let previous = get_config_file_async().await;
+    let diff = running.diff(&effective);
+    let mut recorded = reloaded;
+    let mut failures = Vec::new();
+    for change in diff.hot.iter() {
+        if let Some(topology) = broker_topology(change) {
+            if let Err(e) = apply_broker_topology(running.broker_config.websocket_address, topology, timeout).await {
+                failures.push(format!("{:?}: {}", change, e));
+                // the history must keep describing the running config
+                recorded.revert(change, &previous);
+            }
+        }
+    }
+    // record the reloaded config file once applied, the broker might have persisted the same changes already
+    if recorded != get_config_file_async().await {
+        get_history_mut_async().await.update(recorded);
+    }
+    if !diff.restart.is_empty() {
+        info!(
+            "The reloaded config changes need a restart: {}",
+            diff.restart.join(", ")
+        );
+    }
+    let report = serde_json::to_string(&SocketMsg::General(&diff)).map_err(|e| e.to_string())?;
+    tx.send(warp::ws::Message::text(report)).await.ok();
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Unable to apply: {}", failures.join(", ")))
+    }
+}
+
+/// The broker topology change which applies the config change, if any
+fn broker_topology(change: &ConfigChange) -> Option<BrokerTopology> {
+    Some(match change.clone() {
+        ConfigChange::AddMqttBroker(MqttType::Messages, url) => BrokerTopology::AddMqttMessages(url),
+        ConfigChange::AddMqttBroker(MqttType::MessagesReferenced, url) => {
+            BrokerTopology::AddMqttMessagesReferenced(url)
+        }
+        ConfigChange::AddMqttBroker(MqttType::LatestMilestone, url) => BrokerTopology::AddMqttLatestMilestone(url),
+        ConfigChange::RemoveMqttBroker(MqttType::Messages, url) => BrokerTopology::RemoveMqttMessages(url),
+        ConfigChange::RemoveMqttBroker(MqttType::MessagesReferenced, url) => {
+            BrokerTopology::RemoveMqttMessagesReferenced(url)
+        }
+        ConfigChange::RemoveMqttBroker(MqttType::LatestMilestone, url) => {
+            BrokerTopology::RemoveMqttLatestMilestone(url)
+        }
+        ConfigChange::AddEventStream(url) => BrokerTopology::AddEventStream(url),
+        ConfigChange::RemoveEventStream(url) => BrokerTopology::RemoveEventStream(url),
+        ConfigChange::AddApiEndpoint(url) => BrokerTopology::Requesters(RequesterTopology::AddEndpoint(url)),
+        ConfigChange::RemoveApiEndpoint(url) => BrokerTopology::Requesters(RequesterTopology::RemoveEndpoint(url)),
+        ConfigChange::SyncRange(sync_range) => BrokerTopology::UpdateSyncRange(sync_range.from..sync_range.to),
+        // the alerts read the latest config
+        ConfigChange::AlertRequests => return None,
+    })
+}
+
+/// Send the topology change to the broker, and wait for it to acknowledge it
+async fn apply_broker_topology(address: SocketAddr, topology: BrokerTopology, timeout: Duration) -> Result<(), String> {
+    let request = ChronicleBrokerThrough::Topology(topology).into_request();
+    relay_to_broker(address, &[request], timeout)
+        .await
+        .map_err(|e| e.to_string())?
+}
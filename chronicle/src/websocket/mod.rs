@@ -4,6 +4,7 @@ use async_trait::async_trait;
 use backstage::*;
 use chronicle_common::{
     get_config_async,
+    get_config_file_async,
     get_history_mut,
     get_history_mut_async,
     Persist,